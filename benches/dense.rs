@@ -0,0 +1,113 @@
+//! Benchmarks of the solver on systems whose rows hold many cells.
+//!
+//! Each variable takes part in several overlapping sums, so the rows of the tableau become
+//! dense and the cost of the row arithmetic (insertion of rows, substitution) dominates,
+//! unlike in the enaml benchmark where rows stay short.
+//!
+//! Compare the results across changes using criterion baselines, for example:
+//!
+//!     cargo bench --bench dense -- --save-baseline before
+//!     cargo bench --bench dense -- --baseline before
+//!
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kiwi_rs::strength::{MEDIUM, REQUIRED, WEAK};
+use kiwi_rs::{Constraint, Expression, RelationalOperator, Solver, Term, Variable};
+
+/// Number of variables of the benchmarked systems.
+const SIZES: [usize; 2] = [40, 80];
+
+/// Number of variables in each sum.
+const WINDOW: usize = 10;
+
+/// Build a system of overlapping sums over size variables.
+fn system(size: usize) -> (Vec<Variable>, Vec<Constraint>) {
+    let variables: Vec<Variable> = (0..size)
+        .map(|i| Variable::new_with_name(&format!("x{}", i)))
+        .collect();
+    let sum = |start: usize, constant: f64| {
+        let terms = (start..start + WINDOW)
+            .map(|i| Term::new(variables[i % size].clone(), 1.0))
+            .collect();
+        Expression::new(terms, constant)
+    };
+
+    let mut constraints = Vec::new();
+    for (i, variable) in variables.iter().enumerate() {
+        constraints.push(Constraint::new(
+            variable - 0.0,
+            RelationalOperator::GreaterEqual,
+            REQUIRED,
+        ));
+        constraints.push(Constraint::new(
+            variable - (i % 7) as f64 * 10.0,
+            RelationalOperator::Equal,
+            WEAK,
+        ));
+    }
+    for start in (0..size).step_by(2) {
+        constraints.push(Constraint::new(
+            sum(start, -200.0),
+            RelationalOperator::LessEqual,
+            REQUIRED,
+        ));
+        constraints.push(Constraint::new(
+            sum(start + 1, -150.0),
+            RelationalOperator::Equal,
+            MEDIUM,
+        ));
+    }
+    (variables, constraints)
+}
+
+fn bench_add_constraints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense_add_constraints");
+    group.sample_size(10);
+    for size in SIZES.iter() {
+        let (_, constraints) = system(*size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &constraints,
+            |b, constraints| {
+                b.iter(|| {
+                    let mut solver = Solver::new();
+                    for constraint in constraints.iter() {
+                        solver.add_constraint(constraint.clone()).unwrap();
+                    }
+                    solver
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_suggest_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense_suggest_and_update");
+    for size in SIZES.iter() {
+        let (variables, constraints) = system(*size);
+        let mut solver = Solver::new();
+        for constraint in constraints.iter() {
+            solver.add_constraint(constraint.clone()).unwrap();
+        }
+        let edited = &variables[..4];
+        for variable in edited.iter() {
+            solver.add_edit_variable(variable, MEDIUM * 10.0).unwrap();
+        }
+        let mut step = 0;
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                step += 1;
+                for (i, variable) in edited.iter().enumerate() {
+                    let value = ((step + i) % 20) as f64 * 5.0;
+                    solver.suggest_value(variable, value).unwrap();
+                }
+                solver.update_variable();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add_constraints, bench_suggest_value);
+criterion_main!(benches);
@@ -0,0 +1,226 @@
+//! Benchmarks of the solver on layouts similar to the ones produced by enaml.
+//!
+//! Modeled after the enaml benchmark of kiwi: a grid of widgets with required ordering and
+//! size constraints, strong equal size constraints between siblings and weak preferred
+//! sizes, placed in a container whose size is driven by edit variables.
+//!
+//! The workloads are deterministic so results can be compared across changes using criterion
+//! baselines, for example:
+//!
+//!     cargo bench --bench enaml -- --save-baseline before
+//!     cargo bench --bench enaml -- --baseline before
+//!
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use kiwi_rs::strength::{MEDIUM, REQUIRED, STRONG, WEAK};
+use kiwi_rs::{Constraint, RelationalOperator, Solver, Variable};
+
+/// Sizes (number of rows and columns) of the benchmarked grids.
+///
+/// A grid of size n uses about 9 * n * n constraints.
+const SIZES: [usize; 2] = [5, 10];
+
+/// Number of suggestions made during a benchmarked drag of the container.
+const DRAG_STEPS: usize = 100;
+
+/// Geometry of a widget.
+struct Widget {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable,
+}
+
+impl Widget {
+    fn new(row: usize, column: usize) -> Widget {
+        Widget {
+            left: Variable::new_with_name(&format!("left_{}_{}", row, column)),
+            top: Variable::new_with_name(&format!("top_{}_{}", row, column)),
+            width: Variable::new_with_name(&format!("width_{}_{}", row, column)),
+            height: Variable::new_with_name(&format!("height_{}_{}", row, column)),
+        }
+    }
+}
+
+/// Layout of a grid of widgets in a container.
+struct Grid {
+    width: Variable,
+    height: Variable,
+    constraints: Vec<Constraint>,
+}
+
+/// Build the constraints of a grid of size x size widgets.
+fn grid(size: usize) -> Grid {
+    let width = Variable::new_with_name("container_width");
+    let height = Variable::new_with_name("container_height");
+    let widgets: Vec<Vec<Widget>> = (0..size)
+        .map(|row| (0..size).map(|column| Widget::new(row, column)).collect())
+        .collect();
+    let spacing = 10.0;
+    let margin = 5.0;
+
+    let mut constraints = Vec::new();
+    let mut push = |expression, op, strength| {
+        constraints.push(Constraint::new(expression, op, strength));
+    };
+    for (row, line) in widgets.iter().enumerate() {
+        for (column, widget) in line.iter().enumerate() {
+            // Sizes are positive and prefer a fixed size hint.
+            push(
+                &widget.width - 0.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            );
+            push(
+                &widget.height - 0.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            );
+            push(&widget.width - 80.0, RelationalOperator::Equal, WEAK);
+            push(&widget.height - 25.0, RelationalOperator::Equal, WEAK);
+            push(
+                &widget.width - 40.0,
+                RelationalOperator::GreaterEqual,
+                MEDIUM,
+            );
+
+            // Widgets are placed left to right and top to bottom.
+            if column == 0 {
+                push(&widget.left - margin, RelationalOperator::Equal, REQUIRED);
+            } else {
+                let previous = &line[column - 1];
+                push(
+                    &previous.left + &previous.width + spacing - &widget.left,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                );
+                push(
+                    &widget.width - &previous.width,
+                    RelationalOperator::Equal,
+                    STRONG,
+                );
+            }
+            if row == 0 {
+                push(&widget.top - margin, RelationalOperator::Equal, REQUIRED);
+            } else {
+                let above = &widgets[row - 1][column];
+                push(
+                    &above.top + &above.height + spacing - &widget.top,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                );
+                push(
+                    &widget.left - &above.left,
+                    RelationalOperator::Equal,
+                    STRONG,
+                );
+            }
+
+            // The last widgets stay in the container.
+            if column + 1 == size {
+                push(
+                    &widget.left + &widget.width + margin - &width,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                );
+            }
+            if row + 1 == size {
+                push(
+                    &widget.top + &widget.height + margin - &height,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                );
+            }
+        }
+    }
+    Grid {
+        width,
+        height,
+        constraints,
+    }
+}
+
+/// Create a solver containing all the constraints of a grid.
+fn solver(grid: &Grid) -> Solver {
+    let mut solver = Solver::new();
+    for constraint in grid.constraints.iter() {
+        solver.add_constraint(constraint.clone()).unwrap();
+    }
+    solver
+}
+
+fn bench_add_constraints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_constraints");
+    group.sample_size(10);
+    for size in SIZES.iter() {
+        let grid = grid(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            b.iter(|| solver(grid))
+        });
+    }
+    group.finish();
+}
+
+fn bench_remove_constraints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("remove_half_constraints");
+    group.sample_size(10);
+    for size in SIZES.iter() {
+        let grid = grid(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            b.iter_batched(
+                || solver(grid),
+                |mut solver| {
+                    for constraint in grid.constraints.iter().step_by(2) {
+                        solver.remove_constraint(constraint).unwrap();
+                    }
+                    solver
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_suggest_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("suggest_and_update");
+    group.sample_size(10);
+    for size in SIZES.iter() {
+        let grid = grid(*size);
+        // Resize the container from a compressed to a relaxed layout and back, as during a drag.
+        let suggestions: Vec<(f64, f64)> = (0..DRAG_STEPS)
+            .map(|step| {
+                let offset = step.min(DRAG_STEPS - step) as f64 * 10.0 * *size as f64;
+                (200.0 + offset, 100.0 + offset)
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &grid, |b, grid| {
+            b.iter_batched(
+                || {
+                    let mut solver = solver(grid);
+                    solver.add_edit_variable(&grid.width, STRONG).unwrap();
+                    solver.add_edit_variable(&grid.height, STRONG).unwrap();
+                    solver
+                },
+                |mut solver| {
+                    for &(width, height) in suggestions.iter() {
+                        solver.suggest_value(&grid.width, width).unwrap();
+                        solver.suggest_value(&grid.height, height).unwrap();
+                        solver.update_variable();
+                    }
+                    solver
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_add_constraints,
+    bench_remove_constraints,
+    bench_suggest_value
+);
+criterion_main!(benches);
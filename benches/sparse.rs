@@ -0,0 +1,106 @@
+//! Benchmarks of the solver on large systems whose rows hold few cells.
+//!
+//! The system is made of many small independent blocks, as in a long document whose
+//! paragraphs are laid out independently. Each symbol appears in a handful of rows, so the
+//! cost of a pivot should depend on the size of the block and not on the number of rows.
+//!
+//! Compare the results across changes using criterion baselines, for example:
+//!
+//!     cargo bench --bench sparse -- --save-baseline before
+//!     cargo bench --bench sparse -- --baseline before
+//!
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kiwi_rs::strength::{REQUIRED, STRONG, WEAK};
+use kiwi_rs::{Constraint, RelationalOperator, Solver, Variable};
+
+/// Number of variables of the benchmarked systems.
+const SIZES: [usize; 2] = [1000, 5000];
+
+/// Number of variables in each block.
+const BLOCK: usize = 5;
+
+/// Number of blocks whose last variable is edited.
+const EDITED: usize = 20;
+
+/// Build a system of independent blocks of ordered variables over size variables.
+fn system(size: usize) -> (Vec<Vec<Variable>>, Vec<Constraint>) {
+    let mut blocks = Vec::new();
+    let mut constraints = Vec::new();
+    for b in 0..size / BLOCK {
+        let block: Vec<Variable> = (0..BLOCK)
+            .map(|i| Variable::new_with_name(&format!("x{}_{}", b, i)))
+            .collect();
+        constraints.push(Constraint::new(
+            &block[0] - 0.0,
+            RelationalOperator::GreaterEqual,
+            REQUIRED,
+        ));
+        for i in 1..BLOCK {
+            constraints.push(Constraint::new(
+                &block[i] - &block[i - 1] - 10.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ));
+            constraints.push(Constraint::new(
+                &block[i] - &block[i - 1] - (20 + b % 7) as f64,
+                RelationalOperator::Equal,
+                WEAK,
+            ));
+        }
+        blocks.push(block);
+    }
+    (blocks, constraints)
+}
+
+fn bench_add_constraints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_add_constraints");
+    group.sample_size(10);
+    for size in SIZES.iter() {
+        let (_, constraints) = system(*size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &constraints,
+            |b, constraints| {
+                b.iter(|| {
+                    let mut solver = Solver::new();
+                    for constraint in constraints.iter() {
+                        solver.add_constraint(constraint.clone()).unwrap();
+                    }
+                    solver
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_suggest_value(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_suggest_and_update");
+    for size in SIZES.iter() {
+        let (blocks, constraints) = system(*size);
+        let mut solver = Solver::new();
+        for constraint in constraints.iter() {
+            solver.add_constraint(constraint.clone()).unwrap();
+        }
+        let edited: Vec<&Variable> = blocks.iter().take(EDITED).map(|b| &b[BLOCK - 1]).collect();
+        for variable in edited.iter() {
+            solver.add_edit_variable(variable, STRONG).unwrap();
+        }
+        let mut step = 0;
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| {
+                step += 1;
+                for (i, variable) in edited.iter().enumerate() {
+                    let value = 100.0 + ((step + i) % 10) as f64 * 10.0;
+                    solver.suggest_value(variable, value).unwrap();
+                }
+                solver.update_variable();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add_constraints, bench_suggest_value);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+//! Build script generating the C header of the capi feature.
+//!
+//! The header is written to OUT_DIR/include/kiwi.h, build scripts must not modify the source
+//! tree. The copy committed in include/kiwi.h is checked against it, and updated on request,
+//! by the capi integration test.
+//!
+//! cbindgen does not evaluate constants defined from other constants, so the strength macros
+//! are written from the strength module of the crate, included here, rather than duplicated as
+//! literals in src/capi.rs.
+
+#[cfg(feature = "capi")]
+#[allow(dead_code)]
+#[path = "src/strength.rs"]
+mod strength;
+
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=src/strength.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let include_dir =
+            std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("include");
+        std::fs::create_dir_all(&include_dir).expect("Unable to create the include directory");
+        let strengths = [
+            ("REQUIRED", "absolutely required", strength::REQUIRED),
+            ("STRONG", "strong", strength::STRONG),
+            ("MEDIUM", "medium", strength::MEDIUM),
+            ("WEAK", "weak", strength::WEAK),
+        ];
+        let defines: Vec<String> = strengths
+            .iter()
+            .map(|(name, description, value)| {
+                format!(
+                    "\n/*\n Strength used for {} constraints.\n */\n#define KIWI_STRENGTH_{} {:?}",
+                    description, name, value
+                )
+            })
+            .collect();
+        cbindgen::Builder::new()
+            .with_src("src/capi.rs")
+            .with_config(cbindgen::Config::from_file("cbindgen.toml").unwrap())
+            .with_after_include(defines.join("\n"))
+            .generate()
+            .expect("Unable to generate the C header")
+            .write_to_file(include_dir.join("kiwi.h"));
+    }
+}
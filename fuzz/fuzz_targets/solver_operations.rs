@@ -0,0 +1,221 @@
+//! Fuzz the solver with arbitrary sequences of operations.
+//!
+//! The solver must never panic, every operation must return the error expected from the
+//! sequence of operations so far, or succeed, and the required constraints must hold after every
+//! successful operation. Run with:
+//!
+//!     cargo +nightly fuzz run solver_operations
+//!
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use kiwi_rs::strength::{create, MEDIUM, REQUIRED, STRONG, WEAK};
+use kiwi_rs::{Constraint, Expression, KiwiError, RelationalOperator, Solver, Term, Variable};
+use libfuzzer_sys::fuzz_target;
+
+/// Maximal number of variables created by a single run.
+const MAX_VARIABLES: usize = 16;
+
+/// Maximal number of terms of a constraint.
+///
+/// Layout constraints relate a few variables. Long random combinations quickly make badly
+/// conditioned systems, on which the rounding errors of the pivots exceed the tolerances.
+const MAX_TERMS: usize = 4;
+
+/// Largest magnitude of the solution for which the required constraints are checked.
+///
+/// The solver prunes coefficients using absolute tolerances, so the rounding errors grow with
+/// the magnitude of the solution and accumulate in the tableau. Badly conditioned systems
+/// reaching larger values are outside of the range the solver is designed for.
+const MAX_MAGNITUDE: f64 = 1e6;
+
+#[derive(Arbitrary, Debug)]
+enum Operation {
+    CreateVariable,
+    AddConstraint {
+        terms: Vec<(u8, i8)>,
+        constant: i16,
+        op: u8,
+        strength: u8,
+    },
+    RemoveConstraint(u8),
+    AddEditVariable {
+        variable: u8,
+        strength: u8,
+    },
+    RemoveEditVariable(u8),
+    SuggestValue {
+        variable: u8,
+        value: i16,
+    },
+    UpdateVariables,
+}
+
+fn strength(index: u8) -> f64 {
+    match index % 6 {
+        0 => WEAK,
+        1 => MEDIUM,
+        2 => STRONG,
+        3 => create(0.0, 1.0, 1.0, 1.0),
+        4 => create(1.0, 1.0, 1.0, 1.0),
+        _ => REQUIRED,
+    }
+}
+
+fn operator(index: u8) -> RelationalOperator {
+    match index % 3 {
+        0 => RelationalOperator::LessEqual,
+        1 => RelationalOperator::Equal,
+        _ => RelationalOperator::GreaterEqual,
+    }
+}
+
+/// Check that all the required constraints are satisfied by the current values.
+fn check_required(constraints: &[Constraint], magnitude: f64) {
+    for constraint in constraints.iter().filter(|c| c.strength() >= REQUIRED) {
+        let expression = constraint.expression();
+        let value = expression.value();
+        let scale = expression
+            .terms()
+            .iter()
+            .map(|term| term.coefficient().abs() * magnitude)
+            .fold(1.0 + expression.constant().abs(), |acc, v| acc + v);
+        let tolerance = 1e-6 * scale;
+        let satisfied = match constraint.op() {
+            RelationalOperator::Equal => value.abs() <= tolerance,
+            RelationalOperator::LessEqual => value <= tolerance,
+            RelationalOperator::GreaterEqual => value >= -tolerance,
+        };
+        assert!(
+            satisfied,
+            "required constraint {} violated by {}",
+            constraint, value
+        );
+    }
+}
+
+/// Unwrap the result of an operation which must succeed.
+fn expect_ok(result: Result<(), KiwiError>, operation: &str) {
+    if let Err(error) = result {
+        panic!("{} failed: {}", operation, error);
+    }
+}
+
+fuzz_target!(|operations: Vec<Operation>| {
+    let mut solver = Solver::new();
+    let mut variables = vec![Variable::new_with_name("x0")];
+    // Whether each variable is an edit variable of the solver.
+    let mut edits = vec![false];
+    let mut constraints: Vec<Constraint> = Vec::new();
+
+    for operation in operations {
+        let index = |v: u8| v as usize % variables.len();
+        match operation {
+            Operation::CreateVariable => {
+                if variables.len() < MAX_VARIABLES {
+                    let name = format!("x{}", variables.len());
+                    variables.push(Variable::new_with_name(&name));
+                    edits.push(false);
+                }
+                continue;
+            }
+            Operation::AddConstraint {
+                terms,
+                constant,
+                op,
+                strength: s,
+            } => {
+                let terms = terms
+                    .iter()
+                    .take(MAX_TERMS)
+                    .map(|&(v, coefficient)| {
+                        Term::new(variables[index(v)].clone(), coefficient as f64 / 4.0)
+                    })
+                    .collect();
+                let constraint = Constraint::new(
+                    Expression::new(terms, constant as f64),
+                    operator(op),
+                    strength(s),
+                );
+                match solver.add_constraint(constraint.clone()) {
+                    Ok(()) => constraints.push(constraint),
+                    // Only the required constraints can be unsatisfiable.
+                    Err(KiwiError::UnsatisfiableConstraint { .. })
+                        if constraint.strength() >= REQUIRED =>
+                    {
+                        continue
+                    }
+                    Err(error) => panic!("adding {} failed: {}", constraint, error),
+                }
+            }
+            Operation::RemoveConstraint(i) => {
+                if constraints.is_empty() {
+                    continue;
+                }
+                let constraint = constraints.remove(i as usize % constraints.len());
+                expect_ok(solver.remove_constraint(&constraint), "remove_constraint");
+            }
+            Operation::AddEditVariable {
+                variable: v,
+                strength: s,
+            } => {
+                let v = index(v);
+                let result = solver.add_edit_variable(&variables[v], strength(s));
+                if edits[v] {
+                    assert!(
+                        matches!(result, Err(KiwiError::DuplicateEditVariable { .. })),
+                        "adding a duplicate edit variable returned {:?}",
+                        result
+                    );
+                    continue;
+                } else if strength(s) >= REQUIRED {
+                    assert!(
+                        matches!(result, Err(KiwiError::BadRequiredStrength)),
+                        "adding a required edit variable returned {:?}",
+                        result
+                    );
+                    continue;
+                }
+                expect_ok(result, "add_edit_variable");
+                edits[v] = true;
+            }
+            Operation::RemoveEditVariable(v) => {
+                let v = index(v);
+                let result = solver.remove_edit_variable(&variables[v]);
+                if !edits[v] {
+                    assert!(
+                        matches!(result, Err(KiwiError::UnknownEditVariable { .. })),
+                        "removing an unknown edit variable returned {:?}",
+                        result
+                    );
+                    continue;
+                }
+                expect_ok(result, "remove_edit_variable");
+                edits[v] = false;
+            }
+            Operation::SuggestValue { variable: v, value } => {
+                let v = index(v);
+                let result = solver.suggest_value(&variables[v], value as f64);
+                if !edits[v] {
+                    assert!(
+                        matches!(result, Err(KiwiError::UnknownEditVariable { .. })),
+                        "suggesting a value for an unknown edit variable returned {:?}",
+                        result
+                    );
+                    continue;
+                }
+                expect_ok(result, "suggest_value");
+            }
+            Operation::UpdateVariables => (),
+        }
+        solver.update_variable();
+        let magnitude = variables
+            .iter()
+            .fold(1.0, |acc: f64, variable| acc.max(variable.value().abs()));
+        if magnitude > MAX_MAGNITUDE {
+            return;
+        }
+        check_required(&constraints, magnitude);
+    }
+});
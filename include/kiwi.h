@@ -0,0 +1,331 @@
+/* C API of kiwi-rs, generated by cbindgen from src/capi.rs. Do not edit. */
+
+#ifndef KIWI_H
+#define KIWI_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ Strength used for absolutely required constraints.
+ */
+#define KIWI_STRENGTH_REQUIRED 1001001000.0
+
+/*
+ Strength used for strong constraints.
+ */
+#define KIWI_STRENGTH_STRONG 1000000.0
+
+/*
+ Strength used for medium constraints.
+ */
+#define KIWI_STRENGTH_MEDIUM 1000.0
+
+/*
+ Strength used for weak constraints.
+ */
+#define KIWI_STRENGTH_WEAK 1.0
+
+/*
+ Relational operator of a constraint.
+ */
+typedef enum KiwiOp {
+  KIWI_OP_LESS_EQUAL,
+  KIWI_OP_GREATER_EQUAL,
+  KIWI_OP_EQUAL,
+} KiwiOp;
+
+/*
+ Result of the solver operations.
+ */
+typedef enum KiwiResult {
+  KIWI_RESULT_OK = 0,
+  KIWI_RESULT_UNSATISFIABLE_CONSTRAINT,
+  KIWI_RESULT_UNKNOWN_CONSTRAINT,
+  KIWI_RESULT_DUPLICATE_CONSTRAINT,
+  KIWI_RESULT_UNKNOWN_EDIT_VARIABLE,
+  KIWI_RESULT_DUPLICATE_EDIT_VARIABLE,
+  KIWI_RESULT_UNKNOWN_STAY_VARIABLE,
+  KIWI_RESULT_DUPLICATE_STAY_VARIABLE,
+  KIWI_RESULT_INVALID_BOUNDS,
+  KIWI_RESULT_UNKNOWN_BOUNDS,
+  KIWI_RESULT_BAD_REQUIRED_STRENGTH,
+  KIWI_RESULT_PIVOT_LIMIT_REACHED,
+  KIWI_RESULT_UNBOUNDED_OBJECTIVE,
+  KIWI_RESULT_UNKNOWN_OBJECTIVE,
+  KIWI_RESULT_UNSATISFIABLE_DISJUNCTION,
+  KIWI_RESULT_UNKNOWN_DISJUNCTION,
+  KIWI_RESULT_UNKNOWN_GROUP,
+  KIWI_RESULT_UNKNOWN_RULE,
+  KIWI_RESULT_RULE_OSCILLATION,
+  KIWI_RESULT_INTEGER_INFEASIBLE,
+  KIWI_RESULT_BRANCH_NODE_LIMIT_REACHED,
+  KIWI_RESULT_UNKNOWN_VARIABLE,
+  KIWI_RESULT_INTERNAL_SOLVER_ERROR,
+  /*
+   A null handle was passed to the function.
+   */
+  KIWI_RESULT_NULL_HANDLE,
+} KiwiResult;
+
+/*
+ Opaque handle to a constraint.
+ */
+typedef struct KiwiConstraint KiwiConstraint;
+
+/*
+ Opaque handle to a solver.
+ */
+typedef struct KiwiSolver KiwiSolver;
+
+/*
+ Opaque handle to a variable.
+ */
+typedef struct KiwiVariable KiwiVariable;
+
+/*
+ Term of a linear expression.
+ */
+typedef struct KiwiTerm {
+  const struct KiwiVariable *variable;
+  double coefficient;
+} KiwiTerm;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Get a short description of a result code.
+
+ The returned string is static and must not be freed.
+ */
+const char *kiwi_result_message(enum KiwiResult result);
+
+/*
+ Create a strength from its strong, medium and weak components.
+ */
+double kiwi_strength_create(double a, double b, double c, double weight);
+
+/*
+ Create a variable.
+
+ # Safety
+
+ name must be null or a valid nul terminated UTF-8 string. Invalid UTF-8 sequences are
+ replaced.
+ */
+struct KiwiVariable *kiwi_variable_new(const char *name);
+
+/*
+ Release a variable handle.
+
+ # Safety
+
+ variable must be null or a handle returned by kiwi_variable_new which was not released.
+ */
+void kiwi_variable_del(struct KiwiVariable *variable);
+
+/*
+ Copy the nul terminated name of the variable to a buffer.
+
+ At most size bytes are written. The length of the name (without the nul terminator) is
+ returned, so that a buffer of the right size can be allocated. A null variable has an empty
+ name.
+
+ # Safety
+
+ variable must be null or a valid handle and buffer must be null or point to size writable
+ bytes.
+ */
+size_t kiwi_variable_name(const struct KiwiVariable *variable, char *buffer, size_t size);
+
+/*
+ Set the name of the variable.
+
+ Nothing is done if one of the arguments is null.
+
+ # Safety
+
+ variable must be null or a valid handle and name must be null or a valid nul terminated
+ string.
+ */
+void kiwi_variable_set_name(const struct KiwiVariable *variable, const char *name);
+
+/*
+ Get the value of the variable computed by the last call to kiwi_solver_update_variables.
+
+ NaN is returned for a null variable.
+
+ # Safety
+
+ variable must be null or a valid handle.
+ */
+double kiwi_variable_value(const struct KiwiVariable *variable);
+
+/*
+ Create a constraint: sum(terms) + constant op 0.
+
+ Null is returned if terms is null while count is not 0, or if one of the terms has a null
+ variable.
+
+ # Safety
+
+ terms must be null or point to count terms whose variables are null or valid handles, and
+ op must be a valid KiwiOp.
+ */
+struct KiwiConstraint *kiwi_constraint_new(const struct KiwiTerm *terms,
+                                           size_t count,
+                                           double constant,
+                                           enum KiwiOp op,
+                                           double strength);
+
+/*
+ Release a constraint handle.
+
+ # Safety
+
+ constraint must be null or a handle returned by kiwi_constraint_new which was not released.
+ */
+void kiwi_constraint_del(struct KiwiConstraint *constraint);
+
+/*
+ Get the strength of the constraint.
+
+ NaN is returned for a null constraint.
+
+ # Safety
+
+ constraint must be null or a valid handle.
+ */
+double kiwi_constraint_strength(const struct KiwiConstraint *constraint);
+
+/*
+ Check whether the current values of the variables violate the constraint.
+
+ A null constraint is not violated.
+
+ # Safety
+
+ constraint must be null or a valid handle.
+ */
+bool kiwi_constraint_violated(const struct KiwiConstraint *constraint);
+
+/*
+ Create a solver.
+ */
+struct KiwiSolver *kiwi_solver_new(void);
+
+/*
+ Release a solver.
+
+ # Safety
+
+ solver must be null or a handle returned by kiwi_solver_new which was not released.
+ */
+void kiwi_solver_del(struct KiwiSolver *solver);
+
+/*
+ Add a constraint to the solver.
+
+ # Safety
+
+ solver and constraint must be null or valid handles.
+ */
+enum KiwiResult kiwi_solver_add_constraint(struct KiwiSolver *solver,
+                                           const struct KiwiConstraint *constraint);
+
+/*
+ Remove a constraint from the solver.
+
+ # Safety
+
+ solver and constraint must be null or valid handles.
+ */
+enum KiwiResult kiwi_solver_remove_constraint(struct KiwiSolver *solver,
+                                              const struct KiwiConstraint *constraint);
+
+/*
+ Test whether a constraint has been added to the solver.
+
+ False is returned if one of the handles is null.
+
+ # Safety
+
+ solver and constraint must be null or valid handles.
+ */
+bool kiwi_solver_has_constraint(const struct KiwiSolver *solver,
+                                const struct KiwiConstraint *constraint);
+
+/*
+ Add an edit variable to the solver.
+
+ # Safety
+
+ solver and variable must be null or valid handles.
+ */
+enum KiwiResult kiwi_solver_add_edit_variable(struct KiwiSolver *solver,
+                                              const struct KiwiVariable *variable,
+                                              double strength);
+
+/*
+ Remove an edit variable from the solver.
+
+ # Safety
+
+ solver and variable must be null or valid handles.
+ */
+enum KiwiResult kiwi_solver_remove_edit_variable(struct KiwiSolver *solver,
+                                                 const struct KiwiVariable *variable);
+
+/*
+ Test whether a variable is an edit variable of the solver.
+
+ False is returned if one of the handles is null.
+
+ # Safety
+
+ solver and variable must be null or valid handles.
+ */
+bool kiwi_solver_has_edit_variable(const struct KiwiSolver *solver,
+                                   const struct KiwiVariable *variable);
+
+/*
+ Suggest a value for an edit variable.
+
+ # Safety
+
+ solver and variable must be null or valid handles.
+ */
+enum KiwiResult kiwi_solver_suggest_value(struct KiwiSolver *solver,
+                                          const struct KiwiVariable *variable,
+                                          double value);
+
+/*
+ Update the values of the variables known to the solver.
+
+ # Safety
+
+ solver must be null or a valid handle.
+ */
+enum KiwiResult kiwi_solver_update_variables(struct KiwiSolver *solver);
+
+/*
+ Remove all the constraints and edit variables from the solver.
+
+ Nothing is done for a null solver.
+
+ # Safety
+
+ solver must be null or a valid handle.
+ */
+void kiwi_solver_reset(struct KiwiSolver *solver);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KIWI_H */
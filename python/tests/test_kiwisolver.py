@@ -0,0 +1,495 @@
+"""Tests of the kiwisolver compatible bindings, ported from the kiwisolver test suite.
+
+Build the extension with `maturin develop` (or copy the library built with
+`cargo build --features python` as kiwisolver.so on the Python path) and run
+`python -m unittest discover python/tests`.
+
+"""
+import math
+import operator
+import unittest
+
+from kiwisolver import (
+    BadRequiredStrength,
+    Constraint,
+    DuplicateConstraint,
+    DuplicateEditVariable,
+    Expression,
+    Solver,
+    Term,
+    UnknownConstraint,
+    UnknownEditVariable,
+    UnsatisfiableConstraint,
+    Variable,
+    strength,
+)
+
+
+class TestVariable(unittest.TestCase):
+    def test_variable_methods(self):
+        v = Variable()
+        self.assertEqual(v.name(), "")
+        v.setName("Δ")
+        self.assertEqual(v.name(), "Δ")
+        v.setName("foo")
+        self.assertEqual(v.name(), "foo")
+        self.assertEqual(str(v), "foo")
+        self.assertEqual(v.value(), 0.0)
+
+        self.assertIsNone(v.context())
+        ctx = object()
+        v.setContext(ctx)
+        self.assertIs(v.context(), ctx)
+        self.assertIs(Variable("bar", ctx).context(), ctx)
+
+    def test_variable_neg(self):
+        v = Variable("foo")
+        neg = -v
+        self.assertIsInstance(neg, Term)
+        self.assertIs(neg.variable(), v)
+        self.assertEqual(neg.coefficient(), -1)
+
+    def test_variable_mul(self):
+        v = Variable("foo")
+        for mul in (v * 2.0, 2 * v):
+            self.assertIsInstance(mul, Term)
+            self.assertIs(mul.variable(), v)
+            self.assertEqual(mul.coefficient(), 2)
+        with self.assertRaises(TypeError):
+            v * v
+
+    def test_variable_division(self):
+        v = Variable("foo")
+        div = v / 2
+        self.assertIsInstance(div, Term)
+        self.assertIs(div.variable(), v)
+        self.assertEqual(div.coefficient(), 0.5)
+        with self.assertRaises(TypeError):
+            v / v
+        with self.assertRaises(ZeroDivisionError):
+            v / 0
+
+    def test_variable_addition(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        for add, const in ((v + 2, 2), (2.0 + v, 2.0)):
+            self.assertIsInstance(add, Expression)
+            self.assertEqual(add.constant(), const)
+            (term,) = add.terms()
+            self.assertIs(term.variable(), v)
+            self.assertEqual(term.coefficient(), 1)
+
+        add = v + v2
+        self.assertIsInstance(add, Expression)
+        self.assertEqual(add.constant(), 0)
+        t1, t2 = add.terms()
+        self.assertIs(t1.variable(), v)
+        self.assertIs(t2.variable(), v2)
+        self.assertEqual((t1.coefficient(), t2.coefficient()), (1, 1))
+
+        with self.assertRaises(TypeError):
+            v + "foo"
+
+    def test_variable_sub(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        for sub, const, coeff in ((v - 2, -2, 1), (2 - v, 2, -1)):
+            self.assertIsInstance(sub, Expression)
+            self.assertEqual(sub.constant(), const)
+            (term,) = sub.terms()
+            self.assertIs(term.variable(), v)
+            self.assertEqual(term.coefficient(), coeff)
+
+        sub = v - v2
+        self.assertIsInstance(sub, Expression)
+        t1, t2 = sub.terms()
+        self.assertIs(t1.variable(), v)
+        self.assertIs(t2.variable(), v2)
+        self.assertEqual((t1.coefficient(), t2.coefficient()), (1, -1))
+
+    def test_variable_rich_compare(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        for op, symbol in ((operator.le, "<="), (operator.eq, "=="), (operator.ge, ">=")):
+            c = op(v, v2 + 1)
+            self.assertIsInstance(c, Constraint)
+            e = c.expression()
+            t = e.terms()
+            self.assertEqual(len(t), 2)
+            self.assertEqual(e.constant(), -1)
+            self.assertEqual(c.op(), symbol)
+            self.assertEqual(c.strength(), strength.required)
+
+        for op in (operator.lt, operator.ne, operator.gt):
+            with self.assertRaises(TypeError):
+                op(v, v2)
+
+    def test_variable_hash(self):
+        v = Variable("foo")
+        v2 = Variable("foo")
+        d = {v: 1, v2: 2}
+        self.assertEqual(d[v], 1)
+        self.assertEqual(d[v2], 2)
+
+
+class TestTerm(unittest.TestCase):
+    def test_term_creation(self):
+        v = Variable("foo")
+        t = Term(v)
+        self.assertIs(t.variable(), v)
+        self.assertEqual(t.coefficient(), 1)
+
+        t = Term(v, 100)
+        self.assertIs(t.variable(), v)
+        self.assertEqual(t.coefficient(), 100)
+        self.assertEqual(str(t), "100 * foo")
+
+        with self.assertRaises(TypeError):
+            Term(1)
+
+    def test_term_arith_operators(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        t = Term(v, 10)
+        t2 = Term(v2)
+
+        for res in (t * 2, 2.0 * t):
+            self.assertIsInstance(res, Term)
+            self.assertIs(res.variable(), v)
+            self.assertEqual(res.coefficient(), 20)
+
+        div = t / 2
+        self.assertIsInstance(div, Term)
+        self.assertEqual(div.coefficient(), 5)
+        with self.assertRaises(ZeroDivisionError):
+            t / 0
+
+        neg = -t
+        self.assertIsInstance(neg, Term)
+        self.assertEqual(neg.coefficient(), -10)
+
+        add = t + t2
+        self.assertIsInstance(add, Expression)
+        self.assertEqual(add.constant(), 0)
+        self.assertEqual(len(add.terms()), 2)
+
+        sub = 2 - t
+        self.assertIsInstance(sub, Expression)
+        self.assertEqual(sub.constant(), 2)
+        (term,) = sub.terms()
+        self.assertIs(term.variable(), v)
+        self.assertEqual(term.coefficient(), -10)
+
+    def test_term_value(self):
+        s = Solver()
+        v = Variable("foo")
+        s.addConstraint(v == 3)
+        s.updateVariables()
+        self.assertEqual(Term(v, 2).value(), 6)
+
+
+class TestExpression(unittest.TestCase):
+    def test_expression_creation(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        terms = (Term(v, 1), Term(v2, 2))
+        e = Expression(terms, 5)
+        self.assertEqual(e.constant(), 5)
+        self.assertEqual(len(e.terms()), 2)
+        for t, t2 in zip(e.terms(), terms):
+            self.assertIs(t, t2)
+        self.assertEqual(str(e), "1 * foo + 2 * bar + 5")
+
+        self.assertEqual(Expression(terms).constant(), 0)
+        with self.assertRaises(TypeError):
+            Expression((1, 2))
+
+    def test_expression_arith_operators(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        e = Expression((Term(v, 10),), 5)
+        e2 = v2 - 10
+
+        for res in (e * 2, 2 * e):
+            self.assertIsInstance(res, Expression)
+            self.assertEqual(res.constant(), 10)
+            (term,) = res.terms()
+            self.assertIs(term.variable(), v)
+            self.assertEqual(term.coefficient(), 20)
+
+        div = e / 2
+        self.assertIsInstance(div, Expression)
+        self.assertEqual(div.constant(), 2.5)
+        with self.assertRaises(ZeroDivisionError):
+            e / 0
+
+        neg = -e
+        self.assertEqual(neg.constant(), -5)
+        self.assertEqual(neg.terms()[0].coefficient(), -10)
+
+        add = e + e2
+        self.assertEqual(add.constant(), -5)
+        self.assertEqual(len(add.terms()), 2)
+
+        sub = e - e2
+        self.assertEqual(sub.constant(), 15)
+        self.assertEqual(sub.terms()[1].coefficient(), -1)
+
+        with self.assertRaises(TypeError):
+            e * e2
+
+    def test_expression_rich_compare(self):
+        v = Variable("foo")
+        e = Expression((Term(v, 2),), 5)
+        for op, symbol in ((operator.le, "<="), (operator.eq, "=="), (operator.ge, ">=")):
+            c = op(e, 1)
+            self.assertIsInstance(c, Constraint)
+            self.assertEqual(c.op(), symbol)
+            self.assertEqual(c.expression().constant(), 4)
+        for op in (operator.lt, operator.ne, operator.gt):
+            with self.assertRaises(TypeError):
+                op(e, 1)
+
+    def test_expression_value(self):
+        s = Solver()
+        v = Variable("foo")
+        v2 = Variable("bar")
+        s.addConstraint(v == 1)
+        s.addConstraint(v2 == 2)
+        s.updateVariables()
+        self.assertEqual((v + 2 * v2 + 3).value(), 8)
+
+
+class TestConstraint(unittest.TestCase):
+    def test_constraint_creation(self):
+        v = Variable("foo")
+        c = Constraint(v + 1, "==")
+        self.assertEqual(c.strength(), strength.required)
+        self.assertEqual(c.op(), "==")
+        e = c.expression()
+        (t,) = e.terms()
+        self.assertEqual(e.constant(), 1)
+        self.assertIs(t.variable(), v)
+        self.assertEqual(t.coefficient(), 1)
+
+        for s in ("weak", "medium", "strong", "required"):
+            c = Constraint(v + 1, ">=", s)
+            self.assertEqual(c.strength(), getattr(strength, s))
+        self.assertEqual(Constraint(v + 1, "<=", 12.5).strength(), 12.5)
+
+        with self.assertRaises(TypeError):
+            Constraint(1, "==")
+        with self.assertRaises(ValueError):
+            Constraint(v + 1, "!=")
+        with self.assertRaises(ValueError):
+            Constraint(v + 1, "==", "unknown")
+
+    def test_constraint_reduction(self):
+        v = Variable("foo")
+        v2 = Variable("bar")
+        c = 2 * v + v2 - v + 3 <= v2
+        e = c.expression()
+        (t,) = [t for t in e.terms() if t.coefficient() != 0]
+        self.assertIs(t.variable(), v)
+        self.assertEqual(t.coefficient(), 1)
+        self.assertEqual(e.constant(), 3)
+
+    def test_constraint_or_operator(self):
+        v = Variable("foo")
+        c = v + 1 == 0
+        for s in ("weak", "medium", "strong", "required", strength.create(1, 1, 0)):
+            c2 = c | s
+            self.assertIsNot(c2, c)
+            self.assertEqual(c2.op(), c.op())
+            self.assertIs(c2.expression(), c.expression())
+            expected = getattr(strength, s) if isinstance(s, str) else s
+            self.assertEqual(c2.strength(), expected)
+
+        with self.assertRaises(ValueError):
+            c | "unknown"
+
+    def test_constraint_violated(self):
+        s = Solver()
+        v = Variable("foo")
+        s.addConstraint(v == 10)
+        s.updateVariables()
+        self.assertFalse((v >= 5).violated())
+        self.assertTrue((v <= 5).violated())
+        self.assertTrue((v == 5).violated())
+        self.assertFalse((v == 10).violated())
+        # Equalities are compared with the tolerance used by kiwisolver.
+        self.assertFalse((v == 10 + 1e-12).violated())
+        self.assertTrue((v == 10 + 1e-6).violated())
+
+
+class TestStrength(unittest.TestCase):
+    def test_accessing_predefined_strength(self):
+        self.assertLess(strength.weak, strength.medium)
+        self.assertLess(strength.medium, strength.strong)
+        self.assertLess(strength.strong, strength.required)
+
+    def test_creating_strength(self):
+        self.assertLess(strength.create(0, 0, 1), strength.create(0, 1, 0))
+        self.assertLess(strength.create(0, 1, 0), strength.create(1, 0, 0))
+        self.assertLess(strength.create(1, 0, 0, 1), strength.create(1, 0, 0, 4))
+
+
+class TestSolver(unittest.TestCase):
+    def test_solver_creation(self):
+        self.assertIsInstance(Solver(), Solver)
+
+    def test_managing_edit_variable(self):
+        s = Solver()
+        v1 = Variable("foo")
+        v2 = Variable("bar")
+
+        self.assertFalse(s.hasEditVariable(v1))
+        s.addEditVariable(v1, "weak")
+        self.assertTrue(s.hasEditVariable(v1))
+        with self.assertRaises(DuplicateEditVariable) as cm:
+            s.addEditVariable(v1, "medium")
+        self.assertIs(cm.exception.args[0], v1)
+        with self.assertRaises(UnknownEditVariable) as cm:
+            s.removeEditVariable(v2)
+        self.assertIs(cm.exception.args[0], v2)
+        s.removeEditVariable(v1)
+        self.assertFalse(s.hasEditVariable(v1))
+
+        with self.assertRaises(BadRequiredStrength):
+            s.addEditVariable(v1, strength.required)
+
+        s.addEditVariable(v2, strength.strong)
+        self.assertTrue(s.hasEditVariable(v2))
+        with self.assertRaises(UnknownEditVariable) as cm:
+            s.suggestValue(v1, 10)
+        self.assertIs(cm.exception.args[0], v1)
+
+        s.reset()
+        self.assertFalse(s.hasEditVariable(v2))
+
+    def test_suggesting_values_for_edit_variables(self):
+        s = Solver()
+        v1 = Variable("foo")
+        v2 = Variable("bar")
+
+        s.addEditVariable(v1, "medium")
+        s.addConstraint((v1 == 1) | "strong")
+        s.suggestValue(v1, 2)
+        s.updateVariables()
+        self.assertEqual(v1.value(), 1)
+
+        s.reset()
+        s.addEditVariable(v1, "strong")
+        s.addEditVariable(v2, "strong")
+        s.addConstraint(v1 + v2 == 0)
+        s.suggestValue(v2, 2)
+        s.suggestValue(v1, 10)
+        s.updateVariables()
+        # Any value between the two suggestions has the same (minimal) error.
+        self.assertTrue(math.isclose(v1.value() + v2.value(), 0, abs_tol=1e-9))
+        self.assertTrue(-2 <= v1.value() <= 10)
+
+    def test_managing_constraints(self):
+        s = Solver()
+        v = Variable("foo")
+        c1 = v >= 1
+        c2 = v <= 0
+
+        self.assertFalse(s.hasConstraint(c1))
+        s.addConstraint(c1)
+        self.assertTrue(s.hasConstraint(c1))
+        with self.assertRaises(DuplicateConstraint) as cm:
+            s.addConstraint(c1)
+        self.assertIs(cm.exception.args[0], c1)
+        with self.assertRaises(UnknownConstraint) as cm:
+            s.removeConstraint(c2)
+        self.assertIs(cm.exception.args[0], c2)
+        with self.assertRaises(UnsatisfiableConstraint) as cm:
+            s.addConstraint(c2)
+        self.assertIs(cm.exception.args[0], c2)
+        self.assertFalse(s.hasConstraint(c2))
+
+        s.removeConstraint(c1)
+        self.assertFalse(s.hasConstraint(c1))
+        s.addConstraint(c2)
+        self.assertTrue(s.hasConstraint(c2))
+        s.reset()
+        self.assertFalse(s.hasConstraint(c2))
+
+    def test_solving_under_constrained_system(self):
+        s = Solver()
+        v = Variable("foo")
+        c = 2 * v + 1 >= 0
+        s.addEditVariable(v, "weak")
+        s.addConstraint(c)
+        s.suggestValue(v, 10)
+        s.updateVariables()
+
+        self.assertEqual(c.expression().value(), 21)
+        self.assertEqual(c.expression().terms()[0].value(), 20)
+        self.assertEqual(c.expression().terms()[0].variable().value(), 10)
+
+    def test_solving_with_strength(self):
+        v1 = Variable("foo")
+        v2 = Variable("bar")
+        s = Solver()
+        s.addConstraint(v1 + v2 == 0)
+        s.addConstraint(v1 == 10)
+        s.addConstraint((v2 >= 0) | "weak")
+        s.updateVariables()
+        self.assertEqual((v1.value(), v2.value()), (10, -10))
+
+        s.reset()
+        s.addConstraint(v1 + v2 == 0)
+        s.addConstraint((v1 >= 10) | "medium")
+        s.addConstraint((v2 == 2) | "strong")
+        s.updateVariables()
+        self.assertEqual((v1.value(), v2.value()), (-2, 2))
+
+    def test_handling_infeasible_constraints(self):
+        xm = Variable("xm")
+        xl = Variable("xl")
+        xr = Variable("xr")
+        s = Solver()
+
+        s.addEditVariable(xm, "strong")
+        s.addEditVariable(xl, "weak")
+        s.addEditVariable(xr, "weak")
+
+        s.addConstraint(2 * xm == xl + xr)
+        s.addConstraint(xl + 20 <= xr)
+        s.addConstraint(xl >= -10)
+        s.addConstraint(xr <= 100)
+
+        s.suggestValue(xm, 40)
+        s.suggestValue(xr, 50)
+        s.suggestValue(xl, 30)
+
+        # First update causing a normal update.
+        s.suggestValue(xm, 60)
+
+        # Create an infeasible condition triggering a dual optimization.
+        s.suggestValue(xm, 90)
+        s.updateVariables()
+        self.assertTrue(math.isclose(xl.value() + xr.value(), 2 * xm.value()))
+        self.assertTrue(math.isclose(xl.value(), 80))
+        self.assertTrue(math.isclose(xr.value(), 100))
+
+    def test_constraint_violated_after_removal(self):
+        s = Solver()
+        v = Variable("foo")
+        c1 = v >= 10
+        c2 = (v == 0) | "weak"
+        s.addConstraint(c1)
+        s.addConstraint(c2)
+        s.updateVariables()
+        self.assertEqual(v.value(), 10)
+        self.assertTrue(c2.violated())
+        s.removeConstraint(c1)
+        s.updateVariables()
+        self.assertEqual(v.value(), 0)
+        self.assertFalse(c2.violated())
+
+
+if __name__ == "__main__":
+    unittest.main()
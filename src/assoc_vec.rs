@@ -2,22 +2,22 @@
 //! (Inspired by the C++ implementation of Loki::AssocVec)
 use std::iter::FromIterator;
 use std::mem;
+use std::ops::{Index, IndexMut};
 use std::vec;
 
-// FIXME implement an entry interface similar to HashMap
-// FIXME implement drain
-// FIXME implement Index
 // FIXME Ensure compatibility with HasMap to try and replace one by the other
 
 /// Mapping type relying the ability to sort the keys.
 ///
 /// # Notes
 ///
-/// We do not use IndexMut traits since using the IndexMut would
-/// require us to be able to create a new V, which we may not know how to do.
-/// The use of Entry API similar to HashMap provide a convenient workaround.
+/// Like for std::collections::HashMap, indexing panics if the key is not present
+/// since we do not know how to create a new V. The Entry API provides a convenient
+/// workaround to insert missing values.
 ///
-/// We also try to stay as close as possible to the API of std::collections::HashMap
+/// We also try to stay as close as possible to the API of std::collections::HashMap. The parts
+/// of that API the solver does not use yet are marked `#[allow(dead_code)]` and are covered by
+/// the tests of this module.
 pub struct AssocVec<K, V>
 where
     K: Ord,
@@ -39,6 +39,7 @@ where
 
     /// Create a new AssocVector with a specific capacity
     #[inline]
+    #[allow(dead_code)]
     pub fn with_capacity(capacity: usize) -> AssocVec<K, V> {
         AssocVec {
             vec: Vec::with_capacity(capacity),
@@ -59,6 +60,7 @@ where
 
     /// Returns the capacity of the AssocVector
     #[inline]
+    #[allow(dead_code)]
     pub fn capacity(&self) -> usize {
         self.vec.capacity()
     }
@@ -116,6 +118,57 @@ where
         }
     }
 
+    /// Get the entry corresponding to a key for in-place manipulation
+    ///
+    /// The key is looked up only once whatever the operations performed on the entry.
+    #[inline]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.search(&key) {
+            Ok(index) => Entry::Occupied(OccupiedEntry {
+                vec: &mut self.vec,
+                index,
+            }),
+            Err(index) => Entry::Vacant(VacantEntry {
+                vec: &mut self.vec,
+                key,
+                index,
+            }),
+        }
+    }
+
+    /// Remove all the key, value pairs and iterate over them
+    ///
+    /// The pairs are yielded in the order of the keys.
+    #[inline]
+    #[allow(dead_code)]
+    pub fn drain(&mut self) -> vec::Drain<'_, (K, V)> {
+        self.vec.drain(..)
+    }
+
+    /// Retain only the pairs for which the predicate returns true
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.vec.retain_mut(|(k, v)| f(k, v))
+    }
+
+    /// Access the key, value pairs as a slice sorted by key
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        &self.vec
+    }
+
+    /// Exchange the content with a vector of pairs already sorted by key
+    ///
+    /// The previous content is moved into the vector, which allows to reuse its allocation.
+    #[inline]
+    pub fn swap_sorted(&mut self, vec: &mut Vec<(K, V)>) {
+        debug_assert!(vec.windows(2).all(|w| w[0].0 < w[1].0));
+        mem::swap(&mut self.vec, vec);
+    }
+
     /// Iter over the key, value pairs
     pub fn iter(&self) -> std::slice::Iter<(K, V)> {
         self.vec.iter()
@@ -136,6 +189,179 @@ where
     }
 }
 
+/// A view into a single entry of an AssocVec, which may either be vacant or occupied
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// A view into an occupied entry of an AssocVec
+pub struct OccupiedEntry<'a, K, V> {
+    vec: &'a mut Vec<(K, V)>,
+    index: usize,
+}
+
+/// A view into a vacant entry of an AssocVec
+///
+/// The index at which the key should be inserted to preserve the order is stored.
+pub struct VacantEntry<'a, K, V> {
+    vec: &'a mut Vec<(K, V)>,
+    key: K,
+    index: usize,
+}
+
+#[allow(dead_code)]
+impl<'a, K, V> Entry<'a, K, V> {
+    /// Access the key of the entry
+    #[inline]
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Insert a default value if the entry is vacant and return a mutable reference to the value
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Insert the result of a function if the entry is vacant and return a mutable reference
+    /// to the value
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Modify the value in place if the entry is occupied
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+#[allow(dead_code)]
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    /// Insert the default value if the entry is vacant and return a mutable reference to the
+    /// value
+    #[inline]
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Access the key of the entry
+    #[inline]
+    #[allow(dead_code)]
+    pub fn key(&self) -> &K {
+        &self.vec[self.index].0
+    }
+
+    /// Get an immutable reference to the value
+    #[inline]
+    pub fn get(&self) -> &V {
+        &self.vec[self.index].1
+    }
+
+    /// Get a mutable reference to the value
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.vec[self.index].1
+    }
+
+    /// Convert the entry into a mutable reference to the value bound to the AssocVec
+    #[inline]
+    #[allow(dead_code)]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.vec[self.index].1
+    }
+
+    /// Set the value of the entry and return the old value
+    #[inline]
+    #[allow(dead_code)]
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    /// Remove the entry from the AssocVec and return the value
+    #[inline]
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Remove the entry from the AssocVec and return the key, value pair
+    #[inline]
+    pub fn remove_entry(self) -> (K, V) {
+        self.vec.remove(self.index)
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    /// Access the key of the entry
+    #[inline]
+    #[allow(dead_code)]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key
+    #[inline]
+    #[allow(dead_code)]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Set the value of the entry and return a mutable reference to it
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.vec.insert(self.index, (self.key, value));
+        &mut self.vec[self.index].1
+    }
+}
+
+impl<K, V> Index<&K> for AssocVec<K, V>
+where
+    K: Ord,
+{
+    type Output = V;
+
+    /// Access the value corresponding to a key
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the AssocVec.
+    #[inline]
+    fn index(&self, key: &K) -> &V {
+        self.get(key).expect("key not present in the AssocVec")
+    }
+}
+
+impl<K, V> IndexMut<&K> for AssocVec<K, V>
+where
+    K: Ord,
+{
+    /// Access mutably the value corresponding to a key
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the AssocVec.
+    #[inline]
+    fn index_mut(&mut self, key: &K) -> &mut V {
+        self.get_mut(key).expect("key not present in the AssocVec")
+    }
+}
+
 impl<K, V> Default for AssocVec<K, V>
 where
     K: Ord,
@@ -186,7 +412,7 @@ where
 #[cfg(test)]
 mod test {
 
-    use super::AssocVec;
+    use super::{AssocVec, Entry};
 
     #[test]
     fn test_insert() {
@@ -221,4 +447,73 @@ mod test {
         }
         assert_eq!(*av.get(&1).unwrap(), 3);
     }
+
+    #[test]
+    fn test_entry() {
+        let mut av = AssocVec::new();
+        av.insert(1, 2);
+        av.insert(5, 6);
+
+        *av.entry(3).or_insert(0) += 4;
+        *av.entry(1).or_insert(0) += 1;
+        assert_eq!(av.entry(7).key(), &7);
+        assert_eq!(*av.entry(7).and_modify(|v| *v += 1).or_default(), 0);
+        assert_eq!(
+            av.iter().cloned().collect::<Vec<_>>(),
+            vec![(1, 3), (3, 4), (5, 6), (7, 0)]
+        );
+
+        match av.entry(5) {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.insert(8), 6);
+                assert_eq!(entry.remove_entry(), (5, 8));
+            }
+            Entry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+        match av.entry(5) {
+            Entry::Occupied(_) => panic!("expected a vacant entry"),
+            Entry::Vacant(entry) => assert_eq!(entry.into_key(), 5),
+        }
+        assert!(!av.contains_key(&5));
+    }
+
+    #[test]
+    fn test_drain_retain() {
+        let mut av: AssocVec<i32, i32> = (0..6).rev().map(|i| (i, i * 10)).collect();
+        av.retain(|k, v| {
+            *v += 1;
+            k % 2 == 0
+        });
+        assert_eq!(
+            av.drain().collect::<Vec<_>>(),
+            vec![(0, 1), (2, 21), (4, 41)]
+        );
+        assert!(av.is_empty());
+    }
+
+    #[test]
+    fn test_swap_sorted() {
+        let mut av: AssocVec<i32, i32> = vec![(2, 20), (1, 10)].into_iter().collect();
+        assert_eq!(av.as_slice(), &[(1, 10), (2, 20)]);
+        let mut other = vec![(0, 0), (3, 30)];
+        av.swap_sorted(&mut other);
+        assert_eq!(av.as_slice(), &[(0, 0), (3, 30)]);
+        assert_eq!(other, vec![(1, 10), (2, 20)]);
+        assert_eq!(av[&3], 30);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut av = AssocVec::new();
+        av.insert("a", 1);
+        av[&"a"] += 1;
+        assert_eq!(av[&"a"], 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_missing() {
+        let av: AssocVec<i32, i32> = AssocVec::new();
+        let _ = av[&1];
+    }
 }
@@ -0,0 +1,663 @@
+//! C API of the solver.
+//!
+//! Variables, constraints and solvers are exposed as opaque handles created by the kiwi_*_new
+//! functions and released with the matching kiwi_*_del function. Variable and constraint
+//! handles share their data with the copies kept by the solver, so a handle can be released
+//! while the solver still uses the object. Constraints are built from an array of terms, a
+//! constant, a relational operator and a strength.
+//!
+//! Solver operations return a KiwiResult code mapped from KiwiError. Panics are caught at the
+//! boundary and reported as KIWI_RESULT_INTERNAL_SOLVER_ERROR, after which the solver should
+//! be reset.
+//!
+//! The C header is generated by cbindgen in the build directory when building with the capi
+//! feature, a copy is kept in include/kiwi.h (see tests/capi.rs to update it).
+//!
+
+use crate::constraint::{Constraint, RelationalOperator};
+use crate::errors::KiwiError;
+use crate::expression::Expression;
+use crate::solver::Solver;
+use crate::strength;
+use crate::term::Term;
+use crate::util::{near_zero, EPSILON};
+use crate::variable::Variable;
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+// The strength constants are only used from C, through the generated header where build.rs
+// writes them from the strength module.
+
+/// Strength used for absolutely required constraints.
+#[allow(dead_code)]
+pub const KIWI_STRENGTH_REQUIRED: f64 = strength::REQUIRED;
+
+/// Strength used for strong constraints.
+#[allow(dead_code)]
+pub const KIWI_STRENGTH_STRONG: f64 = strength::STRONG;
+
+/// Strength used for medium constraints.
+#[allow(dead_code)]
+pub const KIWI_STRENGTH_MEDIUM: f64 = strength::MEDIUM;
+
+/// Strength used for weak constraints.
+#[allow(dead_code)]
+pub const KIWI_STRENGTH_WEAK: f64 = strength::WEAK;
+
+/// Result of the solver operations.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KiwiResult {
+    Ok = 0,
+    UnsatisfiableConstraint,
+    UnknownConstraint,
+    DuplicateConstraint,
+    UnknownEditVariable,
+    DuplicateEditVariable,
+    UnknownStayVariable,
+    DuplicateStayVariable,
+    InvalidBounds,
+    UnknownBounds,
+    BadRequiredStrength,
+    PivotLimitReached,
+    UnboundedObjective,
+    UnknownObjective,
+    UnsatisfiableDisjunction,
+    UnknownDisjunction,
+    UnknownGroup,
+    UnknownRule,
+    RuleOscillation,
+    IntegerInfeasible,
+    BranchNodeLimitReached,
+    UnknownVariable,
+    InternalSolverError,
+    /// A null handle was passed to the function.
+    NullHandle,
+}
+
+impl From<&KiwiError> for KiwiResult {
+    fn from(error: &KiwiError) -> KiwiResult {
+        match error {
+            KiwiError::UnsatisfiableConstraint { .. } => KiwiResult::UnsatisfiableConstraint,
+            KiwiError::UnknownConstraint { .. } => KiwiResult::UnknownConstraint,
+            KiwiError::DuplicateConstraint { .. } => KiwiResult::DuplicateConstraint,
+            KiwiError::UnknownEditVariable { .. } => KiwiResult::UnknownEditVariable,
+            KiwiError::DuplicateEditVariable { .. } => KiwiResult::DuplicateEditVariable,
+            KiwiError::UnknownStayVariable { .. } => KiwiResult::UnknownStayVariable,
+            KiwiError::DuplicateStayVariable { .. } => KiwiResult::DuplicateStayVariable,
+            KiwiError::InvalidBounds { .. } => KiwiResult::InvalidBounds,
+            KiwiError::UnknownBounds { .. } => KiwiResult::UnknownBounds,
+            KiwiError::BadRequiredStrength => KiwiResult::BadRequiredStrength,
+            KiwiError::PivotLimitReached { .. } => KiwiResult::PivotLimitReached,
+            KiwiError::UnboundedObjective => KiwiResult::UnboundedObjective,
+            KiwiError::UnknownObjective { .. } => KiwiResult::UnknownObjective,
+            KiwiError::UnsatisfiableDisjunction => KiwiResult::UnsatisfiableDisjunction,
+            KiwiError::UnknownDisjunction { .. } => KiwiResult::UnknownDisjunction,
+            KiwiError::UnknownGroup { .. } => KiwiResult::UnknownGroup,
+            KiwiError::UnknownRule { .. } => KiwiResult::UnknownRule,
+            KiwiError::RuleOscillation => KiwiResult::RuleOscillation,
+            KiwiError::IntegerInfeasible => KiwiResult::IntegerInfeasible,
+            KiwiError::BranchNodeLimitReached { .. } => KiwiResult::BranchNodeLimitReached,
+            KiwiError::UnknownVariable { .. } => KiwiResult::UnknownVariable,
+            KiwiError::InternalSolverError { .. } => KiwiResult::InternalSolverError,
+        }
+    }
+}
+
+/// Relational operator of a constraint.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KiwiOp {
+    LessEqual,
+    GreaterEqual,
+    Equal,
+}
+
+impl From<KiwiOp> for RelationalOperator {
+    fn from(op: KiwiOp) -> RelationalOperator {
+        match op {
+            KiwiOp::LessEqual => RelationalOperator::LessEqual,
+            KiwiOp::GreaterEqual => RelationalOperator::GreaterEqual,
+            KiwiOp::Equal => RelationalOperator::Equal,
+        }
+    }
+}
+
+/// Term of a linear expression.
+#[repr(C)]
+pub struct KiwiTerm {
+    pub variable: *const KiwiVariable,
+    pub coefficient: f64,
+}
+
+/// Opaque handle to a variable.
+pub struct KiwiVariable(Variable);
+
+/// Opaque handle to a constraint.
+pub struct KiwiConstraint(Constraint);
+
+/// Opaque handle to a solver.
+pub struct KiwiSolver(Solver);
+
+/// Run a solver operation, converting errors and panics to a result code.
+fn guard<F>(f: F) -> KiwiResult
+where
+    F: FnOnce() -> Result<(), KiwiError>,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => KiwiResult::Ok,
+        Ok(Err(error)) => KiwiResult::from(&error),
+        Err(_) => KiwiResult::InternalSolverError,
+    }
+}
+
+/// Get a short description of a result code.
+///
+/// The returned string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn kiwi_result_message(result: KiwiResult) -> *const c_char {
+    let message: &'static [u8] = match result {
+        KiwiResult::Ok => b"no error\0",
+        KiwiResult::UnsatisfiableConstraint => b"unsatisfiable constraint\0",
+        KiwiResult::UnknownConstraint => b"unknown constraint\0",
+        KiwiResult::DuplicateConstraint => b"duplicate constraint\0",
+        KiwiResult::UnknownEditVariable => b"unknown edit variable\0",
+        KiwiResult::DuplicateEditVariable => b"duplicate edit variable\0",
+        KiwiResult::UnknownStayVariable => b"unknown stay variable\0",
+        KiwiResult::DuplicateStayVariable => b"duplicate stay variable\0",
+        KiwiResult::InvalidBounds => b"invalid bounds\0",
+        KiwiResult::UnknownBounds => b"unknown bounds\0",
+        KiwiResult::BadRequiredStrength => b"bad required strength\0",
+        KiwiResult::PivotLimitReached => b"pivot limit reached\0",
+        KiwiResult::UnboundedObjective => b"unbounded objective\0",
+        KiwiResult::UnknownObjective => b"unknown objective\0",
+        KiwiResult::UnsatisfiableDisjunction => b"unsatisfiable disjunction\0",
+        KiwiResult::UnknownDisjunction => b"unknown disjunction\0",
+        KiwiResult::UnknownGroup => b"unknown group\0",
+        KiwiResult::UnknownRule => b"unknown rule\0",
+        KiwiResult::RuleOscillation => b"rule oscillation\0",
+        KiwiResult::IntegerInfeasible => b"integer infeasible\0",
+        KiwiResult::BranchNodeLimitReached => b"branch node limit reached\0",
+        KiwiResult::UnknownVariable => b"unknown variable\0",
+        KiwiResult::InternalSolverError => b"internal solver error\0",
+        KiwiResult::NullHandle => b"null handle\0",
+    };
+    message.as_ptr() as *const c_char
+}
+
+/// Create a strength from its strong, medium and weak components.
+#[no_mangle]
+pub extern "C" fn kiwi_strength_create(a: f64, b: f64, c: f64, weight: f64) -> f64 {
+    strength::create(a, b, c, weight)
+}
+
+/// Create a variable.
+///
+/// # Safety
+///
+/// name must be null or a valid nul terminated UTF-8 string. Invalid UTF-8 sequences are
+/// replaced.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_variable_new(name: *const c_char) -> *mut KiwiVariable {
+    let variable = if name.is_null() {
+        Variable::new()
+    } else {
+        Variable::new_with_name(&CStr::from_ptr(name).to_string_lossy())
+    };
+    Box::into_raw(Box::new(KiwiVariable(variable)))
+}
+
+/// Release a variable handle.
+///
+/// # Safety
+///
+/// variable must be null or a handle returned by kiwi_variable_new which was not released.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_variable_del(variable: *mut KiwiVariable) {
+    if !variable.is_null() {
+        drop(Box::from_raw(variable));
+    }
+}
+
+/// Copy the nul terminated name of the variable to a buffer.
+///
+/// At most size bytes are written. The length of the name (without the nul terminator) is
+/// returned, so that a buffer of the right size can be allocated. A null variable has an empty
+/// name.
+///
+/// # Safety
+///
+/// variable must be null or a valid handle and buffer must be null or point to size writable
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_variable_name(
+    variable: *const KiwiVariable,
+    buffer: *mut c_char,
+    size: usize,
+) -> usize {
+    let name = if variable.is_null() {
+        String::new()
+    } else {
+        (*variable).0.name().clone()
+    };
+    if !buffer.is_null() && size > 0 {
+        let count = usize::min(name.len(), size - 1);
+        ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, buffer, count);
+        *buffer.add(count) = 0;
+    }
+    name.len()
+}
+
+/// Set the name of the variable.
+///
+/// Nothing is done if one of the arguments is null.
+///
+/// # Safety
+///
+/// variable must be null or a valid handle and name must be null or a valid nul terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_variable_set_name(
+    variable: *const KiwiVariable,
+    name: *const c_char,
+) {
+    if variable.is_null() || name.is_null() {
+        return;
+    }
+    (*variable)
+        .0
+        .set_name(&CStr::from_ptr(name).to_string_lossy());
+}
+
+/// Get the value of the variable computed by the last call to kiwi_solver_update_variables.
+///
+/// NaN is returned for a null variable.
+///
+/// # Safety
+///
+/// variable must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_variable_value(variable: *const KiwiVariable) -> f64 {
+    if variable.is_null() {
+        return f64::NAN;
+    }
+    *(*variable).0.value()
+}
+
+/// Create a constraint: sum(terms) + constant op 0.
+///
+/// Null is returned if terms is null while count is not 0, or if one of the terms has a null
+/// variable.
+///
+/// # Safety
+///
+/// terms must be null or point to count terms whose variables are null or valid handles, and
+/// op must be a valid KiwiOp.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_constraint_new(
+    terms: *const KiwiTerm,
+    count: usize,
+    constant: f64,
+    op: KiwiOp,
+    strength: f64,
+) -> *mut KiwiConstraint {
+    let terms = if count == 0 {
+        &[]
+    } else if terms.is_null() {
+        return ptr::null_mut();
+    } else {
+        std::slice::from_raw_parts(terms, count)
+    };
+    let mut expr_terms = Vec::with_capacity(count);
+    for term in terms.iter() {
+        if term.variable.is_null() {
+            return ptr::null_mut();
+        }
+        expr_terms.push(Term::new((*term.variable).0.clone(), term.coefficient));
+    }
+    let constraint = Constraint::new(Expression::new(expr_terms, constant), op.into(), strength);
+    Box::into_raw(Box::new(KiwiConstraint(constraint)))
+}
+
+/// Release a constraint handle.
+///
+/// # Safety
+///
+/// constraint must be null or a handle returned by kiwi_constraint_new which was not released.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_constraint_del(constraint: *mut KiwiConstraint) {
+    if !constraint.is_null() {
+        drop(Box::from_raw(constraint));
+    }
+}
+
+/// Get the strength of the constraint.
+///
+/// NaN is returned for a null constraint.
+///
+/// # Safety
+///
+/// constraint must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_constraint_strength(constraint: *const KiwiConstraint) -> f64 {
+    if constraint.is_null() {
+        return f64::NAN;
+    }
+    (*constraint).0.strength()
+}
+
+/// Check whether the current values of the variables violate the constraint.
+///
+/// A null constraint is not violated.
+///
+/// # Safety
+///
+/// constraint must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_constraint_violated(constraint: *const KiwiConstraint) -> bool {
+    if constraint.is_null() {
+        return false;
+    }
+    let constraint = &(*constraint).0;
+    let value = constraint.expression().value();
+    match constraint.op() {
+        RelationalOperator::Equal => !near_zero(value, EPSILON),
+        RelationalOperator::LessEqual => value > 0.0,
+        RelationalOperator::GreaterEqual => value < 0.0,
+    }
+}
+
+/// Create a solver.
+#[no_mangle]
+pub extern "C" fn kiwi_solver_new() -> *mut KiwiSolver {
+    Box::into_raw(Box::new(KiwiSolver(Solver::new())))
+}
+
+/// Release a solver.
+///
+/// # Safety
+///
+/// solver must be null or a handle returned by kiwi_solver_new which was not released.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_del(solver: *mut KiwiSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Add a constraint to the solver.
+///
+/// # Safety
+///
+/// solver and constraint must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_add_constraint(
+    solver: *mut KiwiSolver,
+    constraint: *const KiwiConstraint,
+) -> KiwiResult {
+    if solver.is_null() || constraint.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    let constraint = &(*constraint).0;
+    guard(|| solver.add_constraint(constraint.clone()))
+}
+
+/// Remove a constraint from the solver.
+///
+/// # Safety
+///
+/// solver and constraint must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_remove_constraint(
+    solver: *mut KiwiSolver,
+    constraint: *const KiwiConstraint,
+) -> KiwiResult {
+    if solver.is_null() || constraint.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    let constraint = &(*constraint).0;
+    guard(|| solver.remove_constraint(constraint))
+}
+
+/// Test whether a constraint has been added to the solver.
+///
+/// False is returned if one of the handles is null.
+///
+/// # Safety
+///
+/// solver and constraint must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_has_constraint(
+    solver: *const KiwiSolver,
+    constraint: *const KiwiConstraint,
+) -> bool {
+    if solver.is_null() || constraint.is_null() {
+        return false;
+    }
+    (*solver).0.has_constraint(&(*constraint).0)
+}
+
+/// Add an edit variable to the solver.
+///
+/// # Safety
+///
+/// solver and variable must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_add_edit_variable(
+    solver: *mut KiwiSolver,
+    variable: *const KiwiVariable,
+    strength: f64,
+) -> KiwiResult {
+    if solver.is_null() || variable.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    let variable = &(*variable).0;
+    guard(|| solver.add_edit_variable(variable, strength))
+}
+
+/// Remove an edit variable from the solver.
+///
+/// # Safety
+///
+/// solver and variable must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_remove_edit_variable(
+    solver: *mut KiwiSolver,
+    variable: *const KiwiVariable,
+) -> KiwiResult {
+    if solver.is_null() || variable.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    let variable = &(*variable).0;
+    guard(|| solver.remove_edit_variable(variable))
+}
+
+/// Test whether a variable is an edit variable of the solver.
+///
+/// False is returned if one of the handles is null.
+///
+/// # Safety
+///
+/// solver and variable must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_has_edit_variable(
+    solver: *const KiwiSolver,
+    variable: *const KiwiVariable,
+) -> bool {
+    if solver.is_null() || variable.is_null() {
+        return false;
+    }
+    (*solver).0.has_edit_variable(&(*variable).0)
+}
+
+/// Suggest a value for an edit variable.
+///
+/// # Safety
+///
+/// solver and variable must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_suggest_value(
+    solver: *mut KiwiSolver,
+    variable: *const KiwiVariable,
+    value: f64,
+) -> KiwiResult {
+    if solver.is_null() || variable.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    let variable = &(*variable).0;
+    guard(|| solver.suggest_value(variable, value))
+}
+
+/// Update the values of the variables known to the solver.
+///
+/// # Safety
+///
+/// solver must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_update_variables(solver: *mut KiwiSolver) -> KiwiResult {
+    if solver.is_null() {
+        return KiwiResult::NullHandle;
+    }
+    let solver = &mut (*solver).0;
+    guard(|| {
+        solver.update_variable();
+        Ok(())
+    })
+}
+
+/// Remove all the constraints and edit variables from the solver.
+///
+/// Nothing is done for a null solver.
+///
+/// # Safety
+///
+/// solver must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn kiwi_solver_reset(solver: *mut KiwiSolver) {
+    if !solver.is_null() {
+        (*solver).0.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_strength_constants() {
+        assert_eq!(KIWI_STRENGTH_REQUIRED, strength::REQUIRED);
+        assert_eq!(KIWI_STRENGTH_STRONG, strength::STRONG);
+        assert_eq!(KIWI_STRENGTH_MEDIUM, strength::MEDIUM);
+        assert_eq!(KIWI_STRENGTH_WEAK, strength::WEAK);
+    }
+
+    #[test]
+    fn test_solver() {
+        unsafe {
+            let name = CString::new("x").unwrap();
+            let x = kiwi_variable_new(name.as_ptr());
+            let solver = kiwi_solver_new();
+
+            let terms = [KiwiTerm {
+                variable: x,
+                coefficient: 1.0,
+            }];
+            let cn = kiwi_constraint_new(
+                terms.as_ptr(),
+                1,
+                -10.0,
+                KiwiOp::GreaterEqual,
+                KIWI_STRENGTH_REQUIRED,
+            );
+            assert_eq!(kiwi_solver_add_constraint(solver, cn), KiwiResult::Ok);
+            assert_eq!(
+                kiwi_solver_add_constraint(solver, cn),
+                KiwiResult::DuplicateConstraint
+            );
+            assert_eq!(
+                kiwi_solver_add_edit_variable(solver, x, KIWI_STRENGTH_REQUIRED),
+                KiwiResult::BadRequiredStrength
+            );
+            assert_eq!(
+                kiwi_solver_add_edit_variable(solver, x, KIWI_STRENGTH_STRONG),
+                KiwiResult::Ok
+            );
+            assert_eq!(kiwi_solver_suggest_value(solver, x, 3.0), KiwiResult::Ok);
+            assert_eq!(kiwi_solver_update_variables(solver), KiwiResult::Ok);
+            assert_eq!(kiwi_variable_value(x), 10.0);
+            assert!(!kiwi_constraint_violated(cn));
+
+            // Equalities are compared with the tolerance used by kiwi.
+            let eq = kiwi_constraint_new(
+                terms.as_ptr(),
+                1,
+                -10.0 - 1e-12,
+                KiwiOp::Equal,
+                KIWI_STRENGTH_WEAK,
+            );
+            assert!(!kiwi_constraint_violated(eq));
+            kiwi_constraint_del(eq);
+
+            // The constraint stays alive in the solver after its handle is released.
+            kiwi_constraint_del(cn);
+            assert_eq!(kiwi_solver_suggest_value(solver, x, 20.0), KiwiResult::Ok);
+            assert_eq!(kiwi_solver_update_variables(solver), KiwiResult::Ok);
+            assert_eq!(kiwi_variable_value(x), 20.0);
+
+            assert_eq!(
+                kiwi_solver_add_constraint(solver, ptr::null()),
+                KiwiResult::NullHandle
+            );
+            kiwi_solver_del(solver);
+            kiwi_variable_del(x);
+        }
+    }
+
+    #[test]
+    fn test_variable_name() {
+        unsafe {
+            let x = kiwi_variable_new(ptr::null());
+            let name = CString::new("width").unwrap();
+            kiwi_variable_set_name(x, name.as_ptr());
+
+            let mut buffer = [1 as c_char; 4];
+            assert_eq!(kiwi_variable_name(x, buffer.as_mut_ptr(), buffer.len()), 5);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "wid");
+            assert_eq!(kiwi_variable_name(x, ptr::null_mut(), 0), 5);
+            kiwi_variable_del(x);
+        }
+    }
+
+    #[test]
+    fn test_null_handles() {
+        unsafe {
+            let solver = kiwi_solver_new();
+            let x = kiwi_variable_new(ptr::null());
+            assert_eq!(kiwi_variable_name(ptr::null(), ptr::null_mut(), 0), 0);
+            kiwi_variable_set_name(ptr::null(), ptr::null());
+            kiwi_variable_set_name(x, ptr::null());
+            assert!(kiwi_variable_value(ptr::null()).is_nan());
+            assert!(
+                kiwi_constraint_new(ptr::null(), 1, 0.0, KiwiOp::Equal, KIWI_STRENGTH_WEAK)
+                    .is_null()
+            );
+            assert!(kiwi_constraint_strength(ptr::null()).is_nan());
+            assert!(!kiwi_constraint_violated(ptr::null()));
+            assert!(!kiwi_solver_has_constraint(solver, ptr::null()));
+            assert!(!kiwi_solver_has_constraint(ptr::null(), ptr::null()));
+            assert!(!kiwi_solver_has_edit_variable(solver, ptr::null()));
+            assert!(!kiwi_solver_has_edit_variable(ptr::null(), x));
+            kiwi_solver_reset(ptr::null_mut());
+            kiwi_solver_del(solver);
+            kiwi_variable_del(x);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Numerical settings of the solver.
+//!
+//! The default values reproduce the behavior of Kiwi which is tuned for layouts expressed in
+//! pixels. Systems expressed in very different units (micrometres, meters, ...) may need to
+//! adjust the tolerances.
+//!
+
+use crate::util::EPSILON;
+
+/// How the strengths of non-required constraints are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrengthMode {
+    /// Strengths are used as weights of a single objective (default). Many weak constraints
+    /// can override a strong one.
+    Weighted,
+    /// Strengths are split into levels (see strength::split) which are optimized in order.
+    /// No amount of weaker constraints can override a stronger one.
+    Lexicographic,
+}
+
+/// Tolerances and limits used by the solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverConfig {
+    m_zero_tolerance: f64,
+    m_pivot_tolerance: f64,
+    m_feasibility_tolerance: f64,
+    m_max_pivots: Option<usize>,
+    m_max_branch_nodes: usize,
+    m_disjunction_hysteresis: f64,
+    m_strength_mode: StrengthMode,
+}
+
+impl SolverConfig {
+    /// Create a configuration using the default tolerances and no pivot limit.
+    pub fn new() -> SolverConfig {
+        SolverConfig {
+            m_zero_tolerance: EPSILON,
+            m_pivot_tolerance: 0.0,
+            m_feasibility_tolerance: EPSILON,
+            m_max_pivots: None,
+            m_max_branch_nodes: 1000,
+            m_disjunction_hysteresis: 1.0,
+            m_strength_mode: StrengthMode::Weighted,
+        }
+    }
+
+    /// Access the tolerance below which a coefficient is considered to be zero.
+    ///
+    /// Cells of the tableau whose coefficient falls below this value are pruned.
+    pub fn zero_tolerance(&self) -> f64 {
+        self.m_zero_tolerance
+    }
+
+    /// Set the tolerance below which a coefficient is considered to be zero.
+    pub fn set_zero_tolerance(&mut self, tolerance: f64) -> f64 {
+        let old = self.m_zero_tolerance;
+        self.m_zero_tolerance = f64::max(0.0, tolerance);
+        old
+    }
+
+    /// Access the magnitude a coefficient must exceed to be used as a pivot.
+    ///
+    /// The default value of zero accepts any non-zero coefficient.
+    pub fn pivot_tolerance(&self) -> f64 {
+        self.m_pivot_tolerance
+    }
+
+    /// Set the magnitude a coefficient must exceed to be used as a pivot.
+    pub fn set_pivot_tolerance(&mut self, tolerance: f64) -> f64 {
+        let old = self.m_pivot_tolerance;
+        self.m_pivot_tolerance = f64::max(0.0, tolerance);
+        old
+    }
+
+    /// Access the amount by which the constant of a row can be negative and still be feasible.
+    pub fn feasibility_tolerance(&self) -> f64 {
+        self.m_feasibility_tolerance
+    }
+
+    /// Set the amount by which the constant of a row can be negative and still be feasible.
+    pub fn set_feasibility_tolerance(&mut self, tolerance: f64) -> f64 {
+        let old = self.m_feasibility_tolerance;
+        self.m_feasibility_tolerance = f64::max(0.0, tolerance);
+        old
+    }
+
+    /// Access the maximum number of pivots allowed in a single optimization.
+    ///
+    /// None means that the number of pivots is not limited.
+    pub fn max_pivots(&self) -> Option<usize> {
+        self.m_max_pivots
+    }
+
+    /// Set the maximum number of pivots allowed in a single optimization.
+    ///
+    /// When the limit is reached, the operation that triggered the optimization returns
+    /// `KiwiError::PivotLimitReached`. The tableau remains consistent but the solution may not
+    /// be optimal. Passing None removes the limit.
+    pub fn set_max_pivots(&mut self, limit: Option<usize>) -> Option<usize> {
+        let old = self.m_max_pivots;
+        self.m_max_pivots = limit;
+        old
+    }
+
+    /// Access the maximum number of nodes explored by a branch-and-bound search.
+    pub fn max_branch_nodes(&self) -> usize {
+        self.m_max_branch_nodes
+    }
+
+    /// Set the maximum number of nodes explored by a branch-and-bound search.
+    ///
+    /// When the limit is reached, the best integer solution found so far is used.
+    pub fn set_max_branch_nodes(&mut self, limit: usize) -> usize {
+        let old = self.m_max_branch_nodes;
+        self.m_max_branch_nodes = limit;
+        old
+    }
+
+    /// Access the objective decrease required to switch the alternative of a disjunction.
+    ///
+    /// The default value of 1 corresponds to a pixel of error of a weak constraint.
+    pub fn disjunction_hysteresis(&self) -> f64 {
+        self.m_disjunction_hysteresis
+    }
+
+    /// Set the objective decrease required to switch the alternative of a disjunction.
+    ///
+    /// A positive value avoids flip-flopping between two alternatives of almost equal
+    /// objective while an edit variable is dragged.
+    pub fn set_disjunction_hysteresis(&mut self, hysteresis: f64) -> f64 {
+        let old = self.m_disjunction_hysteresis;
+        self.m_disjunction_hysteresis = f64::max(0.0, hysteresis);
+        old
+    }
+
+    /// Access the way strengths are compared.
+    pub fn strength_mode(&self) -> StrengthMode {
+        self.m_strength_mode
+    }
+
+    /// Set the way strengths are compared.
+    ///
+    /// The mode of a solver cannot change once a constraint has been added.
+    pub fn set_strength_mode(&mut self, mode: StrengthMode) -> StrengthMode {
+        let old = self.m_strength_mode;
+        self.m_strength_mode = mode;
+        old
+    }
+}
+
+impl Default for SolverConfig {
+    /// Create a configuration using the default tolerances and no pivot limit.
+    fn default() -> Self {
+        SolverConfig::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{SolverConfig, StrengthMode};
+
+    #[test]
+    fn test_default() {
+        let config = SolverConfig::default();
+        assert_eq!(config.zero_tolerance(), 1.0e-8);
+        assert_eq!(config.pivot_tolerance(), 0.0);
+        assert_eq!(config.feasibility_tolerance(), 1.0e-8);
+        assert_eq!(config.max_pivots(), None);
+        assert_eq!(config.max_branch_nodes(), 1000);
+        assert_eq!(config.disjunction_hysteresis(), 1.0);
+        assert_eq!(config.strength_mode(), StrengthMode::Weighted);
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut config = SolverConfig::new();
+        assert_eq!(config.set_zero_tolerance(1.0e-12), 1.0e-8);
+        assert_eq!(config.zero_tolerance(), 1.0e-12);
+        assert_eq!(config.set_pivot_tolerance(-1.0), 0.0);
+        assert_eq!(config.pivot_tolerance(), 0.0);
+        assert_eq!(config.set_max_pivots(Some(10)), None);
+        assert_eq!(config.max_pivots(), Some(10));
+        assert_eq!(config.set_max_branch_nodes(10), 1000);
+        assert_eq!(config.max_branch_nodes(), 10);
+    }
+}
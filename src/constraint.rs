@@ -1,12 +1,12 @@
 use crate::expression::Expression;
 use crate::strength::clip;
 use crate::term::Term;
-use std::cell::Ref;
-use std::cell::RefCell;
+use crate::variable::{Variable, VariableHandle};
 use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
-use std::rc::Rc;
+use std::ops::Deref;
+use std::sync::Arc;
 
 /// The comparison operators that can be used in a constraint.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,7 +17,7 @@ pub enum RelationalOperator {
 }
 
 /// Helper function concatenating the terms of an expression involving the same constant.
-fn reduce(expr: Expression) -> Expression {
+fn reduce<V: VariableHandle>(expr: Expression<V>) -> Expression<V> {
     // FIXME use a cheaper hash algorithm than the default
     // A custom hasher simply inspecting the pointer of the VariableData should be
     // quite efficient
@@ -35,19 +35,19 @@ fn reduce(expr: Expression) -> Expression {
 
 /// Internal data associated with a constraint.
 #[derive(Debug)]
-struct ConstraintData {
-    m_expression: Expression,
+struct ConstraintData<V> {
+    m_expression: Expression<V>,
     m_strength: f64,
     m_op: RelationalOperator,
 }
 
-impl ConstraintData {
+impl<V: VariableHandle> ConstraintData<V> {
     /// Create a constraint data from an expression, a comparison and a strength.
     ///
     /// # Note
     /// - the Expression is first reduced to eliminate duplicate use of a variable
     /// - the strength is clipped to be below the strength::REQUIRED value.
-    fn new(expr: Expression, op: RelationalOperator, strength: f64) -> ConstraintData {
+    fn new(expr: Expression<V>, op: RelationalOperator, strength: f64) -> ConstraintData<V> {
         let reduced_expr = reduce(expr);
         let clipped = clip(strength);
         ConstraintData {
@@ -58,8 +58,8 @@ impl ConstraintData {
     }
 
     /// Create a new constraint data from an existing one and a strength.
-    fn from(constraint: &Constraint, strength: f64) -> ConstraintData {
-        let data = constraint.m_data.borrow();
+    fn from(constraint: &Constraint<V>, strength: f64) -> ConstraintData<V> {
+        let data = &constraint.m_data;
         let clipped = clip(strength);
         ConstraintData {
             m_expression: data.m_expression.clone(),
@@ -69,81 +69,172 @@ impl ConstraintData {
     }
 }
 
+/// Shared access to the expression of a constraint (see Constraint::expression).
+pub struct ExpressionRef<'a, V> {
+    m_expression: &'a Expression<V>,
+}
+
+impl<V> Deref for ExpressionRef<'_, V> {
+    type Target = Expression<V>;
+
+    fn deref(&self) -> &Expression<V> {
+        self.m_expression
+    }
+}
+
 /// Representation of a constraint in the solver.
+///
+/// The data of the constraint is shared by its clones and is never modified, the solvers
+/// keep track of their own strength and constant (see Solver::set_strength and
+/// Solver::set_constant). It is reference counted atomically so that constraints on VarId
+/// handles (and the solvers holding them) can be sent to another thread.
 #[derive(Debug, Clone)]
-pub struct Constraint {
-    m_data: Rc<RefCell<ConstraintData>>,
+pub struct Constraint<V = Variable> {
+    m_data: Arc<ConstraintData<V>>,
 }
 
-impl Constraint {
+impl<V: VariableHandle> Constraint<V> {
     /// Create a constraint data from an expression, a comparison and a strength.
-    pub fn new(expr: Expression, op: RelationalOperator, strength: f64) -> Constraint {
+    pub fn new(expr: Expression<V>, op: RelationalOperator, strength: f64) -> Constraint<V> {
         let data = ConstraintData::new(expr, op, strength);
         Constraint {
-            m_data: Rc::new(RefCell::new(data)),
+            m_data: Arc::new(data),
         }
     }
 
     /// Create a new constraint from an existing one and a strength.
-    pub fn from(constraint: &Constraint, strength: f64) -> Constraint {
+    pub fn from(constraint: &Constraint<V>, strength: f64) -> Constraint<V> {
         let data = ConstraintData::from(constraint, strength);
         Constraint {
-            m_data: Rc::new(RefCell::new(data)),
+            m_data: Arc::new(data),
         }
     }
 
     /// Access the Expression used by the constraint.
-    pub fn expression(&self) -> Ref<Expression> {
-        Ref::map(self.m_data.borrow(), |borrow| &borrow.m_expression)
+    pub fn expression(&self) -> ExpressionRef<'_, V> {
+        ExpressionRef {
+            m_expression: &self.m_data.m_expression,
+        }
     }
 
     /// Access the comparison operator used in the constraint.
     pub fn op(&self) -> RelationalOperator {
-        self.m_data.borrow().m_op
+        self.m_data.m_op
     }
 
     /// Access the strength of the conastraint.
     pub fn strength(&self) -> f64 {
-        self.m_data.borrow().m_strength
+        self.m_data.m_strength
     }
 }
 
 // Constraint are compared based on the data they point to.
-impl cmp::PartialEq for Constraint {
+impl<V> cmp::PartialEq for Constraint<V> {
     fn eq(&self, other: &Self) -> bool {
-        Rc::ptr_eq(&self.m_data, &other.m_data)
+        Arc::ptr_eq(&self.m_data, &other.m_data)
     }
 }
 
-impl cmp::Eq for Constraint {}
+impl<V> cmp::Eq for Constraint<V> {}
 
-impl cmp::Ord for Constraint {
+impl<V> cmp::Ord for Constraint<V> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.m_data.as_ptr().cmp(&other.m_data.as_ptr())
+        Arc::as_ptr(&self.m_data).cmp(&Arc::as_ptr(&other.m_data))
     }
 }
 
 // Constraint are ordered based on the pointer value of the data they refer to.
-impl cmp::PartialOrd for Constraint {
+impl<V> cmp::PartialOrd for Constraint<V> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl fmt::Display for Constraint {
+impl<V: VariableHandle> fmt::Display for Constraint<V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = &self.m_data;
         f.write_fmt(format_args!(
             "{} {} | strength = {}",
-            self.expression(),
-            match self.op() {
+            data.m_expression,
+            match data.m_op {
                 RelationalOperator::Equal => String::from("== 0"),
                 RelationalOperator::GreaterEqual => String::from(">= 0"),
                 RelationalOperator::LessEqual => String::from("<= 0"),
             },
-            self.strength(),
+            data.m_strength,
         ))
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+
+    use super::{Constraint, RelationalOperator};
+    use crate::expression::Expression;
+    use crate::strength::{REQUIRED, STRONG, WEAK};
+    use crate::term::Term;
+    use crate::variable::Variable;
+
+    #[test]
+    fn test_constraint() {
+        let x = Variable::new_with_name("x");
+        let c = Constraint::new(&x - 1.0, RelationalOperator::GreaterEqual, STRONG);
+        assert_eq!(c.op(), RelationalOperator::GreaterEqual);
+        assert_eq!(c.strength(), STRONG);
+        assert_eq!(c.expression().constant(), -1.0);
+        assert_eq!(c.expression().terms().len(), 1);
+        assert_eq!(*c.expression().terms()[0].variable(), x);
+
+        // Strengths are clipped to the required strength.
+        let c = Constraint::new(&x - 1.0, RelationalOperator::Equal, 2.0 * REQUIRED);
+        assert_eq!(c.strength(), REQUIRED);
+    }
+
+    #[test]
+    fn test_constraint_reduction() {
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        let expr = Expression::new(
+            vec![
+                Term::new(x.clone(), 2.0),
+                Term::new(y.clone(), 1.0),
+                Term::new(x.clone(), -1.0),
+            ],
+            3.0,
+        );
+        let c = Constraint::new(expr, RelationalOperator::LessEqual, REQUIRED);
+        let expr = c.expression();
+        assert_eq!(expr.terms().len(), 2);
+        for term in expr.terms().iter() {
+            if *term.variable() == x {
+                assert_eq!(term.coefficient(), 1.0);
+            } else {
+                assert_eq!(*term.variable(), y);
+                assert_eq!(term.coefficient(), 1.0);
+            }
+        }
+        assert_eq!(expr.constant(), 3.0);
+    }
+
+    #[test]
+    fn test_constraint_from() {
+        let x = Variable::new_with_name("x");
+        let c = Constraint::new(&x - 1.0, RelationalOperator::Equal, REQUIRED);
+        let weak = Constraint::from(&c, WEAK);
+        assert_eq!(weak.strength(), WEAK);
+        assert_eq!(weak.op(), c.op());
+        assert_eq!(weak.expression().constant(), -1.0);
+        assert!(weak != c);
+
+        let strong = &c | STRONG;
+        assert_eq!(strong.strength(), STRONG);
+        assert!(strong != c);
+    }
+
+    #[test]
+    fn test_display() {
+        let x = Variable::new_with_name("x");
+        let c = Constraint::new(&x - 1.0, RelationalOperator::LessEqual, WEAK);
+        assert_eq!(format!("{}", c), "1 * x + -1 <= 0 | strength = 1");
+    }
+}
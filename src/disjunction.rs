@@ -0,0 +1,41 @@
+//! Disjunctions of constraints.
+//!
+//! A disjunction is a list of alternatives, each of them being a list of constraints, of which
+//! exactly one is enforced by the solver (the tooltip is either above or below its anchor).
+//! A single tableau cannot express such a choice so the solver speculatively adds each
+//! alternative, keeps the one leading to the lowest objective and reconsiders the choice when
+//! the suggested values of the edit variables change.
+//!
+
+/// Handle identifying a disjunction added to a solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DisjunctionHandle {
+    m_id: u64,
+}
+
+impl DisjunctionHandle {
+    /// Create a handle from a unique id.
+    pub(crate) fn new(id: u64) -> DisjunctionHandle {
+        DisjunctionHandle { m_id: id }
+    }
+
+    /// Access the handle id.
+    pub fn id(&self) -> u64 {
+        self.m_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::DisjunctionHandle;
+
+    #[test]
+    fn test_handle() {
+        let h1 = DisjunctionHandle::new(1);
+        let h2 = DisjunctionHandle::new(2);
+        assert_eq!(h1.id(), 1);
+        assert_ne!(h1, h2);
+        assert!(h1 < h2);
+    }
+}
@@ -1,51 +1,151 @@
 //! Errors that can be returned by the solver.
 
 use crate::constraint::Constraint;
-use crate::variable::Variable;
+use crate::disjunction::DisjunctionHandle;
+use crate::group::ConstraintGroup;
+use crate::objective::ObjectiveHandle;
+use crate::rule::RuleHandle;
+use crate::variable::{Variable, VariableHandle};
 use std::error::Error;
 use std::fmt;
 
 #[derive(Debug)]
-pub enum KiwiError {
+pub enum KiwiError<V = Variable> {
     /// A constraint cannot be satisfied
-    UnsatisfiableConstraint { constraint: Constraint },
+    UnsatisfiableConstraint { constraint: Constraint<V> },
 
     /// The constraint has not been added to the solver.
-    UnknownConstraint { constraint: Constraint },
+    UnknownConstraint { constraint: Constraint<V> },
 
     /// The constraint was already added to the solver.
-    DuplicateConstraint { constraint: Constraint },
+    DuplicateConstraint { constraint: Constraint<V> },
 
     /// The edit variable has not been added to the solver.
-    UnknownEditVariable { variable: Variable },
+    UnknownEditVariable { variable: V },
 
     /// The edit variable has already been added to the solver.
-    DuplicateEditVariable { variable: Variable },
+    DuplicateEditVariable { variable: V },
+
+    /// The variable has no stay constraint in the solver.
+    UnknownStayVariable { variable: V },
+
+    /// The variable already has a stay constraint in the solver.
+    DuplicateStayVariable { variable: V },
+
+    /// The bounds of the variable are empty or not numbers.
+    InvalidBounds { variable: V },
+
+    /// The variable has no bounds in the solver.
+    UnknownBounds { variable: V },
 
     /// A required strength cannot be used for this operation.
     BadRequiredStrength,
 
+    /// The solver exceeded the maximum number of pivots allowed for a single optimization.
+    PivotLimitReached { limit: usize },
+
+    /// The objective can be decreased without bound.
+    UnboundedObjective,
+
+    /// The objective has not been added to the solver.
+    UnknownObjective { objective: ObjectiveHandle },
+
+    /// None of the alternatives of the disjunction can be satisfied.
+    UnsatisfiableDisjunction,
+
+    /// The disjunction has not been added to the solver.
+    UnknownDisjunction { disjunction: DisjunctionHandle },
+
+    /// The group has not been added to the solver.
+    UnknownGroup { group: ConstraintGroup },
+
+    /// The rule has not been added to the solver.
+    UnknownRule { rule: RuleHandle },
+
+    /// Switching the branches of the rules never reaches a stable configuration.
+    RuleOscillation,
+
+    /// No integer solution satisfying the required constraints was found.
+    IntegerInfeasible,
+
+    /// The branch-and-bound search reached its node limit before finding an integer solution.
+    BranchNodeLimitReached { limit: usize },
+
+    /// The variable was not allocated by the solver.
+    UnknownVariable { variable: V },
+
     /// Something went awfully wrong with the solver.
     InternalSolverError { msg: String },
 }
 // Since the errors are well defined, small and do not need to be propagated a lot we use a simple
 // enum
 
-impl fmt::Display for KiwiError {
+impl<V: VariableHandle> fmt::Display for KiwiError<V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             KiwiError::BadRequiredStrength => {
                 write!(f, "A required strength cannot be used in this context.")
             }
+            KiwiError::PivotLimitReached { limit } => f.write_fmt(format_args!(
+                "The solver failed to converge within {} pivots.",
+                limit
+            )),
+            KiwiError::UnboundedObjective => write!(f, "The objective is unbounded."),
+            KiwiError::UnknownObjective { objective } => f.write_fmt(format_args!(
+                "The objective {} has not been added to the solver.",
+                objective.id()
+            )),
+            KiwiError::UnsatisfiableDisjunction => {
+                write!(f, "No alternative of the disjunction can be satisfied.")
+            }
+            KiwiError::UnknownDisjunction { disjunction } => f.write_fmt(format_args!(
+                "The disjunction {} has not been added to the solver.",
+                disjunction.id()
+            )),
+            KiwiError::UnknownGroup { group } => f.write_fmt(format_args!(
+                "The group {} has not been added to the solver.",
+                group.id()
+            )),
+            KiwiError::UnknownRule { rule } => f.write_fmt(format_args!(
+                "The rule {} has not been added to the solver.",
+                rule.id()
+            )),
+            KiwiError::RuleOscillation => {
+                write!(f, "The rules do not reach a stable configuration.")
+            }
+            KiwiError::IntegerInfeasible => write!(f, "No integer solution was found."),
+            KiwiError::BranchNodeLimitReached { limit } => f.write_fmt(format_args!(
+                "No integer solution was found within {} branch-and-bound nodes.",
+                limit
+            )),
+            KiwiError::UnknownVariable { variable } => f.write_fmt(format_args!(
+                "The variable {} was not allocated by the solver.",
+                variable
+            )),
             KiwiError::InternalSolverError { msg } => f.write_fmt(format_args!("{}", msg)),
             KiwiError::DuplicateEditVariable { variable } => f.write_fmt(format_args!(
                 "The edit variable {} has already been added to the solver..",
-                variable.name()
+                variable
             )),
             KiwiError::UnknownEditVariable { variable } => f.write_fmt(format_args!(
                 "The edit variable {} has not been added to the solver.",
-                variable.name()
+                variable
             )),
+            KiwiError::DuplicateStayVariable { variable } => f.write_fmt(format_args!(
+                "The variable {} already has a stay constraint.",
+                variable
+            )),
+            KiwiError::UnknownStayVariable { variable } => f.write_fmt(format_args!(
+                "The variable {} has no stay constraint.",
+                variable
+            )),
+            KiwiError::InvalidBounds { variable } => f.write_fmt(format_args!(
+                "The bounds of the variable {} are invalid.",
+                variable
+            )),
+            KiwiError::UnknownBounds { variable } => {
+                f.write_fmt(format_args!("The variable {} has no bounds.", variable))
+            }
             KiwiError::DuplicateConstraint { constraint } => f.write_fmt(format_args!(
                 "The constraint {} has already been added to the solver.",
                 constraint
@@ -62,19 +162,51 @@ impl fmt::Display for KiwiError {
     }
 }
 
-impl Error for KiwiError {}
+impl<V: VariableHandle> Error for KiwiError<V> {}
 
-#[cfg(tests)]
+#[cfg(test)]
 mod tests {
 
+    use super::KiwiError;
+    use crate::constraint::{Constraint, RelationalOperator};
+    use crate::strength::REQUIRED;
+    use crate::variable::Variable;
+
     #[test]
     fn test_error_display() {
-        format!("{}", KiwiError::BadRequiredStrength);
-        // XXX to be added once the solver is working !
-        // format!("{}", KiwiError::BadRequiredStrength);
-        // format!("{}", KiwiError::BadRequiredStrength);
-        // format!("{}", KiwiError::BadRequiredStrength);
-        // format!("{}", KiwiError::BadRequiredStrength);
-        // format!("{}", KiwiError::BadRequiredStrength);
+        assert_eq!(
+            format!("{}", KiwiError::<Variable>::BadRequiredStrength),
+            "A required strength cannot be used in this context."
+        );
+        assert_eq!(
+            format!("{}", KiwiError::<Variable>::PivotLimitReached { limit: 10 }),
+            "The solver failed to converge within 10 pivots."
+        );
+
+        let v = Variable::new_with_name("v");
+        assert_eq!(
+            format!(
+                "{}",
+                KiwiError::UnknownEditVariable {
+                    variable: v.clone()
+                }
+            ),
+            "The edit variable v has not been added to the solver."
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                KiwiError::InvalidBounds {
+                    variable: v.clone()
+                }
+            ),
+            "The bounds of the variable v are invalid."
+        );
+
+        let constraint = Constraint::new(&v - 1.0, RelationalOperator::Equal, REQUIRED);
+        let message = format!("{}", KiwiError::UnsatisfiableConstraint { constraint });
+        assert!(message.starts_with("The constraint "));
+        assert!(message.ends_with(" cannot be satisfied."));
+        assert!(message.contains("== 0"));
     }
 }
@@ -1,16 +1,17 @@
 use crate::term::Term;
+use crate::variable::Variable;
 use std::fmt;
 
 /// An expression represent a sum of term plus a constant.
 #[derive(Debug, Clone)]
-pub struct Expression {
-    m_terms: Vec<Term>,
+pub struct Expression<V = Variable> {
+    m_terms: Vec<Term<V>>,
     m_constant: f64,
 }
 
-impl Expression {
+impl<V> Expression<V> {
     /// Create an expression from a vector of terms and a floating point constant.
-    pub fn new(terms: Vec<Term>, constant: f64) -> Expression {
+    pub fn new(terms: Vec<Term<V>>, constant: f64) -> Expression<V> {
         Expression {
             m_terms: terms,
             m_constant: constant,
@@ -18,12 +19,12 @@ impl Expression {
     }
 
     /// Access the terms in the expression.
-    pub fn terms(&self) -> &Vec<Term> {
+    pub fn terms(&self) -> &Vec<Term<V>> {
         &self.m_terms
     }
 
     /// Mutable access the terms in the expression.
-    pub fn terms_mut(&mut self) -> &mut Vec<Term> {
+    pub fn terms_mut(&mut self) -> &mut Vec<Term<V>> {
         &mut self.m_terms
     }
 
@@ -32,6 +33,15 @@ impl Expression {
         self.m_constant
     }
 
+    /// Set the expression constant.
+    pub fn set_constant(&mut self, constant: f64) -> f64 {
+        let old = self.m_constant;
+        self.m_constant = constant;
+        old
+    }
+}
+
+impl Expression {
     /// Compute the expression value.
     pub fn value(&self) -> f64 {
         let mut value = self.m_constant;
@@ -42,7 +52,7 @@ impl Expression {
     }
 }
 
-impl fmt::Display for Expression {
+impl<V: fmt::Display> fmt::Display for Expression<V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_fmt(format_args!(
             "{}{}",
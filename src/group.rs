@@ -0,0 +1,39 @@
+//! Groups of constraints enabled and disabled as a unit.
+//!
+//! Responsive layouts switch between sets of constraints (compact and regular modes for
+//! example). The constraints of a disabled group are removed from the tableau in a single
+//! batch followed by one optimization, and added back when the group is enabled again.
+//!
+
+/// Handle identifying a group of constraints added to a solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConstraintGroup {
+    m_id: u64,
+}
+
+impl ConstraintGroup {
+    /// Create a handle from a unique id.
+    pub(crate) fn new(id: u64) -> ConstraintGroup {
+        ConstraintGroup { m_id: id }
+    }
+
+    /// Access the handle id.
+    pub fn id(&self) -> u64 {
+        self.m_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ConstraintGroup;
+
+    #[test]
+    fn test_handle() {
+        let h1 = ConstraintGroup::new(1);
+        let h2 = ConstraintGroup::new(2);
+        assert_eq!(h1.id(), 1);
+        assert_ne!(h1, h2);
+        assert!(h1 < h2);
+    }
+}
@@ -0,0 +1,16 @@
+//! Outcome of the search for integer solutions.
+//!
+//! Variables marked as integer are only taken into account by Solver::solve_integer which
+//! runs a branch-and-bound search on top of the simplex: each node of the search adds
+//! temporary required bounds on a fractional variable and the nodes whose relaxation is not
+//! better than the best integer solution found are pruned.
+//!
+
+/// Quality of the integer solution found by a branch-and-bound search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerStatus {
+    /// The solution is an optimal integer solution.
+    Optimal,
+    /// The search stopped at the node limit, the solution is the best one found so far.
+    NodeLimitReached,
+}
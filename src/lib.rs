@@ -1,22 +1,52 @@
+//! Implementation of the Cassowary constraint solving algorithm, ported from kiwi.
+//!
+//! Constraints are built from linear expressions of variables and added to a Solver, which
+//! computes the values of the variables satisfying the required constraints while minimizing
+//! the violation of the non-required ones according to their strength.
+//!
+//! Variables are either Variable handles, which share their name and value with their clones,
+//! or VarId handles allocated by a `Solver<VarId>` which stores the names and values itself.
+//! VarId handles are Copy, so building expressions does not touch any reference count, and a
+//! `Solver<VarId>` can be sent to another thread.
+//!
+
 #[macro_use]
 extern crate impl_ops;
 mod assoc_vec;
+#[cfg(feature = "capi")]
+mod capi;
+mod config;
 mod constraint;
+mod disjunction;
 mod errors;
 mod expression;
+mod group;
+mod integer;
+mod objective;
+#[cfg(feature = "python")]
+mod python;
+mod rounding;
 mod row;
+mod rule;
 mod solver;
-mod strength;
+pub mod strength;
 mod symbol;
 mod symbolics;
+mod tableau;
 mod term;
 mod util;
 mod variable;
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
-    }
-}
+pub use crate::config::{SolverConfig, StrengthMode};
+pub use crate::constraint::{Constraint, ExpressionRef, RelationalOperator};
+pub use crate::disjunction::DisjunctionHandle;
+pub use crate::errors::KiwiError;
+pub use crate::expression::Expression;
+pub use crate::group::ConstraintGroup;
+pub use crate::integer::IntegerStatus;
+pub use crate::objective::{ObjectiveHandle, Sense};
+pub use crate::rounding::RoundingPolicy;
+pub use crate::rule::{RuleBranch, RuleHandle};
+pub use crate::solver::Solver;
+pub use crate::term::Term;
+pub use crate::variable::{VarId, Variable, VariableHandle};
@@ -0,0 +1,65 @@
+//! Explicit linear objectives that can be added to the solver.
+//!
+//! On top of the implicit objective minimizing the weighted errors of the non-required
+//! constraints, the solver can minimize or maximize linear expressions. The strength of an
+//! objective is used to weight it against the errors of the constraints.
+//!
+
+/// Direction in which an objective expression is optimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sense {
+    /// Make the expression as small as possible.
+    Minimize,
+    /// Make the expression as large as possible.
+    Maximize,
+}
+
+impl Sense {
+    /// Factor by which the expression is multiplied in the minimized objective.
+    #[inline]
+    pub fn factor(self) -> f64 {
+        match self {
+            Sense::Minimize => 1.0,
+            Sense::Maximize => -1.0,
+        }
+    }
+}
+
+/// Handle identifying an objective added to a solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectiveHandle {
+    m_id: u64,
+}
+
+impl ObjectiveHandle {
+    /// Create a handle from a unique id.
+    pub(crate) fn new(id: u64) -> ObjectiveHandle {
+        ObjectiveHandle { m_id: id }
+    }
+
+    /// Access the handle id.
+    pub fn id(&self) -> u64 {
+        self.m_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{ObjectiveHandle, Sense};
+
+    #[test]
+    fn test_sense_factor() {
+        assert_eq!(Sense::Minimize.factor(), 1.0);
+        assert_eq!(Sense::Maximize.factor(), -1.0);
+    }
+
+    #[test]
+    fn test_handle() {
+        let h1 = ObjectiveHandle::new(1);
+        let h2 = ObjectiveHandle::new(2);
+        assert_eq!(h1.id(), 1);
+        assert_ne!(h1, h2);
+        assert!(h1 < h2);
+    }
+}
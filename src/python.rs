@@ -0,0 +1,764 @@
+//! Python bindings following the API of kiwisolver.
+//!
+//! The extension module is named kiwisolver so that code written for kiwisolver can run
+//! unchanged. As in kiwisolver, variables, terms and expressions are lightweight Python
+//! objects (a term refers to the Python variable it was created from) which are converted
+//! to the types of the crate when a constraint is created. The errors of the solver are
+//! raised as the exceptions defined by kiwisolver, with the offending constraint or
+//! variable as argument.
+//!
+//! The dump and dumps debugging methods of the kiwisolver Solver are not provided.
+//!
+
+// The code generated by pyo3 for methods returning PyResult triggers this lint.
+#![allow(clippy::useless_conversion)]
+
+use crate::constraint::{Constraint, RelationalOperator};
+use crate::errors::KiwiError;
+use crate::expression::Expression;
+use crate::solver::Solver;
+use crate::strength;
+use crate::term::Term;
+use crate::util::{near_zero, EPSILON};
+use crate::variable::Variable;
+use pyo3::basic::CompareOp;
+use pyo3::create_exception;
+use pyo3::exceptions::{
+    PyException, PyRuntimeError, PyTypeError, PyValueError, PyZeroDivisionError,
+};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+create_exception!(kiwisolver, UnsatisfiableConstraint, PyException);
+create_exception!(kiwisolver, UnknownConstraint, PyException);
+create_exception!(kiwisolver, DuplicateConstraint, PyException);
+create_exception!(kiwisolver, UnknownEditVariable, PyException);
+create_exception!(kiwisolver, DuplicateEditVariable, PyException);
+create_exception!(kiwisolver, BadRequiredStrength, PyException);
+
+/// Convert an error of the solver to the matching kiwisolver exception.
+///
+/// The argument is the Python object (constraint or variable) passed to the solver method.
+fn kiwi_error(error: KiwiError, argument: PyObject) -> PyErr {
+    match error {
+        KiwiError::UnsatisfiableConstraint { .. } => UnsatisfiableConstraint::new_err(argument),
+        KiwiError::UnknownConstraint { .. } => UnknownConstraint::new_err(argument),
+        KiwiError::DuplicateConstraint { .. } => DuplicateConstraint::new_err(argument),
+        KiwiError::UnknownEditVariable { .. } => UnknownEditVariable::new_err(argument),
+        KiwiError::DuplicateEditVariable { .. } => DuplicateEditVariable::new_err(argument),
+        KiwiError::BadRequiredStrength => {
+            BadRequiredStrength::new_err("A required strength cannot be used by an edit variable")
+        }
+        error => PyRuntimeError::new_err(error.to_string()),
+    }
+}
+
+/// Convert a strength given as a number or as a name to its value.
+fn extract_strength(value: &Bound<'_, PyAny>) -> PyResult<f64> {
+    if let Ok(name) = value.extract::<String>() {
+        match name.as_str() {
+            "required" => Ok(strength::REQUIRED),
+            "strong" => Ok(strength::STRONG),
+            "medium" => Ok(strength::MEDIUM),
+            "weak" => Ok(strength::WEAK),
+            _ => Err(PyValueError::new_err(
+                "string strength must be 'required', 'strong', 'medium', or 'weak'",
+            )),
+        }
+    } else {
+        value.extract::<f64>().map_err(|_| {
+            PyTypeError::new_err("strength must be a number or one of the strength names")
+        })
+    }
+}
+
+/// Convert a relational operator given as a string.
+fn extract_op(op: &str) -> PyResult<RelationalOperator> {
+    match op {
+        "==" => Ok(RelationalOperator::Equal),
+        "<=" => Ok(RelationalOperator::LessEqual),
+        ">=" => Ok(RelationalOperator::GreaterEqual),
+        _ => Err(PyValueError::new_err(
+            "relational operator must be '==', '<=', or '>='",
+        )),
+    }
+}
+
+/// Get the string representation of a relational operator.
+fn op_str(op: RelationalOperator) -> &'static str {
+    match op {
+        RelationalOperator::Equal => "==",
+        RelationalOperator::LessEqual => "<=",
+        RelationalOperator::GreaterEqual => ">=",
+    }
+}
+
+/// Operand of an arithmetic operation seen as terms and a constant.
+struct Operand {
+    terms: Vec<Py<PyTerm>>,
+    constant: f64,
+}
+
+impl Operand {
+    /// Convert a variable, a term, an expression or a number.
+    ///
+    /// None is returned for the other types so that Python can try the reflected operation.
+    fn extract(value: &Bound<'_, PyAny>) -> PyResult<Option<Operand>> {
+        let py = value.py();
+        if let Ok(variable) = value.downcast::<PyVariable>() {
+            let term = Py::new(
+                py,
+                PyTerm {
+                    m_variable: variable.clone().unbind(),
+                    m_coefficient: 1.0,
+                },
+            )?;
+            Ok(Some(Operand {
+                terms: vec![term],
+                constant: 0.0,
+            }))
+        } else if let Ok(term) = value.downcast::<PyTerm>() {
+            Ok(Some(Operand {
+                terms: vec![term.clone().unbind()],
+                constant: 0.0,
+            }))
+        } else if let Ok(expression) = value.downcast::<PyExpression>() {
+            let expression = expression.borrow();
+            Ok(Some(Operand {
+                terms: expression.m_terms.iter().map(|t| t.clone_ref(py)).collect(),
+                constant: expression.m_constant,
+            }))
+        } else if let Ok(number) = value.extract::<f64>() {
+            Ok(Some(Operand {
+                terms: Vec::new(),
+                constant: number,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Multiply the operand by a factor, creating new terms.
+    fn scale(self, py: Python<'_>, factor: f64) -> PyResult<Operand> {
+        let mut terms = Vec::with_capacity(self.terms.len());
+        for term in self.terms.iter() {
+            let term = term.borrow(py);
+            terms.push(Py::new(
+                py,
+                PyTerm {
+                    m_variable: term.m_variable.clone_ref(py),
+                    m_coefficient: term.m_coefficient * factor,
+                },
+            )?);
+        }
+        Ok(Operand {
+            terms,
+            constant: self.constant * factor,
+        })
+    }
+
+    /// Build the expression of a + sign * b, None if one operand is not supported.
+    fn combine(
+        a: &Bound<'_, PyAny>,
+        b: &Bound<'_, PyAny>,
+        sign: f64,
+    ) -> PyResult<Option<PyExpression>> {
+        let py = a.py();
+        match (Operand::extract(a)?, Operand::extract(b)?) {
+            (Some(a), Some(b)) => {
+                let b = if sign == 1.0 { b } else { b.scale(py, sign)? };
+                let mut terms = a.terms;
+                terms.extend(b.terms);
+                Ok(Some(PyExpression {
+                    m_terms: terms,
+                    m_constant: a.constant + b.constant,
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Build the constraint a op b, None if one operand is not supported.
+    fn compare(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>, op: CompareOp) -> PyResult<PyObject> {
+        let py = a.py();
+        let op = match op {
+            CompareOp::Eq => RelationalOperator::Equal,
+            CompareOp::Le => RelationalOperator::LessEqual,
+            CompareOp::Ge => RelationalOperator::GreaterEqual,
+            _ => {
+                let symbol = match op {
+                    CompareOp::Lt => "<",
+                    CompareOp::Gt => ">",
+                    _ => "!=",
+                };
+                return Err(PyTypeError::new_err(format!(
+                    "unsupported operand type(s) for {}: '{}' and '{}'",
+                    symbol,
+                    a.get_type().name()?,
+                    b.get_type().name()?
+                )));
+            }
+        };
+        match Operand::combine(a, b, -1.0)? {
+            Some(expression) => {
+                let expression = Py::new(py, expression)?;
+                Ok(PyConstraint::create(py, expression, op, strength::REQUIRED)?.into_py(py))
+            }
+            None => Ok(py.NotImplemented()),
+        }
+    }
+}
+
+/// Multiply a variable, a term or an expression by a number.
+fn multiply(value: &Bound<'_, PyAny>, factor: f64) -> PyResult<PyObject> {
+    let py = value.py();
+    if let Ok(variable) = value.downcast::<PyVariable>() {
+        let term = PyTerm {
+            m_variable: variable.clone().unbind(),
+            m_coefficient: factor,
+        };
+        Ok(Py::new(py, term)?.into_py(py))
+    } else if let Ok(term) = value.downcast::<PyTerm>() {
+        let term = term.borrow();
+        let term = PyTerm {
+            m_variable: term.m_variable.clone_ref(py),
+            m_coefficient: term.m_coefficient * factor,
+        };
+        Ok(Py::new(py, term)?.into_py(py))
+    } else {
+        // Unwrapping is safe since the callers only pass expressions otherwise.
+        let operand = Operand::extract(value)?.unwrap().scale(py, factor)?;
+        let expression = PyExpression {
+            m_terms: operand.terms,
+            m_constant: operand.constant,
+        };
+        Ok(Py::new(py, expression)?.into_py(py))
+    }
+}
+
+/// Multiply by a number given as a Python object, NotImplemented for other objects.
+fn multiply_by(value: &Bound<'_, PyAny>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    match other.extract::<f64>() {
+        Ok(factor) => multiply(value, factor),
+        Err(_) => Ok(value.py().NotImplemented()),
+    }
+}
+
+/// Divide by a number given as a Python object, NotImplemented for other objects.
+fn divide_by(value: &Bound<'_, PyAny>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    match other.extract::<f64>() {
+        Ok(0.0) => Err(PyZeroDivisionError::new_err("float division by zero")),
+        Ok(divisor) => multiply(value, 1.0 / divisor),
+        Err(_) => Ok(value.py().NotImplemented()),
+    }
+}
+
+/// Add two operands, NotImplemented if one of them is not supported.
+fn add(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>, sign: f64) -> PyResult<PyObject> {
+    let py = a.py();
+    match Operand::combine(a, b, sign)? {
+        Some(expression) => Ok(Py::new(py, expression)?.into_py(py)),
+        None => Ok(py.NotImplemented()),
+    }
+}
+
+/// Implement the arithmetic and comparison operators shared by variables, terms and
+/// expressions.
+macro_rules! impl_arithmetic {
+    ($name: ident) => {
+        #[pymethods]
+        impl $name {
+            fn __add__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                add(slf.as_any(), other, 1.0)
+            }
+
+            fn __radd__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                add(other, slf.as_any(), 1.0)
+            }
+
+            fn __sub__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                add(slf.as_any(), other, -1.0)
+            }
+
+            fn __rsub__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                add(other, slf.as_any(), -1.0)
+            }
+
+            fn __mul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                multiply_by(slf.as_any(), other)
+            }
+
+            fn __rmul__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                multiply_by(slf.as_any(), other)
+            }
+
+            fn __truediv__(slf: &Bound<'_, Self>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+                divide_by(slf.as_any(), other)
+            }
+
+            fn __neg__(slf: &Bound<'_, Self>) -> PyResult<PyObject> {
+                multiply(slf.as_any(), -1.0)
+            }
+
+            fn __richcmp__(
+                slf: &Bound<'_, Self>,
+                other: &Bound<'_, PyAny>,
+                op: CompareOp,
+            ) -> PyResult<PyObject> {
+                Operand::compare(slf.as_any(), other, op)
+            }
+
+            // Defining __richcmp__ removes the default hash, objects are hashed by identity.
+            fn __hash__(slf: &Bound<'_, Self>) -> isize {
+                slf.as_ptr() as isize
+            }
+        }
+    };
+}
+
+/// Python wrapper of a variable, which can carry an arbitrary context object.
+#[pyclass(name = "Variable", module = "kiwisolver", unsendable)]
+struct PyVariable {
+    m_variable: Variable,
+    m_context: PyObject,
+}
+
+#[pymethods]
+impl PyVariable {
+    #[new]
+    #[pyo3(signature = (name = "", context = None))]
+    fn new(py: Python<'_>, name: &str, context: Option<PyObject>) -> PyVariable {
+        PyVariable {
+            m_variable: Variable::new_with_name(name),
+            m_context: context.unwrap_or_else(|| py.None()),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.m_variable.name().clone()
+    }
+
+    #[pyo3(name = "setName")]
+    fn set_name(&self, name: &str) {
+        self.m_variable.set_name(name);
+    }
+
+    fn context(&self, py: Python<'_>) -> PyObject {
+        self.m_context.clone_ref(py)
+    }
+
+    #[pyo3(name = "setContext")]
+    fn set_context(&mut self, context: PyObject) {
+        self.m_context = context;
+    }
+
+    fn value(&self) -> f64 {
+        *self.m_variable.value()
+    }
+
+    fn __repr__(&self) -> String {
+        self.m_variable.name().clone()
+    }
+}
+
+impl_arithmetic!(PyVariable);
+
+/// Python wrapper of a term, referring to the Python variable it was created from.
+#[pyclass(name = "Term", module = "kiwisolver")]
+struct PyTerm {
+    m_variable: Py<PyVariable>,
+    m_coefficient: f64,
+}
+
+#[pymethods]
+impl PyTerm {
+    #[new]
+    #[pyo3(signature = (variable, coefficient = 1.0))]
+    fn new(variable: Py<PyVariable>, coefficient: f64) -> PyTerm {
+        PyTerm {
+            m_variable: variable,
+            m_coefficient: coefficient,
+        }
+    }
+
+    fn variable(&self, py: Python<'_>) -> Py<PyVariable> {
+        self.m_variable.clone_ref(py)
+    }
+
+    fn coefficient(&self) -> f64 {
+        self.m_coefficient
+    }
+
+    fn value(&self, py: Python<'_>) -> f64 {
+        self.m_coefficient * self.m_variable.borrow(py).value()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "{} * {}",
+            self.m_coefficient,
+            self.m_variable.borrow(py).__repr__()
+        )
+    }
+}
+
+impl_arithmetic!(PyTerm);
+
+/// Python wrapper of an expression: a sum of terms and a constant.
+#[pyclass(name = "Expression", module = "kiwisolver")]
+struct PyExpression {
+    m_terms: Vec<Py<PyTerm>>,
+    m_constant: f64,
+}
+
+impl PyExpression {
+    /// Build the expression of the crate used by a constraint.
+    fn to_expression(&self, py: Python<'_>) -> Expression {
+        let terms = self
+            .m_terms
+            .iter()
+            .map(|term| {
+                let term = term.borrow(py);
+                let variable = term.m_variable.borrow(py).m_variable.clone();
+                Term::new(variable, term.m_coefficient)
+            })
+            .collect();
+        Expression::new(terms, self.m_constant)
+    }
+
+    /// Build an equivalent expression in which each variable appears once.
+    fn reduce(&self, py: Python<'_>) -> PyResult<PyExpression> {
+        let mut reduced: Vec<(Py<PyVariable>, f64)> = Vec::new();
+        for term in self.m_terms.iter() {
+            let term = term.borrow(py);
+            match reduced
+                .iter_mut()
+                .find(|(variable, _)| variable.is(&term.m_variable))
+            {
+                Some((_, coefficient)) => *coefficient += term.m_coefficient,
+                None => reduced.push((term.m_variable.clone_ref(py), term.m_coefficient)),
+            }
+        }
+        let mut terms = Vec::with_capacity(reduced.len());
+        for (variable, coefficient) in reduced.into_iter() {
+            terms.push(Py::new(
+                py,
+                PyTerm {
+                    m_variable: variable,
+                    m_coefficient: coefficient,
+                },
+            )?);
+        }
+        Ok(PyExpression {
+            m_terms: terms,
+            m_constant: self.m_constant,
+        })
+    }
+}
+
+#[pymethods]
+impl PyExpression {
+    #[new]
+    #[pyo3(signature = (terms, constant = 0.0))]
+    fn new(terms: &Bound<'_, PyAny>, constant: f64) -> PyResult<PyExpression> {
+        let mut py_terms = Vec::new();
+        for term in terms.iter()? {
+            let term = term?;
+            match term.downcast::<PyTerm>() {
+                Ok(term) => py_terms.push(term.clone().unbind()),
+                Err(_) => return Err(PyTypeError::new_err("Expected object of type `Term`")),
+            }
+        }
+        Ok(PyExpression {
+            m_terms: py_terms,
+            m_constant: constant,
+        })
+    }
+
+    fn terms<'py>(&self, py: Python<'py>) -> Bound<'py, PyTuple> {
+        PyTuple::new_bound(py, self.m_terms.iter().map(|term| term.clone_ref(py)))
+    }
+
+    fn constant(&self) -> f64 {
+        self.m_constant
+    }
+
+    fn value(&self, py: Python<'_>) -> f64 {
+        self.m_constant
+            + self
+                .m_terms
+                .iter()
+                .map(|term| term.borrow(py).value(py))
+                .sum::<f64>()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        let mut repr = String::new();
+        for term in self.m_terms.iter() {
+            repr.push_str(&term.borrow(py).__repr__(py));
+            repr.push_str(" + ");
+        }
+        repr.push_str(&self.m_constant.to_string());
+        repr
+    }
+}
+
+impl_arithmetic!(PyExpression);
+
+/// Python wrapper of a constraint.
+#[pyclass(name = "Constraint", module = "kiwisolver", unsendable)]
+struct PyConstraint {
+    m_expression: Py<PyExpression>,
+    m_constraint: Constraint,
+}
+
+impl PyConstraint {
+    /// Create a constraint from an expression which is reduced first.
+    fn create(
+        py: Python<'_>,
+        expression: Py<PyExpression>,
+        op: RelationalOperator,
+        strength: f64,
+    ) -> PyResult<Py<PyConstraint>> {
+        let reduced = expression.borrow(py).reduce(py)?;
+        let constraint = Constraint::new(reduced.to_expression(py), op, strength);
+        Py::new(
+            py,
+            PyConstraint {
+                m_expression: Py::new(py, reduced)?,
+                m_constraint: constraint,
+            },
+        )
+    }
+
+    /// Create a copy of the constraint using a different strength.
+    fn with_strength(&self, py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        // Unknown strength names are errors, other types let Python report the operation.
+        let strength = match extract_strength(value) {
+            Ok(strength) => strength,
+            Err(error) if error.is_instance_of::<PyValueError>(py) => return Err(error),
+            Err(_) => return Ok(py.NotImplemented()),
+        };
+        let constraint = PyConstraint {
+            m_expression: self.m_expression.clone_ref(py),
+            m_constraint: Constraint::from(&self.m_constraint, strength),
+        };
+        Ok(Py::new(py, constraint)?.into_py(py))
+    }
+}
+
+#[pymethods]
+impl PyConstraint {
+    #[new]
+    #[pyo3(signature = (expression, op = "==", strength = None))]
+    fn new(
+        py: Python<'_>,
+        expression: &Bound<'_, PyAny>,
+        op: &str,
+        strength: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Py<PyConstraint>> {
+        let expression = match expression.downcast::<PyExpression>() {
+            Ok(expression) => expression.clone().unbind(),
+            Err(_) => return Err(PyTypeError::new_err("Expected object of type `Expression`")),
+        };
+        let strength = match strength {
+            Some(value) => extract_strength(value)?,
+            None => strength::REQUIRED,
+        };
+        PyConstraint::create(py, expression, extract_op(op)?, strength)
+    }
+
+    fn expression(&self, py: Python<'_>) -> Py<PyExpression> {
+        self.m_expression.clone_ref(py)
+    }
+
+    fn op(&self) -> &'static str {
+        op_str(self.m_constraint.op())
+    }
+
+    fn strength(&self) -> f64 {
+        self.m_constraint.strength()
+    }
+
+    fn violated(&self) -> bool {
+        let value = self.m_constraint.expression().value();
+        match self.m_constraint.op() {
+            RelationalOperator::Equal => !near_zero(value, EPSILON),
+            RelationalOperator::LessEqual => value > 0.0,
+            RelationalOperator::GreaterEqual => value < 0.0,
+        }
+    }
+
+    fn __or__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.with_strength(py, other)
+    }
+
+    fn __ror__(&self, py: Python<'_>, other: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        self.with_strength(py, other)
+    }
+
+    fn __hash__(slf: &Bound<'_, Self>) -> isize {
+        slf.as_ptr() as isize
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> String {
+        format!(
+            "{} {} 0 | strength = {}",
+            self.m_expression.borrow(py).__repr__(py),
+            self.op(),
+            self.strength()
+        )
+    }
+}
+
+/// Python wrapper of the solver.
+#[pyclass(name = "Solver", module = "kiwisolver", unsendable)]
+struct PySolver {
+    m_solver: Solver,
+}
+
+#[pymethods]
+impl PySolver {
+    #[new]
+    fn new() -> PySolver {
+        PySolver {
+            m_solver: Solver::new(),
+        }
+    }
+
+    #[pyo3(name = "addConstraint")]
+    fn add_constraint(&mut self, constraint: &Bound<'_, PyConstraint>) -> PyResult<()> {
+        let cn = constraint.borrow().m_constraint.clone();
+        self.m_solver
+            .add_constraint(cn)
+            .map_err(|error| kiwi_error(error, constraint.clone().into_any().unbind()))
+    }
+
+    #[pyo3(name = "removeConstraint")]
+    fn remove_constraint(&mut self, constraint: &Bound<'_, PyConstraint>) -> PyResult<()> {
+        let cn = constraint.borrow().m_constraint.clone();
+        self.m_solver
+            .remove_constraint(&cn)
+            .map_err(|error| kiwi_error(error, constraint.clone().into_any().unbind()))
+    }
+
+    #[pyo3(name = "hasConstraint")]
+    fn has_constraint(&self, constraint: &Bound<'_, PyConstraint>) -> bool {
+        self.m_solver
+            .has_constraint(&constraint.borrow().m_constraint)
+    }
+
+    #[pyo3(name = "addEditVariable")]
+    fn add_edit_variable(
+        &mut self,
+        variable: &Bound<'_, PyVariable>,
+        strength: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let strength = extract_strength(strength)?;
+        let var = variable.borrow().m_variable.clone();
+        self.m_solver
+            .add_edit_variable(&var, strength)
+            .map_err(|error| kiwi_error(error, variable.clone().into_any().unbind()))
+    }
+
+    #[pyo3(name = "removeEditVariable")]
+    fn remove_edit_variable(&mut self, variable: &Bound<'_, PyVariable>) -> PyResult<()> {
+        let var = variable.borrow().m_variable.clone();
+        self.m_solver
+            .remove_edit_variable(&var)
+            .map_err(|error| kiwi_error(error, variable.clone().into_any().unbind()))
+    }
+
+    #[pyo3(name = "hasEditVariable")]
+    fn has_edit_variable(&self, variable: &Bound<'_, PyVariable>) -> bool {
+        self.m_solver
+            .has_edit_variable(&variable.borrow().m_variable)
+    }
+
+    #[pyo3(name = "suggestValue")]
+    fn suggest_value(&mut self, variable: &Bound<'_, PyVariable>, value: f64) -> PyResult<()> {
+        let var = variable.borrow().m_variable.clone();
+        self.m_solver
+            .suggest_value(&var, value)
+            .map_err(|error| kiwi_error(error, variable.clone().into_any().unbind()))
+    }
+
+    #[pyo3(name = "updateVariables")]
+    fn update_variables(&mut self) {
+        self.m_solver.update_variable();
+    }
+
+    fn reset(&mut self) {
+        self.m_solver.reset();
+    }
+}
+
+/// Namespace exposing the predefined strengths and the strength factory.
+#[pyclass(name = "strength", module = "kiwisolver")]
+struct PyStrength {}
+
+#[pymethods]
+impl PyStrength {
+    #[getter]
+    fn weak(&self) -> f64 {
+        strength::WEAK
+    }
+
+    #[getter]
+    fn medium(&self) -> f64 {
+        strength::MEDIUM
+    }
+
+    #[getter]
+    fn strong(&self) -> f64 {
+        strength::STRONG
+    }
+
+    #[getter]
+    fn required(&self) -> f64 {
+        strength::REQUIRED
+    }
+
+    #[pyo3(signature = (a, b, c, weight = 1.0))]
+    fn create(&self, a: f64, b: f64, c: f64, weight: f64) -> f64 {
+        strength::create(a, b, c, weight)
+    }
+}
+
+#[pymodule]
+fn kiwisolver(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("__kiwi_version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_class::<PyVariable>()?;
+    m.add_class::<PyTerm>()?;
+    m.add_class::<PyExpression>()?;
+    m.add_class::<PyConstraint>()?;
+    m.add_class::<PySolver>()?;
+    m.add("strength", Py::new(py, PyStrength {})?)?;
+    m.add(
+        "UnsatisfiableConstraint",
+        py.get_type_bound::<UnsatisfiableConstraint>(),
+    )?;
+    m.add(
+        "UnknownConstraint",
+        py.get_type_bound::<UnknownConstraint>(),
+    )?;
+    m.add(
+        "DuplicateConstraint",
+        py.get_type_bound::<DuplicateConstraint>(),
+    )?;
+    m.add(
+        "UnknownEditVariable",
+        py.get_type_bound::<UnknownEditVariable>(),
+    )?;
+    m.add(
+        "DuplicateEditVariable",
+        py.get_type_bound::<DuplicateEditVariable>(),
+    )?;
+    m.add(
+        "BadRequiredStrength",
+        py.get_type_bound::<BadRequiredStrength>(),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Rounding of a continuous solution to whole pixels.
+//!
+//! Rounding each variable independently breaks the required equalities linking them (the
+//! widths of three columns sharing 100 pixels are 33.33 each and round to a total of 99).
+//! Solver::round_solution instead propagates the equalities and shares the remainders of
+//! the variables of an equality using the largest remainder method.
+//!
+
+/// Rounding applied to the variables which are not determined by an equality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingPolicy {
+    /// Round to the nearest integer.
+    Nearest,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceil,
+}
+
+impl RoundingPolicy {
+    /// Round a value according to the policy.
+    pub fn round(self, value: f64) -> f64 {
+        match self {
+            RoundingPolicy::Nearest => value.round(),
+            RoundingPolicy::Floor => value.floor(),
+            RoundingPolicy::Ceil => value.ceil(),
+        }
+    }
+}
+
+/// Round values to integers whose sum is total using the largest remainder method.
+///
+/// Every value is first rounded down, then the units missing to reach the total are given
+/// one by one to the values with the largest remainders (the first one in case of a tie).
+/// If more units are missing than there are values (or if the total is below the sum of
+/// the floors), every value first receives an equal share.
+pub(crate) fn distribute_remainders(values: &[f64], total: f64) -> Vec<f64> {
+    let mut rounded: Vec<f64> = values.iter().map(|v| v.floor()).collect();
+    if values.is_empty() {
+        return rounded;
+    }
+    let count = values.len() as i64;
+    let missing = (total - rounded.iter().sum::<f64>()).round() as i64;
+    let share = missing.div_euclid(count) as f64;
+    let extra = missing.rem_euclid(count) as usize;
+
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    // The sort is stable so ties are resolved in favor of the first values.
+    order.sort_by(|a, b| {
+        let ra = values[*a] - values[*a].floor();
+        let rb = values[*b] - values[*b].floor();
+        rb.partial_cmp(&ra).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    for (rank, index) in order.iter().enumerate() {
+        rounded[*index] += share;
+        if rank < extra {
+            rounded[*index] += 1.0;
+        }
+    }
+    rounded
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{distribute_remainders, RoundingPolicy};
+
+    #[test]
+    fn test_policy() {
+        assert_eq!(RoundingPolicy::Nearest.round(2.5), 3.0);
+        assert_eq!(RoundingPolicy::Floor.round(2.7), 2.0);
+        assert_eq!(RoundingPolicy::Ceil.round(2.1), 3.0);
+    }
+
+    #[test]
+    fn test_distribute_remainders() {
+        let third = 100.0 / 3.0;
+        assert_eq!(
+            distribute_remainders(&[third, third, third], 100.0),
+            vec![34.0, 33.0, 33.0]
+        );
+        assert_eq!(
+            distribute_remainders(&[10.2, 20.7, 9.1], 40.0),
+            vec![10.0, 21.0, 9.0]
+        );
+        assert_eq!(distribute_remainders(&[1.5, 1.5], 6.0), vec![3.0, 3.0]);
+        assert!(distribute_remainders(&[], 0.0).is_empty());
+    }
+}
@@ -1,9 +1,16 @@
 //!
 //!
 //!
-use crate::assoc_vec::AssocVec;
+use crate::assoc_vec::{AssocVec, Entry};
 use crate::symbol::Symbol;
 use crate::util::near_zero;
+use std::cmp::Ordering;
+
+/// Scratch buffer used by the row operations building a new set of cells.
+///
+/// The operations build the new cells in the buffer and swap it with the current cells, so
+/// the buffer holds the previous cells afterward and its allocation can be reused.
+pub type RowBuffer = Vec<(Symbol, f64)>;
 
 // FIXME add comments and tests
 #[derive(Clone)]
@@ -40,36 +47,101 @@ impl Row {
         &self.m_constant
     }
 
-    pub fn insert_symbol(&mut self, symbol: &Symbol, coefficient: f64) {
-        // FIXME can be made more efficient by implementing Entry on AssocVec
-        if let Some(c) = self.m_cells.get_mut(symbol) {
-            *c += coefficient;
-            if near_zero(*c) {
-                self.m_cells.remove(symbol);
+    /// Add a coefficient for a symbol.
+    ///
+    /// Cells whose coefficient becomes smaller than eps are removed.
+    ///
+    pub fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64, eps: f64) {
+        match self.m_cells.entry(symbol) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += coefficient;
+                if near_zero(*entry.get(), eps) {
+                    entry.remove();
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(coefficient);
             }
-        } else {
-            self.m_cells.insert(symbol.clone(), coefficient);
         }
     }
 
-    pub fn insert_row(&mut self, row: &Row, coefficient: f64) {
+    /// Add another row multiplied by a coefficient.
+    ///
+    /// Cells whose coefficient becomes smaller than eps are removed.
+    ///
+    pub fn insert_row(&mut self, row: &Row, coefficient: f64, eps: f64, buffer: &mut RowBuffer) {
         self.m_constant += row.m_constant * coefficient;
-        for (key, value) in row.m_cells.iter() {
-            // FIXME can be made more efficient by implementing Entry on AssocVec
-            let coeff = value * coefficient;
-            if let Some(c) = self.m_cells.get_mut(key) {
-                *c += coeff;
-                if near_zero(*c) {
-                    self.m_cells.remove(key);
+        self.merge(row, coefficient, None, eps, buffer, |_, _| ());
+    }
+
+    /// Merge the cells of another row multiplied by a coefficient with the cells of this row.
+    ///
+    /// Since the cells of both rows are sorted by symbol, the new cells are built in a single
+    /// linear pass. The cell of the skipped symbol, if any, is dropped. As when inserting the
+    /// cells one at a time, only the cells present in both rows can become smaller than eps and
+    /// be removed.
+    ///
+    /// on_change is called with true for each inserted cell and false for each removed one.
+    ///
+    fn merge<F: FnMut(Symbol, bool)>(
+        &mut self,
+        row: &Row,
+        coefficient: f64,
+        skip: Option<Symbol>,
+        eps: f64,
+        buffer: &mut RowBuffer,
+        mut on_change: F,
+    ) {
+        let left = self.m_cells.as_slice();
+        let right = row.m_cells.as_slice();
+        buffer.clear();
+        buffer.reserve(left.len() + right.len());
+        let (mut i, mut j) = (0, 0);
+        while i < left.len() && j < right.len() {
+            let (ls, lc) = left[i];
+            let (rs, rc) = right[j];
+            match ls.cmp(&rs) {
+                Ordering::Less => {
+                    if skip != Some(ls) {
+                        buffer.push((ls, lc));
+                    } else {
+                        on_change(ls, false);
+                    }
+                    i += 1;
                 }
+                Ordering::Greater => {
+                    buffer.push((rs, rc * coefficient));
+                    on_change(rs, true);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    let c = lc + rc * coefficient;
+                    if skip != Some(ls) && !near_zero(c, eps) {
+                        buffer.push((ls, c));
+                    } else {
+                        on_change(ls, false);
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        for &(ls, lc) in left[i..].iter() {
+            if skip != Some(ls) {
+                buffer.push((ls, lc));
             } else {
-                self.m_cells.insert(key.clone(), coeff);
+                on_change(ls, false);
             }
         }
+        for &(rs, rc) in right[j..].iter() {
+            buffer.push((rs, rc * coefficient));
+            on_change(rs, true);
+        }
+        self.m_cells.swap_sorted(buffer);
     }
 
-    pub fn remove(&mut self, symbol: &Symbol) {
-        self.m_cells.remove(symbol);
+    pub fn remove(&mut self, symbol: Symbol) {
+        self.m_cells.remove(&symbol);
     }
 
     pub fn reverse_sign(&mut self) {
@@ -79,14 +151,15 @@ impl Row {
         }
     }
 
-    pub fn solve_for(&mut self, symbol: &Symbol) {
-        if let Some(target_coeff) = self.m_cells.get(symbol) {
-            let coeff = -1.0 / (*target_coeff);
-            self.m_cells.remove(symbol);
+    pub fn solve_for(&mut self, symbol: Symbol) {
+        if let Some(target_coeff) = self.m_cells.get(&symbol) {
+            let coeff = -1.0 / target_coeff;
             self.m_constant *= coeff;
-            for (key, value) in self.m_cells.iter_mut() {
+            // Drop the symbol and scale the other cells in a single pass.
+            self.m_cells.retain(|key, value| {
                 *value *= coeff;
-            }
+                *key != symbol
+            });
         }
         // FIXME add a nice else clause
     }
@@ -102,9 +175,24 @@ impl Row {
     *must* exist in the row.
 
     */
-    pub fn solve_for_symbols(&mut self, lhs: &Symbol, rhs: &Symbol) {
-        self.insert_symbol(lhs, -1.0);
-        self.solve_for(rhs);
+    pub fn solve_for_symbols(&mut self, lhs: Symbol, rhs: Symbol, buffer: &mut RowBuffer) {
+        let coeff = -1.0 / self.coefficient_for(rhs);
+        self.m_constant *= coeff;
+
+        // Build the scaled cells with the lhs inserted at its place in a single pass.
+        buffer.clear();
+        buffer.reserve(self.m_cells.len());
+        let mut lhs_cell = Some((lhs, -coeff));
+        for &(key, value) in self.m_cells.iter() {
+            if lhs_cell.is_some_and(|(s, _)| s < key) {
+                buffer.extend(lhs_cell.take());
+            }
+            if key != rhs {
+                buffer.push((key, value * coeff));
+            }
+        }
+        buffer.extend(lhs_cell);
+        self.m_cells.swap_sorted(buffer);
     }
 
     /* Get the coefficient for the given symbol.
@@ -112,8 +200,8 @@ impl Row {
     If the symbol does not exist in the row, zero will be returned.
 
     */
-    pub fn coefficient_for(&self, symbol: &Symbol) -> f64 {
-        match self.m_cells.get(symbol) {
+    pub fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        match self.m_cells.get(&symbol) {
             None => 0.0,
             Some(c) => *c,
         }
@@ -128,9 +216,104 @@ impl Row {
     If the symbol does not exist in the row, this is a no-op.
 
     */
-    pub fn substitute(&mut self, symbol: &Symbol, row: &Row) {
-        if let Some(c) = self.m_cells.remove(symbol) {
-            self.insert_row(row, c)
+    pub fn substitute(&mut self, symbol: Symbol, row: &Row, eps: f64, buffer: &mut RowBuffer) {
+        self.substitute_with(symbol, row, eps, buffer, |_, _| ());
+    }
+
+    /* Substitute a symbol with the data from another row, reporting the changed cells.
+
+    on_change is called with true for each cell inserted in the row and
+    false for each cell removed from it (including the cell of the symbol).
+
+    */
+    pub fn substitute_with<F: FnMut(Symbol, bool)>(
+        &mut self,
+        symbol: Symbol,
+        row: &Row,
+        eps: f64,
+        buffer: &mut RowBuffer,
+        on_change: F,
+    ) {
+        if let Some(&c) = self.m_cells.get(&symbol) {
+            self.m_constant += row.m_constant * c;
+            self.merge(row, c, Some(symbol), eps, buffer, on_change);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Row, RowBuffer};
+    use crate::symbol::{Symbol, SymbolKind};
+
+    fn row(constant: f64, cells: &[(u64, f64)]) -> Row {
+        let mut row = Row::new(constant);
+        for (id, coefficient) in cells.iter() {
+            row.insert_symbol(Symbol::new(SymbolKind::Slack, *id), *coefficient, 1e-8);
+        }
+        row
+    }
+
+    fn cells(row: &Row) -> Vec<(u64, f64)> {
+        row.cells().iter().map(|(s, c)| (s.id(), *c)).collect()
+    }
+
+    #[test]
+    fn test_insert_row() {
+        let mut buffer = RowBuffer::new();
+        let mut a = row(1.0, &[(1, 1.0), (3, 2.0), (5, 1.0)]);
+        let b = row(2.0, &[(2, 1.0), (3, -1.0), (6, 0.5)]);
+        a.insert_row(&b, 2.0, 1e-8, &mut buffer);
+        assert_eq!(*a.constant(), 5.0);
+        // The cell of symbol 3 cancels out and is removed.
+        assert_eq!(cells(&a), vec![(1, 1.0), (2, 2.0), (5, 1.0), (6, 1.0)]);
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut buffer = RowBuffer::new();
+        let mut a = row(1.0, &[(1, 1.0), (2, 3.0), (4, 1.0)]);
+        let b = row(2.0, &[(1, -0.5), (3, 1.0)]);
+        a.substitute(Symbol::new(SymbolKind::Slack, 2), &b, 1e-8, &mut buffer);
+        assert_eq!(*a.constant(), 7.0);
+        assert_eq!(cells(&a), vec![(1, -0.5), (3, 3.0), (4, 1.0)]);
+
+        // Substituting a symbol absent from the row is a no-op.
+        a.substitute(Symbol::new(SymbolKind::Slack, 2), &b, 1e-8, &mut buffer);
+        assert_eq!(*a.constant(), 7.0);
+        assert_eq!(cells(&a), vec![(1, -0.5), (3, 3.0), (4, 1.0)]);
+
+        // The cell of symbol 1 cancels out, the one of symbol 5 is inserted.
+        let mut changes = Vec::new();
+        a.substitute_with(
+            Symbol::new(SymbolKind::Slack, 4),
+            &row(1.0, &[(1, 0.5), (5, 2.0)]),
+            1e-8,
+            &mut buffer,
+            |s, inserted| changes.push((s.id(), inserted)),
+        );
+        assert_eq!(*a.constant(), 8.0);
+        assert_eq!(cells(&a), vec![(3, 3.0), (5, 2.0)]);
+        assert_eq!(changes, vec![(1, false), (4, false), (5, true)]);
+    }
+
+    #[test]
+    fn test_solve_for() {
+        let mut buffer = RowBuffer::new();
+        // x1 = 4 + 2 * x2 - x4, solved for x2 gives x2 = -2 + 0.5 * x1 + 0.5 * x4
+        let mut a = row(4.0, &[(2, 2.0), (4, -1.0)]);
+        a.solve_for_symbols(
+            Symbol::new(SymbolKind::Slack, 1),
+            Symbol::new(SymbolKind::Slack, 2),
+            &mut buffer,
+        );
+        assert_eq!(*a.constant(), -2.0);
+        assert_eq!(cells(&a), vec![(1, 0.5), (4, 0.5)]);
+
+        // 0 = -2 + 0.5 * x1 + 0.5 * x4, solved for x4 gives x4 = 4 - x1
+        a.solve_for(Symbol::new(SymbolKind::Slack, 4));
+        assert_eq!(*a.constant(), 4.0);
+        assert_eq!(cells(&a), vec![(1, -1.0)]);
+    }
+}
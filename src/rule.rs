@@ -0,0 +1,49 @@
+//! Conditional rules switching constraints depending on the solution.
+//!
+//! A rule compares an expression of the solved variables with a threshold (the width of a
+//! container with a breakpoint for example) and enforces one list of constraints when the
+//! condition holds and another one otherwise. Since switching the constraints changes the
+//! solution, the rules are evaluated again until no rule switches.
+//!
+
+/// Handle identifying a rule added to a solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleHandle {
+    m_id: u64,
+}
+
+impl RuleHandle {
+    /// Create a handle from a unique id.
+    pub(crate) fn new(id: u64) -> RuleHandle {
+        RuleHandle { m_id: id }
+    }
+
+    /// Access the handle id.
+    pub fn id(&self) -> u64 {
+        self.m_id
+    }
+}
+
+/// Branch of a rule whose constraints are in the tableau.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleBranch {
+    /// The condition holds.
+    Then,
+    /// The condition does not hold.
+    Else,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::RuleHandle;
+
+    #[test]
+    fn test_handle() {
+        let h1 = RuleHandle::new(1);
+        let h2 = RuleHandle::new(2);
+        assert_eq!(h1.id(), 1);
+        assert_ne!(h1, h2);
+        assert!(h1 < h2);
+    }
+}
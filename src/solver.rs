@@ -3,69 +3,333 @@
 //!
 //!
 use crate::assoc_vec::AssocVec;
+use crate::config::{SolverConfig, StrengthMode};
 use crate::constraint::{Constraint, RelationalOperator};
+use crate::disjunction::DisjunctionHandle;
 use crate::errors::KiwiError;
 use crate::expression::Expression;
-use crate::row::Row;
+use crate::group::ConstraintGroup;
+use crate::integer::IntegerStatus;
+use crate::objective::{ObjectiveHandle, Sense};
+use crate::rounding::{distribute_remainders, RoundingPolicy};
+use crate::row::{Row, RowBuffer};
+use crate::rule::{RuleBranch, RuleHandle};
 use crate::strength;
 use crate::symbol::{Symbol, SymbolKind};
+use crate::tableau::Tableau;
 use crate::term::Term;
 use crate::util::near_zero;
-use crate::variable::Variable;
+use crate::variable::{VarId, Variable, VariableHandle};
+use std::cmp::Ordering;
 
 ///
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 struct Tag {
     marker: Symbol,
     other: Option<Symbol>,
+    // Strength and constant of the constraint in this solver, which differ from the ones of
+    // the Constraint after a call to set_strength or set_constant.
+    strength: f64,
+    constant: f64,
 }
 
 ///
-struct EditInfo {
+struct EditInfo<V> {
     tag: Tag,
-    constraint: Constraint,
+    constraint: Constraint<V>,
+    constant: f64,
+}
+
+///
+struct ObjectiveInfo<V> {
+    expression: Expression<V>,
+    sense: Sense,
+    strength: f64,
+}
+
+/// Side of the bounds of a variable.
+#[derive(Clone, Copy, PartialEq)]
+enum BoundSide {
+    Lower,
+    Upper,
+}
+
+/// Bounds of a variable.
+///
+/// A bound is only represented in the tableau by a constraint while it is
+/// active. Otherwise it is enforced by the ratio tests of the optimizations.
+struct BoundInfo<V> {
+    variable: V,
+    lower: f64,
+    upper: f64,
+    strength: f64,
+    lower_constraint: Option<Constraint<V>>,
+    upper_constraint: Option<Constraint<V>>,
+}
+
+impl<V: VariableHandle> BoundInfo<V> {
+    fn limit(&self, side: BoundSide) -> f64 {
+        match side {
+            BoundSide::Lower => self.lower,
+            BoundSide::Upper => self.upper,
+        }
+    }
+
+    fn constraint(&self, side: BoundSide) -> &Option<Constraint<V>> {
+        match side {
+            BoundSide::Lower => &self.lower_constraint,
+            BoundSide::Upper => &self.upper_constraint,
+        }
+    }
+
+    fn set_constraint(&mut self, side: BoundSide, constraint: Option<Constraint<V>>) {
+        match side {
+            BoundSide::Lower => self.lower_constraint = constraint,
+            BoundSide::Upper => self.upper_constraint = constraint,
+        }
+    }
+
+    /// Test whether a value of the variable lies beyond one side of the bounds.
+    fn is_violated(&self, side: BoundSide, value: f64, tolerance: f64) -> bool {
+        match side {
+            BoundSide::Lower => value < self.lower - tolerance,
+            BoundSide::Upper => value > self.upper + tolerance,
+        }
+    }
+
+    /// Build the constraint representing one side of the bounds.
+    fn make_constraint(&self, side: BoundSide) -> Constraint<V> {
+        let op = match side {
+            BoundSide::Lower => RelationalOperator::GreaterEqual,
+            BoundSide::Upper => RelationalOperator::LessEqual,
+        };
+        Constraint::new(
+            Expression::new(
+                vec![Term::new(self.variable.clone(), 1.0)],
+                -self.limit(side),
+            ),
+            op,
+            self.strength,
+        )
+    }
+}
+
+/// Outcome of the ratio test of a primal pivot.
+#[derive(Clone, Copy)]
+enum Leaving {
+    /// The basic symbol of a row of the tableau.
+    Row(Symbol),
+    /// A side of the bounds of a variable which is not in the tableau.
+    Bound(Symbol, BoundSide),
+}
+
+/// State of a branch-and-bound search.
+struct BranchSearch<V> {
+    nodes: usize,
+    limited: bool,
+    // Objective value and variable values of the best integer solution found.
+    best_objective: Option<[f64; strength::LEVELS]>,
+    best_values: Vec<(V, f64)>,
+}
+
+/// Required equality kept exact when rounding a solution.
+///
+/// The sum of the terms and of the constant is zero. The coefficients of the terms are 1 or
+/// -1 and the constant includes the value of the variables which are not rounded.
+struct RoundingEquation<V> {
+    constraint: Constraint<V>,
+    terms: Vec<(V, f64)>,
     constant: f64,
 }
 
+/// Alternatives of a disjunction and the index of the one in the tableau.
+struct DisjunctionInfo<V> {
+    alternatives: Vec<Vec<Constraint<V>>>,
+    active: usize,
+}
+
+/// Constraints of a group and whether they are in the tableau.
+struct GroupInfo<V> {
+    constraints: Vec<Constraint<V>>,
+    enabled: bool,
+}
+
+/// Condition and branches of a rule.
+struct RuleInfo<V> {
+    condition: Expression<V>,
+    op: RelationalOperator,
+    threshold: f64,
+    then_constraints: Vec<Constraint<V>>,
+    else_constraints: Vec<Constraint<V>>,
+    branch: RuleBranch,
+}
+
+impl<V: VariableHandle> RuleInfo<V> {
+    fn constraints(&self, branch: RuleBranch) -> &Vec<Constraint<V>> {
+        match branch {
+            RuleBranch::Then => &self.then_constraints,
+            RuleBranch::Else => &self.else_constraints,
+        }
+    }
+}
+
 ///
 enum OptimizationTarget {
     Objective,
-    Artificial,
+    // The artificial objective is the value of the artificial symbol.
+    Artificial(Symbol),
+}
+
+/// Number of consecutive degenerate pivots after which the solver switches to Bland's rule.
+///
+/// A degenerate pivot leaves the objective unchanged, which is frequent in layouts with many
+/// equal-zero spacings. A few of them are harmless but a long sequence is the signature of a
+/// potential cycle.
+const DEGENERATE_PIVOTS_THRESHOLD: usize = 8;
+
+/// Compare two vectors of per level coefficients lexicographically.
+///
+/// Coefficients which differ by less than eps are considered equal.
+fn compare_levels(a: &[f64; strength::LEVELS], b: &[f64; strength::LEVELS], eps: f64) -> Ordering {
+    for (x, y) in a.iter().zip(b.iter()) {
+        if (x - y).abs() > eps {
+            return if x < y {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+    }
+    Ordering::Equal
+}
+
+/// Test whether the per level objective value a is lower than b by more than margin.
+///
+/// The first level where the values differ by more than eps decides.
+fn improves(
+    a: &[f64; strength::LEVELS],
+    b: &[f64; strength::LEVELS],
+    margin: f64,
+    eps: f64,
+) -> bool {
+    for (x, y) in a.iter().zip(b.iter()) {
+        if (x - y).abs() > eps {
+            return *x < *y - margin;
+        }
+    }
+    false
 }
 
-struct Solver {
-    m_cns: AssocVec<Constraint, Tag>,
-    m_rows: AssocVec<Symbol, Row>,
-    m_vars: AssocVec<Variable, Symbol>,
-    m_edits: AssocVec<Variable, EditInfo>,
+pub struct Solver<V: VariableHandle = Variable> {
+    m_cns: AssocVec<Constraint<V>, Tag>,
+    m_rows: Tableau,
+    m_vars: AssocVec<V, Symbol>,
+    m_edits: AssocVec<V, EditInfo<V>>,
+    m_stays: AssocVec<V, EditInfo<V>>,
+    m_objectives: AssocVec<ObjectiveHandle, ObjectiveInfo<V>>,
+    // Bounds keyed by the symbol of the variable.
+    m_bounds: AssocVec<Symbol, BoundInfo<V>>,
+    m_integers: AssocVec<V, Symbol>,
+    m_disjunctions: AssocVec<DisjunctionHandle, DisjunctionInfo<V>>,
+    m_groups: AssocVec<ConstraintGroup, GroupInfo<V>>,
+    m_rules: AssocVec<RuleHandle, RuleInfo<V>>,
     m_infeasible_rows: Vec<Symbol>,
     m_objective: Row,
+    // Per level objective rows, only used in lexicographic strength mode (empty otherwise).
+    m_objective_levels: Vec<Row>,
     m_artificial: Option<Row>,
     m_id_tick: u64,
+    m_config: SolverConfig,
+    // Scratch buffer reused by the row operations to avoid an allocation per operation.
+    m_buffer: RowBuffer,
+    // Names and values of the variables allocated by a Solver<VarId>, indexed by VarId.
+    m_names: Vec<String>,
+    m_values: Vec<f64>,
+}
+
+type SolverResult<V> = Result<(), KiwiError<V>>;
+
+impl<V: VariableHandle> Default for Solver<V> {
+    fn default() -> Solver<V> {
+        Self::new()
+    }
 }
 
-type SolverResult = Result<(), KiwiError>;
+impl<V: VariableHandle> Solver<V> {
+    /// Create a solver using the default configuration.
+    pub fn new() -> Solver<V> {
+        Self::with_config(SolverConfig::default())
+    }
 
-impl Solver {
-    // XXX Need to also implement Default
-    fn new() -> Solver {
+    /// Create a solver using specific tolerances and limits.
+    pub fn with_config(config: SolverConfig) -> Solver<V> {
         Solver {
             m_cns: AssocVec::new(),
-            m_rows: AssocVec::new(),
+            m_rows: Tableau::new(),
             m_vars: AssocVec::new(),
             m_edits: AssocVec::new(),
+            m_stays: AssocVec::new(),
+            m_objectives: AssocVec::new(),
+            m_bounds: AssocVec::new(),
+            m_integers: AssocVec::new(),
+            m_disjunctions: AssocVec::new(),
+            m_groups: AssocVec::new(),
+            m_rules: AssocVec::new(),
             m_infeasible_rows: Vec::new(),
             m_objective: Row::new(1.0),
+            m_objective_levels: Self::objective_levels(config.strength_mode()),
             m_artificial: None,
             m_id_tick: 1,
+            m_config: config,
+            m_buffer: RowBuffer::new(),
+            m_names: Vec::new(),
+            m_values: Vec::new(),
+        }
+    }
+
+    /// Access the tolerances and limits used by the solver.
+    pub fn config(&self) -> &SolverConfig {
+        &self.m_config
+    }
+
+    /// Set the tolerances and limits used by the solver and return the previous ones.
+    ///
+    /// The new values are used from the next operation on, the current
+    /// tableau is not re-evaluated. As a consequence, the strength mode is
+    /// only updated if no constraint has been added to the solver.
+    pub fn set_config(&mut self, mut config: SolverConfig) -> SolverConfig {
+        if self.m_cns.is_empty() {
+            self.m_objective_levels = Self::objective_levels(config.strength_mode());
+        } else {
+            config.set_strength_mode(self.m_config.strength_mode());
         }
+        std::mem::replace(&mut self.m_config, config)
     }
 
     /// Add a constraint to the solver.
-    pub fn add_constraint(&mut self, constraint: Constraint) -> Result<Tag, KiwiError> {
+    ///
+    /// The constraint is not added if an error is returned, including when
+    /// the pivot limit is reached while optimizing the new tableau.
+    ///
+    /// A `Solver<VarId>` rejects the constraints using a variable it did not
+    /// allocate with an `UnknownVariable` error.
+    ///
+    /// The alternatives of the disjunctions are re-evaluated once the
+    /// constraint is added.
+    pub fn add_constraint(&mut self, constraint: Constraint<V>) -> SolverResult<V> {
+        self.insert_constraint(constraint)?;
+        self.reevaluate_disjunctions()
+    }
+
+    /// Add a constraint to the tableau without re-evaluating the disjunctions.
+    ///
+    /// This is add_constraint for the internal uses, which must not switch
+    /// the alternatives of the disjunctions while they change the tableau.
+    fn insert_constraint(&mut self, constraint: Constraint<V>) -> SolverResult<V> {
         if self.m_cns.contains_key(&constraint) {
             return Err(KiwiError::DuplicateConstraint { constraint });
         }
+        self.check_expression(&constraint.expression())?;
 
         // Creating a row causes symbols to be reserved for the variables
         // in the constraint. If this method exits with an exception,
@@ -74,7 +338,7 @@ impl Solver {
         // constraints and since exceptional conditions are uncommon,
         // i'm not too worried about aggressive cleanup of the var map.
         let (mut row, tag) = self.create_row(&constraint);
-        let mut subject = self.choose_subject(&row, &tag);
+        let mut subject = self.choose_subject(&row, tag);
 
         // If chooseSubject could not find a valid entering symbol, one
         // last option is available if the entire row is composed of
@@ -83,10 +347,10 @@ impl Solver {
         // marker can enter the basis. If the constant is non-zero,
         // then it represents an unsatisfiable constraint.
         if subject.kind() == SymbolKind::Invalid && self.all_dummies(&row) {
-            if !near_zero(*row.constant()) {
+            if !near_zero(*row.constant(), self.m_config.feasibility_tolerance()) {
                 return Err(KiwiError::UnsatisfiableConstraint { constraint });
             } else {
-                subject = tag.marker.clone();
+                subject = tag.marker;
             }
         }
 
@@ -94,58 +358,54 @@ impl Solver {
         // be added using an artificial variable. If that fails, then
         // the row represents an unsatisfiable constraint.
         if subject.kind() == SymbolKind::Invalid {
-            if !self.add_with_artificial_variable(row) {
+            if !self.add_with_artificial_variable(row)? {
+                // The pivots of the artificial optimization may have moved the
+                // solution away from the optimum.
+                self.optimize(OptimizationTarget::Objective)?;
                 return Err(KiwiError::UnsatisfiableConstraint { constraint });
             }
         } else {
-            row.solve_for(&subject);
-            self.substitute(&subject, &row);
-            self.m_rows.insert(subject.clone(), row);
+            row.solve_for(subject);
+            self.substitute(subject, &row);
+            self.m_rows.insert(subject, row);
         }
 
-        self.m_cns.insert(constraint, tag.clone());
+        self.m_cns.insert(constraint.clone(), tag);
 
         // Optimizing after each constraint is added performs less
         // aggregate work due to a smaller average system size. It
         // also ensures the solver remains in a consistent state.
-        self.optimize(OptimizationTarget::Objective)?;
-        Ok(tag)
+        // If the optimization fails, the constraint is removed again so
+        // that the solver is left as it was. The only way for the
+        // optimization to report an unsatisfiable constraint is a
+        // conflict with the required bounds of a variable.
+        match self.optimize(OptimizationTarget::Objective) {
+            Ok(()) => Ok(()),
+            Err(error) => {
+                self.delete_constraint(&constraint)?;
+                match error {
+                    KiwiError::UnsatisfiableConstraint { .. } => {
+                        Err(KiwiError::UnsatisfiableConstraint { constraint })
+                    }
+                    error => Err(error),
+                }
+            }
+        }
     }
 
     /// Remove a constraint from the solver.
     ///
-    pub fn remove_constraint(&mut self, constraint: &Constraint) -> SolverResult {
-        let tag: Tag;
-        match self.m_cns.remove(constraint) {
-            Some(t) => tag = t,
-            None => {
-                return Err(KiwiError::UnknownConstraint {
-                    constraint: constraint.clone(),
-                });
-            }
-        }
-
-        // Remove the error effects from the objective function
-        // *before* pivoting, or substitutions into the objective
-        // will lead to incorrect solver results.
-        self.remove_constraint_effects(constraint, &tag);
+    /// The alternatives of the disjunctions are re-evaluated once the
+    /// constraint is removed.
+    pub fn remove_constraint(&mut self, constraint: &Constraint<V>) -> SolverResult<V> {
+        self.delete_constraint(constraint)?;
+        self.reevaluate_disjunctions()
+    }
 
-        // If the marker is basic, simply drop the row. Otherwise,
-        // pivot the marker into the basis and then drop the row.
-        if let Some(_row) = self.m_rows.remove(&tag.marker) {
-        } else {
-            match self.get_marker_leaving_row(&tag.marker) {
-                Some((leaving_symbol, mut leaving_row)) => {
-                    leaving_row.solve_for_symbols(&leaving_symbol, &tag.marker);
-                    self.substitute(&tag.marker, &leaving_row);
-                }
-                None => {
-                    return Err(KiwiError::InternalSolverError {
-                        msg: String::from("failed to find leaving row"),
-                    })
-                }
-            }
-        }
+    /// Remove a constraint from the tableau without re-evaluating the disjunctions.
+    ///
+    fn delete_constraint(&mut self, constraint: &Constraint<V>) -> SolverResult<V> {
+        self.remove_constraint_rows(constraint)?;
 
         // Optimizing after each constraint is removed ensures that the
         // solver remains consistent. It makes the solver api easier to
@@ -155,10 +415,83 @@ impl Solver {
 
     /// Test whether a constraint has been added to the solver.
     ///
-    pub fn has_constraint(&self, constraint: &Constraint) -> bool {
+    pub fn has_constraint(&self, constraint: &Constraint<V>) -> bool {
         self.m_cns.contains_key(constraint)
     }
 
+    /* Change the strength of a constraint already added to the solver.
+
+    The weight of the error symbols of the constraint in the objective
+    function is updated in place and the objective is then re-optimized,
+    which is much cheaper than removing and re-adding the constraint.
+
+    The new strength is only used by this solver: the Constraint itself
+    (which may be shared with other solvers) is left unchanged. Use
+    constraint_strength to access the strength used by the solver.
+
+    Throws
+    ------
+    UnknownConstraint
+        The given constraint has not been added to the solver.
+
+    BadRequiredStrength
+        The constraint is required or the new strength is >= required.
+        Required constraints have no error symbols and their strength
+        cannot change without rebuilding their row.
+
+    */
+    pub fn set_strength(&mut self, constraint: &Constraint<V>, strength: f64) -> SolverResult<V> {
+        let tag = match self.m_cns.get(constraint) {
+            Some(tag) => *tag,
+            None => {
+                return Err(KiwiError::UnknownConstraint {
+                    constraint: constraint.clone(),
+                });
+            }
+        };
+        let c_strength = strength::clip(strength);
+        if tag.strength == strength::REQUIRED || c_strength == strength::REQUIRED {
+            return Err(KiwiError::BadRequiredStrength);
+        }
+        if tag.strength == c_strength {
+            return Ok(());
+        }
+
+        self.remove_constraint_effects(tag);
+        let tag = Tag {
+            strength: c_strength,
+            ..tag
+        };
+        self.m_cns.insert(constraint.clone(), tag);
+        self.add_constraint_effects(tag);
+
+        // The tableau is still feasible only the objective changed.
+        self.optimize(OptimizationTarget::Objective)?;
+        self.reevaluate_disjunctions()
+    }
+
+    /* Get the strength used by the solver for a constraint.
+
+    This is the strength of the constraint unless it was changed with
+    set_strength. None is returned if the constraint has not been added
+    to the solver.
+
+    */
+    pub fn constraint_strength(&self, constraint: &Constraint<V>) -> Option<f64> {
+        self.m_cns.get(constraint).map(|tag| tag.strength)
+    }
+
+    /* Get the constant used by the solver for the expression of a constraint.
+
+    This is the constant of the expression unless it was changed with
+    set_constant. None is returned if the constraint has not been added
+    to the solver.
+
+    */
+    pub fn constraint_constant(&self, constraint: &Constraint<V>) -> Option<f64> {
+        self.m_cns.get(constraint).map(|tag| tag.constant)
+    }
+
     /* Add an edit variable to the solver.
 
     This method should be called before the `suggestValue` method is
@@ -172,8 +505,11 @@ impl Solver {
     BadRequiredStrength
         The given strength is >= required.
 
+    UnknownVariable
+        The given variable was not allocated by the solver.
+
     */
-    pub fn add_edit_variable(&mut self, variable: &Variable, strength: f64) -> SolverResult {
+    pub fn add_edit_variable(&mut self, variable: &V, strength: f64) -> SolverResult<V> {
         if self.m_edits.contains_key(variable) {
             return Err(KiwiError::DuplicateEditVariable {
                 variable: variable.clone(),
@@ -190,11 +526,12 @@ impl Solver {
         );
 
         // Add the constraint and get the associated tag
-        let tag = self.add_constraint(cn.clone())?;
+        self.insert_constraint(cn.clone())?;
+        let tag = self.m_cns[&cn];
         self.m_edits.insert(
             variable.clone(),
             EditInfo {
-                tag: tag.clone(),
+                tag,
                 constraint: cn,
                 constant: 0.0,
             },
@@ -210,9 +547,9 @@ impl Solver {
     // 	The given edit variable has not been added to the solver.
 
     // */
-    pub fn remove_edit_variable(&mut self, variable: &Variable) -> SolverResult {
+    pub fn remove_edit_variable(&mut self, variable: &V) -> SolverResult<V> {
         if let Some(edit_info) = self.m_edits.remove(variable) {
-            match self.remove_constraint(&edit_info.constraint) {
+            match self.delete_constraint(&edit_info.constraint) {
                 Err(error) => {
                     self.m_edits.insert(variable.clone(), edit_info);
                     Err(error)
@@ -229,542 +566,4265 @@ impl Solver {
     // /* Test whether an edit variable has been added to the solver.
 
     // */
-    pub fn has_edit_variable(&self, variable: &Variable) -> bool {
+    pub fn has_edit_variable(&self, variable: &V) -> bool {
         self.m_edits.contains_key(variable)
     }
 
-    /* Suggest a value for the given edit variable.
+    /* Add a stay constraint on a variable.
 
-    This method should be used after an edit variable as been added to
-    the solver in order to suggest the value for that variable.
+    A stay constraint keeps a variable close to its last solved value. It
+    behaves as an edit variable whose suggested value is updated, by
+    update_variable, to the value of the variable after each solve. The
+    initial target is the current value of the variable.
 
     Throws
     ------
-    UnknownEditVariable
-        The given edit variable has not been added to the solver.
+    DuplicateStayVariable
+        The given variable already has a stay constraint.
+
+    BadRequiredStrength
+        The given strength is >= required.
+
+    UnknownVariable
+        The given variable was not allocated by the solver.
 
     */
-    pub fn suggest_value(&mut self, variable: &Variable, value: f64) -> SolverResult {
-        // If the edit variable exists update the solver and perform a dual optimize
-        if self.m_edits.contains_key(variable) {
-            self.call_with_dual_guard(|solver| {
-                let mut edit_info = solver.m_edits.get_mut(variable).unwrap();
-                let delta = value - edit_info.constant;
-                edit_info.constant = value;
-
-                // Check first if the positive error variable is basic.
-                if let Some(mut row) = solver.m_rows.get_mut(&edit_info.tag.marker) {
-                    if *row.add(-delta) < 0.0 {
-                        solver.m_infeasible_rows.push(edit_info.tag.marker.clone());
-                        return;
-                    }
-                }
+    pub fn add_stay(&mut self, variable: &V, strength: f64) -> SolverResult<V> {
+        if self.m_stays.contains_key(variable) {
+            return Err(KiwiError::DuplicateStayVariable {
+                variable: variable.clone(),
+            });
+        }
+        let c_strength = strength::clip(strength);
+        if c_strength == strength::REQUIRED {
+            return Err(KiwiError::BadRequiredStrength {});
+        }
+        self.check_variable(variable)?;
+        let value = variable.load(&self.m_values);
+        let cn = Constraint::new(
+            Expression::new(vec![Term::new(variable.clone(), 1.0)], -value),
+            RelationalOperator::Equal,
+            c_strength,
+        );
 
-                // Check next if the negative error variable is basic.
-                if let Some(symbol) = edit_info.tag.other.as_ref() {
-                    if let Some(row) = solver.m_rows.get_mut(symbol) {
-                        if *row.add(delta) < 0.0 {
-                            solver.m_infeasible_rows.push(edit_info.tag.marker.clone());
-                            return;
-                        }
-                    }
-                }
+        self.insert_constraint(cn.clone())?;
+        let tag = self.m_cns[&cn];
+        self.m_stays.insert(
+            variable.clone(),
+            EditInfo {
+                tag,
+                constraint: cn,
+                constant: value,
+            },
+        );
+        Ok(())
+    }
 
-                // Otherwise update each row where the error variables exist.
-                for (symbol, row) in solver.m_rows.iter_mut() {
-                    let coeff = row.coefficient_for(&edit_info.tag.marker);
-                    if coeff != 0.0
-                        && *row.add(delta * coeff) < 0.0
-                        && symbol.kind() != SymbolKind::External
-                    {
-                        solver.m_infeasible_rows.push(symbol.clone());
-                    }
+    /* Remove the stay constraint of a variable.
+
+    Throws
+    ------
+    UnknownStayVariable
+        The given variable has no stay constraint.
+
+    */
+    pub fn remove_stay(&mut self, variable: &V) -> SolverResult<V> {
+        if let Some(stay_info) = self.m_stays.remove(variable) {
+            match self.delete_constraint(&stay_info.constraint) {
+                Err(error) => {
+                    self.m_stays.insert(variable.clone(), stay_info);
+                    Err(error)
                 }
-            })
+                Ok(()) => Ok(()),
+            }
         } else {
-            Err(KiwiError::UnknownEditVariable {
+            Err(KiwiError::UnknownStayVariable {
                 variable: variable.clone(),
             })
         }
     }
 
-    /* Update the values of the external solver variables.
+    /* Test whether a variable has a stay constraint.
 
     */
-    pub fn update_variable(&mut self) {
-        for (variable, symbol) in self.m_vars.iter_mut() {
-            if let Some(row) = self.m_rows.get(symbol) {
-                variable.set_value(*row.constant());
-            } else {
-                variable.set_value(0.0);
-            }
-        }
+    pub fn has_stay(&self, variable: &V) -> bool {
+        self.m_stays.contains_key(variable)
     }
 
-    // /* Reset the solver to the empty starting condition.
+    /* Set the bounds of a variable.
 
-    // This method resets the internal solver state to the empty starting
-    // condition, as if no constraints or edit variables have been added.
-    // This can be faster than deleting the solver and creating a new one
-    // when the entire system must change, since it can avoid unecessary
-    // heap (de)allocations.
+    The bounds are not added to the tableau as regular constraints.
+    They are enforced by the ratio tests of the optimizations and a
+    bound only enters the tableau while it is active, which keeps the
+    tableau small for layouts where most variables are clamped to a
+    range they rarely reach. Infinite values can be used for a side
+    without bound. Previous bounds of the variable are replaced.
 
-    // */
-    pub fn reset(&mut self) {}
-    // void reset()
-    // {
-    // 	clearRows();
-    // 	m_cns.clear();
-    // 	m_vars.clear();
-    // 	m_edits.clear();
-    // 	m_infeasible_rows.clear();
-    // 	m_objective.reset( new Row() );
-    // 	m_artificial.reset();
-    // 	m_id_tick = 1;
-    // }
+    Throws
+    ------
+    InvalidBounds
+        The lower bound is greater than the upper bound or one of them
+        is not a number.
 
-    // =============================================================================================
-    // --- Private methods -------------------------------------------------------------------------
-    // =============================================================================================
+    UnsatisfiableConstraint
+        The bounds are required and conflict with the other required
+        constraints. The variable is left without bounds.
 
-    /* Create a new Row object for the given constraint.
+    UnknownVariable
+        The given variable was not allocated by the solver.
 
-    The terms in the constraint will be converted to cells in the row.
-    Any term in the constraint with a coefficient of zero is ignored.
-    This method uses the `getVarSymbol` method to get the symbol for
-    the variables added to the row. If the symbol for a given cell
-    variable is basic, the cell variable will be substituted with the
-    basic row.
+    */
+    pub fn set_bounds(
+        &mut self,
+        variable: &V,
+        lower: f64,
+        upper: f64,
+        strength: f64,
+    ) -> SolverResult<V> {
+        if lower.is_nan() || upper.is_nan() || lower > upper {
+            return Err(KiwiError::InvalidBounds {
+                variable: variable.clone(),
+            });
+        }
+        self.check_variable(variable)?;
+        if self.has_bounds(variable) {
+            self.remove_bounds(variable)?;
+        }
 
-    The necessary slack and error variables will be added to the row.
-    If the constant for the row is negative, the sign for the row
-    will be inverted so the constant becomes positive.
+        let symbol = self.get_var_symbol(variable);
+        self.m_bounds.insert(
+            symbol,
+            BoundInfo {
+                variable: variable.clone(),
+                lower,
+                upper,
+                strength: strength::clip(strength),
+                lower_constraint: None,
+                upper_constraint: None,
+            },
+        );
+        if let Err(error) = self.enforce_bounds() {
+            self.remove_bounds(variable)?;
+            return Err(error);
+        }
+        self.release_bounds();
+        Ok(())
+    }
 
-    The tag will be updated with the marker and error symbols to use
-    for tracking the movement of the constraint in the tableau.
+    /* Remove the bounds of a variable.
 
-    */
-    fn create_row(&mut self, constraint: &Constraint) -> (Row, Tag) {
-        let expr = constraint.expression();
-        let mut row = Row::new(expr.constant());
-        let mut marker: Symbol;
-        let mut other: Option<Symbol> = None;
+    Throws
+    ------
+    UnknownBounds
+        The given variable has no bounds.
 
-        // Substitute the current basic variables into the row.
-        for term in expr.terms().iter() {
-            if !near_zero(term.coefficient()) {
-                let symbol = self.get_var_symbol(term.variable());
-                match self.m_rows.get(&symbol) {
-                    Some(existing_row) => row.insert_row(existing_row, term.coefficient()),
-                    None => row.insert_symbol(&symbol, term.coefficient()),
+    */
+    pub fn remove_bounds(&mut self, variable: &V) -> SolverResult<V> {
+        let info = match self.m_vars.get(variable) {
+            Some(symbol) => self.m_bounds.remove(&symbol.clone()),
+            None => None,
+        };
+        match info {
+            Some(info) => {
+                for constraint in [&info.lower_constraint, &info.upper_constraint]
+                    .iter()
+                    .filter_map(|c| c.as_ref())
+                {
+                    self.delete_constraint(constraint)?;
                 }
+                Ok(())
             }
+            None => Err(KiwiError::UnknownBounds {
+                variable: variable.clone(),
+            }),
         }
+    }
 
-        type RO = RelationalOperator;
+    /* Test whether a variable has bounds.
 
-        // Add the necessary slack, error, and dummy variables.
-        let op = constraint.op();
-        let c_strength = constraint.strength();
-        match op {
-            RO::GreaterEqual | RO::LessEqual => {
-                let coeff = if op == RO::LessEqual { 1.0 } else { -1.0 };
-                let slack = Symbol::new(SymbolKind::Slack, self.next_symbol_id());
-                row.insert_symbol(&slack, coeff);
-                marker = slack;
-                if c_strength < strength::REQUIRED {
-                    let error = Symbol::new(SymbolKind::Error, self.next_symbol_id());
-                    row.insert_symbol(&error, -coeff);
-                    self.m_objective.insert_symbol(&error, c_strength);
-                    other = Some(error);
-                }
-            }
-            RO::Equal => {
-                if c_strength < strength::REQUIRED {
-                    let errplus = Symbol::new(SymbolKind::Error, self.next_symbol_id());
-                    let errminus = Symbol::new(SymbolKind::Error, self.next_symbol_id());
-                    row.insert_symbol(&errplus, -1.0); // v = eplus - eminus
-                    row.insert_symbol(&errminus.clone(), 1.0); // v - eplus + eminus = 0
-                    self.m_objective.insert_symbol(&errplus, c_strength);
-                    self.m_objective.insert_symbol(&errminus, c_strength);
-                    marker = errplus;
-                    other = Some(errminus);
-                } else {
-                    let dummy = Symbol::new(SymbolKind::Dummy, self.next_symbol_id());
-                    row.insert_symbol(&dummy, 1.0);
-                    marker = dummy;
-                }
-            }
+    */
+    pub fn has_bounds(&self, variable: &V) -> bool {
+        match self.m_vars.get(variable) {
+            Some(symbol) => self.m_bounds.contains_key(symbol),
+            None => false,
         }
+    }
 
-        // Ensure the row as a positive constant.
-        if *row.constant() < 0.0 {
-            row.reverse_sign();
+    /* Suggest a value for the given edit variable.
+
+    This method should be used after an edit variable as been added to
+    the solver in order to suggest the value for that variable.
+
+    Throws
+    ------
+    UnknownEditVariable
+        The given edit variable has not been added to the solver.
+
+    */
+    pub fn suggest_value(&mut self, variable: &V, value: f64) -> SolverResult<V> {
+        // If the edit variable exists update the solver and perform a dual optimize
+        if self.m_edits.contains_key(variable) {
+            self.call_with_dual_guard(|solver| solver.apply_suggestion(variable, value))?;
+            self.reevaluate_disjunctions()?;
+            self.evaluate_rules()
+        } else {
+            Err(KiwiError::UnknownEditVariable {
+                variable: variable.clone(),
+            })
         }
+    }
 
-        (row, Tag { marker, other })
+    /* Suggest values for several edit variables at once.
+
+    All the suggestions are applied to the tableau before a single dual
+    optimization is performed, which is cheaper than calling suggest_value
+    for each variable (when dragging the corner of a box for example). If
+    a variable appears several times, the last value is used.
+
+    Throws
+    ------
+    UnknownEditVariable
+        One of the given variables has not been added to the solver as an
+        edit variable. No suggestion is applied.
+
+    */
+    pub fn suggest_values(&mut self, suggestions: &[(V, f64)]) -> SolverResult<V> {
+        if let Some((variable, _)) = suggestions
+            .iter()
+            .find(|(variable, _)| !self.m_edits.contains_key(variable))
+        {
+            return Err(KiwiError::UnknownEditVariable {
+                variable: variable.clone(),
+            });
+        }
+        self.call_with_dual_guard(|solver| {
+            for (variable, value) in suggestions.iter() {
+                solver.apply_suggestion(variable, *value);
+            }
+        })?;
+        self.reevaluate_disjunctions()?;
+        self.evaluate_rules()
     }
 
-    ///
-    fn call_with_dual_guard(&mut self, func: impl Fn(&mut Solver)) -> SolverResult {
-        func(self);
-        self.dual_optimize()
+    /* Set the constant of the expression of a constraint added to the solver.
+
+    The constant of the rows in which the constraint marker appears are
+    shifted in place, as suggest_value does for edit variables, and a dual
+    optimization restores the feasibility of the tableau. This avoids
+    removing and re-adding constraints whose offset changes at runtime.
+
+    The new constant is only used by this solver: the expression of the
+    Constraint (which may be shared with other solvers) is left unchanged.
+    Use constraint_constant to access the constant used by the solver.
+
+    Throws
+    ------
+    UnknownConstraint
+        The given constraint has not been added to the solver.
+
+    UnsatisfiableConstraint
+        The constraint is required and cannot be satisfied with the new
+        constant. The solver is left unchanged.
+
+    */
+    pub fn set_constant(&mut self, constraint: &Constraint<V>, constant: f64) -> SolverResult<V> {
+        let tag = match self.m_cns.get(constraint) {
+            Some(tag) => *tag,
+            None => {
+                return Err(KiwiError::UnknownConstraint {
+                    constraint: constraint.clone(),
+                });
+            }
+        };
+        let old = tag.constant;
+        if old == constant {
+            return Ok(());
+        }
+
+        // Changing the constant by d is equivalent to shifting the marker by
+        // d divided by the coefficient of the marker in the constraint row.
+        let delta = (constant - old) / Self::marker_coefficient(constraint);
+
+        // A basic dummy marker represents a redundant required constraint,
+        // which cannot be satisfied anymore if its constant changes.
+        if tag.marker.kind() == SymbolKind::Dummy {
+            if let Some(row) = self.m_rows.get(tag.marker) {
+                if !near_zero(
+                    *row.constant() - delta,
+                    self.m_config.feasibility_tolerance(),
+                ) {
+                    return Err(KiwiError::UnsatisfiableConstraint {
+                        constraint: constraint.clone(),
+                    });
+                }
+            }
+        }
+
+        match self.call_with_dual_guard(|solver| solver.shift_marker(tag, delta)) {
+            Ok(()) => {
+                self.m_cns
+                    .insert(constraint.clone(), Tag { constant, ..tag });
+                self.reevaluate_disjunctions()
+            }
+            Err(KiwiError::InternalSolverError { .. }) if tag.strength == strength::REQUIRED => {
+                // Undo the shift and restore the feasibility of the tableau.
+                self.m_infeasible_rows.clear();
+                self.shift_marker(tag, -delta);
+                let feasibility_tolerance = self.m_config.feasibility_tolerance();
+                for (symbol, row) in self.m_rows.iter() {
+                    if symbol.kind() != SymbolKind::External
+                        && *row.constant() < -feasibility_tolerance
+                    {
+                        self.m_infeasible_rows.push(*symbol);
+                    }
+                }
+                self.dual_optimize()?;
+                Err(KiwiError::UnsatisfiableConstraint {
+                    constraint: constraint.clone(),
+                })
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /* Add an explicit objective to the solver.
+
+    The expression, weighted by the strength, is added to the objective
+    function (minimizing the weighted errors of the constraints) and the
+    objective is then re-optimized. The returned handle can be used to
+    remove the objective.
+
+    Throws
+    ------
+    BadRequiredStrength
+        The given strength is >= required.
+
+    UnboundedObjective
+        The expression can be improved without bound. The objective is not
+        added to the solver.
+
+    UnknownVariable
+        A variable of the expression was not allocated by the solver.
+
+    */
+    pub fn add_objective(
+        &mut self,
+        expression: Expression<V>,
+        sense: Sense,
+        strength: f64,
+    ) -> Result<ObjectiveHandle, KiwiError<V>> {
+        let c_strength = strength::clip(strength);
+        if c_strength == strength::REQUIRED {
+            return Err(KiwiError::BadRequiredStrength);
+        }
+        self.check_expression(&expression)?;
+        let info = ObjectiveInfo {
+            expression,
+            sense,
+            strength: c_strength,
+        };
+        // The objective is registered before optimizing so that it is part
+        // of the objective function if the latter is recomputed.
+        let handle = ObjectiveHandle::new(self.next_symbol_id());
+        self.insert_objective_effects(&info, 1.0);
+        self.m_objectives.insert(handle, info);
+        if let Err(error) = self.optimize(OptimizationTarget::Objective) {
+            // The tableau is still feasible so removing the objective and
+            // re-optimizing restores the previous solution.
+            // Unwrapping is safe since the objective was just inserted.
+            let info = self.m_objectives.remove(&handle).unwrap();
+            self.insert_objective_effects(&info, -1.0);
+            self.optimize(OptimizationTarget::Objective)?;
+            return Err(error);
+        }
+        Ok(handle)
+    }
+
+    /* Remove an explicit objective from the solver.
+
+    Throws
+    ------
+    UnknownObjective
+        The given objective has not been added to the solver.
+
+    */
+    pub fn remove_objective(&mut self, objective: ObjectiveHandle) -> SolverResult<V> {
+        match self.m_objectives.remove(&objective) {
+            Some(info) => {
+                self.insert_objective_effects(&info, -1.0);
+                self.optimize(OptimizationTarget::Objective)
+            }
+            None => Err(KiwiError::UnknownObjective { objective }),
+        }
     }
 
+    /// Test whether an objective has been added to the solver.
     ///
-    fn dual_optimize(&mut self) -> SolverResult {
-        while !self.m_infeasible_rows.is_empty() {
-            // Get the last infeasible symbol.
-            // Unwrapping is safe since we know that the vector is not empty.
-            let leaving = self.m_infeasible_rows.pop().unwrap();
-            if let Some(row) = self.m_rows.get(&leaving) {
-                if !near_zero(*row.constant()) && *row.constant() < 0.0 {
-                    if let Some(entering) = self.get_dual_entering_symbol(&row) {
-                        // Pivot the entering symbol into the basis
-                        // Unwrapping is safe since we know the symbol is known
-                        // to be in the map, we shadow row since we need an owned
-                        // row not a reference to re-insert it.
-                        let mut row = self.m_rows.remove(&leaving).unwrap();
-                        row.solve_for_symbols(&leaving, &entering);
-                        self.substitute(&entering, &row);
-                        self.m_rows.insert(entering, row);
-                    } else {
-                        return Err(KiwiError::InternalSolverError {
-                            msg: String::from("Dual optimize failed."),
-                        });
+    pub fn has_objective(&self, objective: ObjectiveHandle) -> bool {
+        self.m_objectives.contains_key(&objective)
+    }
+
+    /* Add a disjunction: exactly one of the alternatives is enforced.
+
+    Each alternative is speculatively added to the tableau and removed
+    again, and the one leading to the lowest objective is kept (the first
+    one in case of a tie). The choice is reconsidered each time the
+    suggested values of the edit variables or the constraints change, but
+    the alternative only changes if the objective decreases by more than
+    the disjunction hysteresis of the configuration.
+
+    Throws
+    ------
+    UnsatisfiableDisjunction
+        No alternative can be added without conflicting with the
+        required constraints.
+
+    */
+    pub fn add_disjunction(
+        &mut self,
+        alternatives: Vec<Vec<Constraint<V>>>,
+    ) -> Result<DisjunctionHandle, KiwiError<V>> {
+        let eps = self.m_config.feasibility_tolerance();
+        let mut best: Option<(usize, [f64; strength::LEVELS])> = None;
+        for (index, alternative) in alternatives.iter().enumerate() {
+            if let Some(value) = self.evaluate_alternative(alternative)? {
+                let better = match &best {
+                    Some((_, best_value)) => {
+                        compare_levels(&value, best_value, eps) == Ordering::Less
                     }
+                    None => true,
+                };
+                if better {
+                    best = Some((index, value));
+                }
+            }
+        }
+        let active = match best {
+            Some((index, _)) => index,
+            None => return Err(KiwiError::UnsatisfiableDisjunction),
+        };
+        self.add_constraints(&alternatives[active])?;
+        let handle = DisjunctionHandle::new(self.next_symbol_id());
+        self.m_disjunctions.insert(
+            handle,
+            DisjunctionInfo {
+                alternatives,
+                active,
+            },
+        );
+        Ok(handle)
+    }
+
+    /* Remove a disjunction and the constraints of its active alternative.
+
+    Throws
+    ------
+    UnknownDisjunction
+        The given disjunction has not been added to the solver.
+
+    */
+    pub fn remove_disjunction(&mut self, disjunction: DisjunctionHandle) -> SolverResult<V> {
+        match self.m_disjunctions.remove(&disjunction) {
+            Some(info) => self.remove_constraints(&info.alternatives[info.active]),
+            None => Err(KiwiError::UnknownDisjunction { disjunction }),
+        }
+    }
+
+    /* Test whether a disjunction has been added to the solver.
+
+    */
+    pub fn has_disjunction(&self, disjunction: DisjunctionHandle) -> bool {
+        self.m_disjunctions.contains_key(&disjunction)
+    }
+
+    /* Get the index of the alternative of a disjunction in the tableau.
+
+    */
+    pub fn active_alternative(&self, disjunction: DisjunctionHandle) -> Option<usize> {
+        self.m_disjunctions
+            .get(&disjunction)
+            .map(|info| info.active)
+    }
+
+    /* Add a group of constraints, initially enabled.
+
+    The constraints of the group must not be added or removed directly.
+
+    Throws
+    ------
+    DuplicateConstraint
+        One of the constraints has already been added to the solver.
+
+    UnsatisfiableConstraint
+        One of the constraints cannot be satisfied.
+
+    In both cases none of the constraints is added.
+
+    */
+    pub fn add_group(
+        &mut self,
+        constraints: Vec<Constraint<V>>,
+    ) -> Result<ConstraintGroup, KiwiError<V>> {
+        self.add_constraints(&constraints)?;
+        let group = ConstraintGroup::new(self.next_symbol_id());
+        self.m_groups.insert(
+            group,
+            GroupInfo {
+                constraints,
+                enabled: true,
+            },
+        );
+        Ok(group)
+    }
+
+    /* Remove a group and its constraints from the solver.
+
+    Throws
+    ------
+    UnknownGroup
+        The given group has not been added to the solver.
+
+    */
+    pub fn remove_group(&mut self, group: ConstraintGroup) -> SolverResult<V> {
+        match self.m_groups.remove(&group) {
+            Some(info) if info.enabled => self.remove_constraints(&info.constraints),
+            Some(_) => Ok(()),
+            None => Err(KiwiError::UnknownGroup { group }),
+        }
+    }
+
+    /* Test whether a group has been added to the solver.
+
+    */
+    pub fn has_group(&self, group: ConstraintGroup) -> bool {
+        self.m_groups.contains_key(&group)
+    }
+
+    /* Add the constraints of a disabled group back to the tableau.
+
+    Enabling an enabled group does nothing.
+
+    Throws
+    ------
+    UnknownGroup
+        The given group has not been added to the solver.
+
+    UnsatisfiableConstraint
+        One of the constraints conflicts with the required constraints
+        added since the group was disabled. The group stays disabled.
+
+    */
+    pub fn enable_group(&mut self, group: ConstraintGroup) -> SolverResult<V> {
+        let constraints = match self.m_groups.get(&group) {
+            Some(info) if info.enabled => return Ok(()),
+            Some(info) => info.constraints.clone(),
+            None => return Err(KiwiError::UnknownGroup { group }),
+        };
+        self.add_constraints(&constraints)?;
+        self.m_groups[&group].enabled = true;
+        Ok(())
+    }
+
+    /* Remove the constraints of a group from the tableau in one batch.
+
+    Disabling a disabled group does nothing.
+
+    Throws
+    ------
+    UnknownGroup
+        The given group has not been added to the solver.
+
+    */
+    pub fn disable_group(&mut self, group: ConstraintGroup) -> SolverResult<V> {
+        let constraints = match self.m_groups.get(&group) {
+            Some(info) if !info.enabled => return Ok(()),
+            Some(info) => info.constraints.clone(),
+            None => return Err(KiwiError::UnknownGroup { group }),
+        };
+        // The group is marked disabled first since its constraints are no longer
+        // in the tableau even if the optimization fails.
+        self.m_groups[&group].enabled = false;
+        self.remove_constraints(&constraints)
+    }
+
+    /* Test whether a group is enabled (false if the group is unknown).
+
+    */
+    pub fn is_group_enabled(&self, group: ConstraintGroup) -> bool {
+        match self.m_groups.get(&group) {
+            Some(info) => info.enabled,
+            None => false,
+        }
+    }
+
+    /* Access the constraints of a group.
+
+    */
+    pub fn group_constraints(&self, group: ConstraintGroup) -> Option<&[Constraint<V>]> {
+        self.m_groups
+            .get(&group)
+            .map(|info| info.constraints.as_slice())
+    }
+
+    /* Test whether a constraint belongs to a group.
+
+    */
+    pub fn group_contains(&self, group: ConstraintGroup, constraint: &Constraint<V>) -> bool {
+        match self.m_groups.get(&group) {
+            Some(info) => info.constraints.contains(constraint),
+            None => false,
+        }
+    }
+
+    /* Add a rule enforcing one of two lists of constraints.
+
+    The then constraints are enforced when the condition expression,
+    evaluated on the current solution, compares to the threshold as
+    requested by the operator (Equal uses the feasibility tolerance).
+    The else constraints are enforced otherwise. The rules are evaluated
+    when they are added and after each suggestion of edit values (see
+    evaluate_rules).
+
+    Throws
+    ------
+    UnsatisfiableConstraint
+        The constraints of the selected branch cannot be satisfied. The
+        rule is not added.
+
+    RuleOscillation
+        See evaluate_rules. The rule is not added and the rules switched
+        by the evaluation are put back in their previous branch.
+
+    */
+    pub fn add_rule(
+        &mut self,
+        condition: Expression<V>,
+        op: RelationalOperator,
+        threshold: f64,
+        then_constraints: Vec<Constraint<V>>,
+        else_constraints: Vec<Constraint<V>>,
+    ) -> Result<RuleHandle, KiwiError<V>> {
+        let mut info = RuleInfo {
+            condition,
+            op,
+            threshold,
+            then_constraints,
+            else_constraints,
+            branch: RuleBranch::Then,
+        };
+        info.branch = self.rule_branch(&info);
+        self.add_constraints(info.constraints(info.branch))?;
+        let rule = RuleHandle::new(self.next_symbol_id());
+        let branches: Vec<(RuleHandle, RuleBranch)> = self
+            .m_rules
+            .iter()
+            .map(|(other, info)| (*other, info.branch))
+            .collect();
+        self.m_rules.insert(rule, info);
+        if let Err(error) = self.evaluate_rules() {
+            // Roll back the rule since the caller cannot remove it without its handle. The
+            // original error is reported, switch_rule keeps a branch in the tableau even
+            // when it fails.
+            if let Some(info) = self.m_rules.remove(&rule) {
+                self.remove_constraints(info.constraints(info.branch)).ok();
+            }
+            for (other, branch) in branches.iter() {
+                if self.m_rules[other].branch != *branch {
+                    self.switch_rule(*other, *branch).ok();
+                }
+            }
+            return Err(error);
+        }
+        Ok(rule)
+    }
+
+    /* Remove a rule and the constraints of its active branch.
+
+    Throws
+    ------
+    UnknownRule
+        The given rule has not been added to the solver.
+
+    */
+    pub fn remove_rule(&mut self, rule: RuleHandle) -> SolverResult<V> {
+        match self.m_rules.remove(&rule) {
+            Some(info) => self.remove_constraints(info.constraints(info.branch)),
+            None => Err(KiwiError::UnknownRule { rule }),
+        }
+    }
+
+    /* Test whether a rule has been added to the solver.
+
+    */
+    pub fn has_rule(&self, rule: RuleHandle) -> bool {
+        self.m_rules.contains_key(&rule)
+    }
+
+    /* Get the branch of a rule whose constraints are in the tableau.
+
+    */
+    pub fn active_branch(&self, rule: RuleHandle) -> Option<RuleBranch> {
+        self.m_rules.get(&rule).map(|info| info.branch)
+    }
+
+    /* Evaluate the rules until they are consistent with the solution.
+
+    The conditions of all the rules are evaluated on the current solution
+    and the rules whose branch changes are switched together. This is
+    repeated until no rule switches.
+
+    Throws
+    ------
+    RuleOscillation
+        A configuration of branches was reached twice, the rules switch
+        endlessly. The solver is left in the last configuration which is
+        consistent but does not match the conditions.
+
+    UnsatisfiableConstraint
+        The constraints of a branch cannot be satisfied. The rule keeps
+        its previous branch.
+
+    */
+    pub fn evaluate_rules(&mut self) -> SolverResult<V> {
+        let handles: Vec<RuleHandle> = self.m_rules.iter().map(|(rule, _)| *rule).collect();
+        let mut seen: Vec<Vec<RuleBranch>> = Vec::new();
+        loop {
+            let branches: Vec<RuleBranch> = handles
+                .iter()
+                .map(|rule| self.m_rules[rule].branch)
+                .collect();
+            let mut switches = Vec::new();
+            for rule in handles.iter() {
+                let info = &self.m_rules[rule];
+                let branch = self.rule_branch(info);
+                if branch != info.branch {
+                    switches.push((*rule, branch));
                 }
             }
+            if switches.is_empty() {
+                return Ok(());
+            }
+            if seen.contains(&branches) {
+                return Err(KiwiError::RuleOscillation);
+            }
+            seen.push(branches);
+
+            for (rule, branch) in switches.iter() {
+                self.switch_rule(*rule, *branch)?;
+            }
+        }
+    }
+
+    /* Update the values of the external solver variables.
+
+    The targets of the stay constraints are then moved to the new values.
+    Since the current solution satisfies the moved stays exactly, this
+    does not affect the feasibility nor the optimality of the tableau.
+
+    */
+    pub fn update_variable(&mut self) {
+        for (variable, symbol) in self.m_vars.iter_mut() {
+            if let Some(row) = self.m_rows.get(*symbol) {
+                variable.store(&mut self.m_values, *row.constant());
+            } else {
+                variable.store(&mut self.m_values, 0.0);
+            }
+        }
+
+        let mut shifts = Vec::with_capacity(self.m_stays.len());
+        for (variable, stay_info) in self.m_stays.iter_mut() {
+            let value = variable.load(&self.m_values);
+            shifts.push((stay_info.tag, value - stay_info.constant));
+            stay_info.constant = value;
+        }
+        for (tag, delta) in shifts.iter() {
+            self.shift_marker(*tag, *delta);
+        }
+    }
+
+    /* Mark a variable as integer or continuous and return its previous state.
+
+    Integer variables are only taken into account by solve_integer.
+
+    Throws
+    ------
+    UnknownVariable
+        The given variable was not allocated by the solver.
+
+    */
+    pub fn set_integer(&mut self, variable: &V, integer: bool) -> Result<bool, KiwiError<V>> {
+        if integer {
+            self.check_variable(variable)?;
+            let symbol = self.get_var_symbol(variable);
+            Ok(self.m_integers.insert(variable.clone(), symbol).is_some())
+        } else {
+            Ok(self.m_integers.remove(variable).is_some())
+        }
+    }
+
+    /* Test whether a variable is marked as integer.
+
+    */
+    pub fn is_integer(&self, variable: &V) -> bool {
+        self.m_integers.contains_key(variable)
+    }
+
+    /* Update the values of the external variables with an integer solution.
+
+    A branch-and-bound search looks for the solution which satisfies the
+    required constraints, gives an integer value to every integer
+    variable and minimizes the same objective as the continuous solver.
+    The values of the integer variables are rounded to remove the
+    rounding errors of the simplex.
+    The branching constraints are removed once the search completes so
+    the tableau is left unchanged, and the stays are not moved.
+
+    If the node limit of the configuration is reached, the best solution
+    found so far is used and NodeLimitReached is returned.
+
+    Throws
+    ------
+    IntegerInfeasible
+        The search completed without finding an integer solution, the
+        problem has none. The variables are not updated.
+
+    BranchNodeLimitReached
+        The node limit was reached before any integer solution was found.
+        The variables are not updated.
+
+    */
+    pub fn solve_integer(&mut self) -> Result<IntegerStatus, KiwiError<V>> {
+        let mut search = BranchSearch {
+            nodes: 0,
+            limited: false,
+            best_objective: None,
+            best_values: Vec::new(),
+        };
+        self.branch(&mut search)?;
+        match search.best_objective {
+            Some(_) => {
+                for (variable, value) in search.best_values.iter() {
+                    variable.store(&mut self.m_values, *value);
+                }
+                if search.limited {
+                    Ok(IntegerStatus::NodeLimitReached)
+                } else {
+                    Ok(IntegerStatus::Optimal)
+                }
+            }
+            None if search.limited => Err(KiwiError::BranchNodeLimitReached {
+                limit: self.m_config.max_branch_nodes(),
+            }),
+            None => Err(KiwiError::IntegerInfeasible),
+        }
+    }
+
+    /* Round the values of some variables to integers and update them.
+
+    Rounding the variables independently would break the required
+    equalities linking them, so the equalities whose coefficients are 1
+    or -1 and whose constant is an integer are processed first, using the
+    current solution as a reference:
+
+    - an equality with a single variable left to round determines it.
+    - the variables left in an equality where they all have the same
+      sign share the remainders using the largest remainder method, so
+      that the widths of siblings still sum to the size of their
+      container.
+    - otherwise the first variable left is rounded using the policy.
+
+    The variables which do not appear in those equalities are rounded
+    using the policy. The tableau is left unchanged.
+
+    The equalities which could not be kept exact (because of redundant
+    equalities for example) are returned.
+
+    */
+    pub fn round_solution(
+        &mut self,
+        variables: &[V],
+        policy: RoundingPolicy,
+    ) -> Vec<Constraint<V>> {
+        let eps = self.m_config.feasibility_tolerance();
+        let mut values = AssocVec::new();
+        for variable in variables.iter() {
+            values.insert(variable.clone(), self.variable_value(variable));
+        }
+        let equations = self.rounding_equations(&values);
+
+        let mut rounded: AssocVec<V, f64> = AssocVec::new();
+        loop {
+            // Select the equation with the fewest variables left, preferring the ones where
+            // those variables have the same sign.
+            let mut selected: Option<(usize, usize, bool)> = None;
+            for (index, equation) in equations.iter().enumerate() {
+                let mut count = 0;
+                let mut positive = false;
+                let mut negative = false;
+                for (variable, coefficient) in equation.terms.iter() {
+                    if !rounded.contains_key(variable) {
+                        count += 1;
+                        positive |= *coefficient > 0.0;
+                        negative |= *coefficient < 0.0;
+                    }
+                }
+                if count == 0 {
+                    continue;
+                }
+                let same_sign = !(positive && negative);
+                let better = match selected {
+                    None => true,
+                    Some((_, c, s)) => (same_sign && !s) || (same_sign == s && count < c),
+                };
+                if better {
+                    selected = Some((index, count, same_sign));
+                }
+            }
+            let (index, same_sign) = match selected {
+                Some((index, _, same_sign)) => (index, same_sign),
+                None => break,
+            };
+
+            let equation = &equations[index];
+            let mut total = -equation.constant;
+            let mut remaining = Vec::new();
+            for (variable, coefficient) in equation.terms.iter() {
+                match rounded.get(variable) {
+                    Some(value) => total -= coefficient * value,
+                    None => remaining.push((variable.clone(), *coefficient)),
+                }
+            }
+            if same_sign {
+                let sign = remaining[0].1;
+                let relaxed: Vec<f64> = remaining
+                    .iter()
+                    .map(|(variable, _)| *values.get(variable).unwrap())
+                    .collect();
+                let shares = distribute_remainders(&relaxed, total * sign);
+                for ((variable, _), share) in remaining.into_iter().zip(shares) {
+                    rounded.insert(variable, share);
+                }
+            } else {
+                let (variable, _) = remaining.swap_remove(0);
+                let value = policy.round(*values.get(&variable).unwrap());
+                rounded.insert(variable, value);
+            }
+        }
+
+        for (variable, value) in values.iter() {
+            if !rounded.contains_key(variable) {
+                rounded.insert(variable.clone(), policy.round(*value));
+            }
+        }
+        for (variable, value) in rounded.iter() {
+            variable.store(&mut self.m_values, *value);
+        }
+
+        equations
+            .iter()
+            .filter(|equation| {
+                let residual = equation
+                    .terms
+                    .iter()
+                    .map(|(variable, coefficient)| coefficient * rounded.get(variable).unwrap())
+                    .sum::<f64>()
+                    + equation.constant;
+                residual.abs() > eps
+            })
+            .map(|equation| equation.constraint.clone())
+            .collect()
+    }
+
+    /* Reset the solver to the empty starting condition.
+
+    This method resets the internal solver state to the empty starting
+    condition, as if no constraints or edit variables have been added.
+    This can be faster than deleting the solver and creating a new one
+    when the entire system must change, since it can avoid unecessary
+    heap (de)allocations.
+
+    The configuration is kept. Unlike kiwi, identifiers are not reused so that
+    the handles obtained before the reset remain unknown to the solver. The
+    variables allocated by a Solver<VarId> are not released: they remain
+    valid, keep their names and values, and can be used in the constraints
+    added after the reset.
+
+    */
+    pub fn reset(&mut self) {
+        self.clear_rows();
+        self.m_cns.clear();
+        self.m_vars.clear();
+        self.m_edits.clear();
+        self.m_stays.clear();
+        self.m_objectives.clear();
+        self.m_bounds.clear();
+        self.m_integers.clear();
+        self.m_disjunctions.clear();
+        self.m_groups.clear();
+        self.m_rules.clear();
+        self.m_infeasible_rows.clear();
+        self.m_objective = Row::new(1.0);
+        self.m_objective_levels = Self::objective_levels(self.m_config.strength_mode());
+        self.m_artificial = None;
+    }
+
+    // =============================================================================================
+    // --- Private methods -------------------------------------------------------------------------
+    // =============================================================================================
+
+    /* Remove a constraint from the tableau without optimizing.
+
+    The tableau remains feasible but the solution may not be optimal.
+
+    */
+    fn remove_constraint_rows(&mut self, constraint: &Constraint<V>) -> SolverResult<V> {
+        let tag = match self.m_cns.remove(constraint) {
+            Some(tag) => tag,
+            None => {
+                return Err(KiwiError::UnknownConstraint {
+                    constraint: constraint.clone(),
+                });
+            }
+        };
+
+        // Remove the error effects from the objective function
+        // *before* pivoting, or substitutions into the objective
+        // will lead to incorrect solver results.
+        self.remove_constraint_effects(tag);
+
+        // If the marker is basic, simply drop the row. Otherwise,
+        // pivot the marker into the basis and then drop the row.
+        if let Some(_row) = self.m_rows.remove(tag.marker) {
+        } else {
+            match self.get_marker_leaving_row(tag.marker) {
+                Some((leaving_symbol, mut leaving_row)) => {
+                    leaving_row.solve_for_symbols(leaving_symbol, tag.marker, &mut self.m_buffer);
+                    self.substitute(tag.marker, &leaving_row);
+                }
+                None => {
+                    return Err(KiwiError::InternalSolverError {
+                        msg: String::from("failed to find leaving row"),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /* Create a new Row object for the given constraint.
+
+    The terms in the constraint will be converted to cells in the row.
+    Any term in the constraint with a coefficient of zero is ignored.
+    This method uses the `getVarSymbol` method to get the symbol for
+    the variables added to the row. If the symbol for a given cell
+    variable is basic, the cell variable will be substituted with the
+    basic row.
+
+    The necessary slack and error variables will be added to the row.
+    If the constant for the row is negative, the sign for the row
+    will be inverted so the constant becomes positive.
+
+    The tag will be updated with the marker and error symbols to use
+    for tracking the movement of the constraint in the tableau.
+
+    */
+    fn create_row(&mut self, constraint: &Constraint<V>) -> (Row, Tag) {
+        let expr = constraint.expression();
+        let eps = self.m_config.zero_tolerance();
+        let mut row = Row::new(expr.constant());
+        let mut marker: Symbol;
+        let mut other: Option<Symbol> = None;
+
+        // Substitute the current basic variables into the row.
+        for term in expr.terms().iter() {
+            if !near_zero(term.coefficient(), eps) {
+                let symbol = self.get_var_symbol(term.variable());
+                match self.m_rows.get(symbol) {
+                    Some(existing_row) => {
+                        row.insert_row(existing_row, term.coefficient(), eps, &mut self.m_buffer)
+                    }
+                    None => row.insert_symbol(symbol, term.coefficient(), eps),
+                }
+            }
+        }
+
+        type RO = RelationalOperator;
+
+        // Add the necessary slack, error, and dummy variables.
+        let op = constraint.op();
+        let c_strength = constraint.strength();
+        match op {
+            RO::GreaterEqual | RO::LessEqual => {
+                let coeff = if op == RO::LessEqual { 1.0 } else { -1.0 };
+                let slack = Symbol::new(SymbolKind::Slack, self.next_symbol_id());
+                row.insert_symbol(slack, coeff, eps);
+                marker = slack;
+                if c_strength < strength::REQUIRED {
+                    let error = Symbol::new(SymbolKind::Error, self.next_symbol_id());
+                    row.insert_symbol(error, -coeff, eps);
+                    self.insert_objective_symbol(error, c_strength, 1.0);
+                    other = Some(error);
+                }
+            }
+            RO::Equal => {
+                if c_strength < strength::REQUIRED {
+                    let errplus = Symbol::new(SymbolKind::Error, self.next_symbol_id());
+                    let errminus = Symbol::new(SymbolKind::Error, self.next_symbol_id());
+                    row.insert_symbol(errplus, -1.0, eps); // v = eplus - eminus
+                    row.insert_symbol(errminus, 1.0, eps); // v - eplus + eminus = 0
+                    self.insert_objective_symbol(errplus, c_strength, 1.0);
+                    self.insert_objective_symbol(errminus, c_strength, 1.0);
+                    marker = errplus;
+                    other = Some(errminus);
+                } else {
+                    let dummy = Symbol::new(SymbolKind::Dummy, self.next_symbol_id());
+                    row.insert_symbol(dummy, 1.0, eps);
+                    marker = dummy;
+                }
+            }
+        }
+
+        // Ensure the row as a positive constant.
+        if *row.constant() < 0.0 {
+            row.reverse_sign();
+        }
+
+        (
+            row,
+            Tag {
+                marker,
+                other,
+                strength: c_strength,
+                constant: expr.constant(),
+            },
+        )
+    }
+
+    /* Update the tableau for a new suggested value of an edit variable.
+
+    The variable *must* be an edit variable. The dual optimization is left
+    to the caller.
+
+    */
+    fn apply_suggestion(&mut self, variable: &V, value: f64) {
+        // Unwrapping is safe since the caller ensures the edit exists.
+        let edit_info = &mut self.m_edits[variable];
+        let delta = value - edit_info.constant;
+        edit_info.constant = value;
+        let tag = edit_info.tag;
+        self.shift_marker(tag, delta);
+    }
+
+    /* Shift the value of the marker of a constraint by delta.
+
+    If the marker (or the other error symbol) is basic only its row is
+    updated, otherwise the constant of every row in which the marker
+    appears is updated. The rows which become infeasible are recorded for
+    the next dual optimization.
+
+    */
+    fn shift_marker(&mut self, tag: Tag, delta: f64) {
+        // Check first if the marker is basic.
+        if let Some(constant) = self.m_rows.add(tag.marker, -delta) {
+            if constant < 0.0 {
+                self.m_infeasible_rows.push(tag.marker);
+            }
+            return;
+        }
+
+        // Check next if the other symbol is basic. Its coefficient in the
+        // constraint row is always the opposite of the marker one.
+        if let Some(symbol) = tag.other.as_ref() {
+            if let Some(constant) = self.m_rows.add(*symbol, delta) {
+                if constant < 0.0 {
+                    self.m_infeasible_rows.push(*symbol);
+                }
+                return;
+            }
+        }
+
+        // Otherwise update each row where the marker exists.
+        self.m_rows
+            .shift(tag.marker, delta, &mut self.m_infeasible_rows);
+    }
+
+    /* Get the coefficient of the marker of a constraint in its row.
+
+    This is the coefficient used by create_row, before the row sign is
+    possibly reversed.
+
+    */
+    fn marker_coefficient(constraint: &Constraint<V>) -> f64 {
+        match constraint.op() {
+            RelationalOperator::LessEqual => 1.0,
+            RelationalOperator::GreaterEqual => -1.0,
+            RelationalOperator::Equal => {
+                if constraint.strength() < strength::REQUIRED {
+                    -1.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+
+    ///
+    fn call_with_dual_guard(&mut self, func: impl Fn(&mut Solver<V>)) -> SolverResult<V> {
+        func(self);
+        self.dual_optimize()
+    }
+
+    /// Restore the feasibility of the tableau after the constant of some rows changed.
+    ///
+    /// Infeasible rows are processed last in first out. When too many consecutive degenerate
+    /// pivots are performed, the solver switches to Bland's rule: the infeasible row with the
+    /// lowest symbol leaves the basis and ties in the entering ratio test are broken in favor
+    /// of the lowest symbol.
+    ///
+    /// The bounds of the basic variables which are violated are added to the tableau (as
+    /// infeasible rows) before each pivot and the inactive ones are released at the end.
+    ///
+    fn dual_optimize(&mut self) -> SolverResult<V> {
+        let mut pivots = 0;
+        let mut degenerate_pivots = 0;
+        loop {
+            self.activate_violated_bounds();
+            if self.m_infeasible_rows.is_empty() {
+                break;
+            }
+            let bland = degenerate_pivots >= DEGENERATE_PIVOTS_THRESHOLD;
+            let leaving = self.pop_infeasible_row(bland);
+            let entering = match self.m_rows.get(leaving) {
+                Some(row) if *row.constant() < -self.m_config.feasibility_tolerance() => {
+                    match self.get_dual_entering_symbol(row, bland) {
+                        Some(entering) => entering,
+                        None => {
+                            return Err(KiwiError::InternalSolverError {
+                                msg: String::from("Dual optimize failed."),
+                            });
+                        }
+                    }
+                }
+                _ => continue,
+            };
+            self.count_pivot(&mut pivots)?;
+
+            // A dual pivot is degenerate if the entering symbol does not contribute to the
+            // objective.
+            if near_zero(
+                self.m_objective.coefficient_for(entering),
+                self.m_config.zero_tolerance(),
+            ) {
+                degenerate_pivots += 1;
+            } else {
+                degenerate_pivots = 0;
+            }
+
+            // Pivot the entering symbol into the basis
+            // Unwrapping is safe since we know the symbol is known to be in the map.
+            let mut row = self.m_rows.remove(leaving).unwrap();
+            row.solve_for_symbols(leaving, entering, &mut self.m_buffer);
+            self.substitute(entering, &row);
+            self.m_rows.insert(entering, row);
+        }
+        self.release_bounds();
+        Ok(())
+    }
+
+    /* Add to the tableau the bounds violated by basic variables.
+
+    The bound rows are solved for their slack which is negative, so that
+    they are processed by the dual optimization.
+
+    */
+    fn activate_violated_bounds(&mut self) {
+        let tolerance = self.m_config.feasibility_tolerance();
+        let mut violated = Vec::new();
+        for (symbol, info) in self.m_bounds.iter() {
+            if let Some(row) = self.m_rows.get(*symbol) {
+                for side in [BoundSide::Lower, BoundSide::Upper].iter() {
+                    if info.constraint(*side).is_none()
+                        && info.is_violated(*side, *row.constant(), tolerance)
+                    {
+                        violated.push((*symbol, *side));
+                    }
+                }
+            }
+        }
+        for (symbol, side) in violated.iter() {
+            self.activate_bound(*symbol, *side);
+        }
+    }
+
+    /* Add one side of the bounds of a variable to the tableau.
+
+    The row is solved for the slack of the bound and is recorded as
+    infeasible if the bound is violated. The slack is returned.
+
+    */
+    fn activate_bound(&mut self, symbol: Symbol, side: BoundSide) -> Symbol {
+        // Unwrapping is safe since the caller ensures the bounds exist.
+        let constraint = self.m_bounds[&symbol].make_constraint(side);
+        let (mut row, tag) = self.create_row(&constraint);
+        row.solve_for(tag.marker);
+        if *row.constant() < 0.0 {
+            self.m_infeasible_rows.push(tag.marker);
+        }
+        let marker = tag.marker;
+        self.m_rows.insert(marker, row);
+        self.m_cns.insert(constraint.clone(), tag);
+        self.m_bounds
+            .get_mut(&symbol)
+            .unwrap()
+            .set_constraint(side, Some(constraint));
+        marker
+    }
+
+    /* Add to the tableau the bounds which cannot be left to the ratio tests.
+
+    Those are the bounds violated by the value of their variable (after
+    a new row was substituted for example). They are added as regular
+    constraints so that conflicts with the required constraints are
+    detected.
+
+    */
+    fn enforce_bounds(&mut self) -> SolverResult<V> {
+        let mut pending = Vec::new();
+        for (symbol, _info) in self.m_bounds.iter() {
+            for side in [BoundSide::Lower, BoundSide::Upper].iter() {
+                pending.push((*symbol, *side));
+            }
+        }
+        for (symbol, side) in pending.iter() {
+            // Adding a previous bound may have enforced this one as well.
+            if !self.must_enforce_bound(*symbol, *side) {
+                continue;
+            }
+            // Unwrapping is safe since must_enforce_bound checked the bounds exist.
+            let info = &mut self.m_bounds[symbol];
+            let constraint = info.make_constraint(*side);
+            info.set_constraint(*side, Some(constraint.clone()));
+            if let Err(error) = self.insert_constraint(constraint) {
+                if let Some(info) = self.m_bounds.get_mut(symbol) {
+                    info.set_constraint(*side, None);
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /// Test whether one side of the bounds must be added to the tableau by enforce_bounds.
+    fn must_enforce_bound(&self, symbol: Symbol, side: BoundSide) -> bool {
+        match self.m_bounds.get(&symbol) {
+            Some(info) if info.constraint(side).is_none() && info.limit(side).is_finite() => {
+                // A non-basic variable is zero.
+                let value = self.m_rows.get(symbol).map_or(0.0, |row| *row.constant());
+                info.is_violated(side, value, self.m_config.feasibility_tolerance())
+            }
+            _ => false,
+        }
+    }
+
+    /* Remove from the tableau the bounds which are not active.
+
+    A bound is inactive when its slack is basic and positive. Since the
+    slack does not appear in any other row, and neither does the error
+    symbol of a non-required bound, dropping the row and the effects of
+    the error does not affect the solution.
+
+    */
+    fn release_bounds(&mut self) {
+        let tolerance = self.m_config.feasibility_tolerance();
+        let mut released = Vec::new();
+        for (symbol, info) in self.m_bounds.iter() {
+            for side in [BoundSide::Lower, BoundSide::Upper].iter() {
+                if let Some(constraint) = info.constraint(*side) {
+                    if let Some(tag) = self.m_cns.get(constraint) {
+                        match self.m_rows.get(tag.marker) {
+                            Some(row) if *row.constant() > tolerance => {
+                                released.push((*symbol, *side, constraint.clone()));
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+        }
+        for (symbol, side, constraint) in released.iter() {
+            // The marker is basic so removing the rows cannot fail.
+            let _ = self.remove_constraint_rows(constraint);
+            self.m_bounds
+                .get_mut(symbol)
+                .unwrap()
+                .set_constraint(*side, None);
+        }
+    }
+
+    /// Get the next infeasible row to process during a dual optimization.
+    ///
+    /// The last row pushed is used unless Bland's rule is requested in which case the row
+    /// with the lowest symbol is used.
+    ///
+    fn pop_infeasible_row(&mut self, bland: bool) -> Symbol {
+        // Unwrapping is safe since the caller ensures that the vector is not empty.
+        if bland {
+            let (index, _) = self
+                .m_infeasible_rows
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.cmp(b.1))
+                .unwrap();
+            self.m_infeasible_rows.swap_remove(index)
+        } else {
+            self.m_infeasible_rows.pop().unwrap()
+        }
+    }
+
+    /// Increment the pivot count of an optimization and check it against the limit.
+    ///
+    fn count_pivot(&self, pivots: &mut usize) -> SolverResult<V> {
+        *pivots += 1;
+        match self.m_config.max_pivots() {
+            Some(limit) if *pivots > limit => Err(KiwiError::PivotLimitReached { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /* Choose the subject for solving for the row.
+
+    This method will choose the best subject for using as the solve
+    target for the row. An invalid symbol will be returned if there
+    is no valid target.
+
+    The symbols are chosen according to the following precedence:
+
+    1) The first symbol representing an external variable.
+    2) A negative slack or error tag variable.
+
+    If a subject cannot be found, an invalid symbol will be returned.
+
+    */
+    fn choose_subject(&self, row: &Row, tag: Tag) -> Symbol {
+        for (symbol, coeff) in row.cells().iter() {
+            if symbol.kind() == SymbolKind::External {
+                return *symbol;
+            }
+        }
+
+        if (tag.marker.kind() == SymbolKind::Slack || tag.marker.kind() == SymbolKind::Error)
+            && row.coefficient_for(tag.marker) < -self.m_config.pivot_tolerance()
+        {
+            return tag.marker;
+        }
+
+        match tag.other {
+            Some(ref symbol) => {
+                if (symbol.kind() == SymbolKind::Slack || symbol.kind() == SymbolKind::Error)
+                    && row.coefficient_for(*symbol) < -self.m_config.pivot_tolerance()
+                {
+                    return *symbol;
+                }
+            }
+            None => (),
+        }
+        Symbol::new(SymbolKind::Invalid, 0)
+    }
+
+    /// Check that the value of the variable is held by the solver.
+    ///
+    /// Only the VarId which were not allocated by the solver are rejected. The
+    /// variables are checked by the public methods before they reach
+    /// get_var_symbol, so that the variables of the tableau can always be
+    /// loaded and stored.
+    fn check_variable(&self, variable: &V) -> SolverResult<V> {
+        if variable.is_known(&self.m_values) {
+            Ok(())
+        } else {
+            Err(KiwiError::UnknownVariable {
+                variable: variable.clone(),
+            })
+        }
+    }
+
+    /// Check the variables of an expression with check_variable.
+    fn check_expression(&self, expression: &Expression<V>) -> SolverResult<V> {
+        for term in expression.terms().iter() {
+            self.check_variable(term.variable())?;
+        }
+        Ok(())
+    }
+
+    /// Get the symbol for the given variable.
+    ///
+    /// If a symbol does not exist for the variable, one will be created.
+    ///
+    fn get_var_symbol(&mut self, variable: &V) -> Symbol {
+        match self.m_vars.get(variable) {
+            Some(symbol) => *symbol,
+            None => {
+                let symbol = Symbol::new(SymbolKind::External, self.next_symbol_id());
+                self.m_vars.insert(variable.clone(), symbol);
+                symbol
+            }
+        }
+    }
+
+    ///
+    fn all_dummies(&self, row: &Row) -> bool {
+        for (symbol, _coeff) in row.cells().iter() {
+            if symbol.kind() != SymbolKind::Dummy {
+                return false;
+            }
+        }
+        true
+    }
+
+    ///
+    fn clear_rows(&mut self) {
+        self.m_rows.clear();
+    }
+
+    ///
+    fn add_with_artificial_variable(&mut self, row: Row) -> Result<bool, KiwiError<V>> {
+        // Create and add the artificial variable to the tableau
+        let art = Symbol::new(SymbolKind::Slack, self.next_symbol_id());
+        self.m_rows.insert(art, row.clone());
+        self.m_artificial = Some(row);
+
+        // Optimize the artificial objective. This is successful
+        // only if the artificial objective is optimized to zero.
+        // Using unwrap here is safe since we just set the artificial row
+        let result = self.optimize(OptimizationTarget::Artificial(art));
+        let success = near_zero(
+            *self.m_artificial.as_ref().unwrap().constant(),
+            self.m_config.feasibility_tolerance(),
+        );
+        self.m_artificial = None;
+        if let Err(error) = result {
+            self.drop_artificial_variable(art);
+            return Err(error);
+        }
+
+        // On failure the artificial variable is basic (otherwise its value would be zero).
+        if !success {
+            self.drop_artificial_variable(art);
+            return Ok(false);
+        }
+
+        // If the artificial variable is not basic, pivot the row so that
+        // it becomes basic. If the row is constant, exit early.
+        if self.m_rows.contains_key(art) {
+            let mut art_row = self.m_rows.remove(art).unwrap();
+            if art_row.cells().is_empty() {
+                return Ok(success);
+            }
+            let entering = self.any_pivotable_symbol(&art_row);
+            if entering.kind() == SymbolKind::Invalid {
+                return Ok(false);
+            } // unsatisfiable (will this ever happen?)
+            art_row.solve_for_symbols(art, entering, &mut self.m_buffer);
+            self.substitute(entering, &art_row);
+            self.m_rows.insert(entering, art_row);
+        }
+
+        // Remove the artificial variable from the tableau.
+        self.m_rows.remove_symbol(art);
+        self.m_objective.remove(art);
+        for level in self.m_objective_levels.iter_mut() {
+            level.remove(art);
+        }
+        Ok(success)
+    }
+
+    /* Drop the row of an artificial variable whose constraint is not added.
+
+    If the artificial variable is not basic (the optimization stopped
+    before reaching an optimum), it is first pivoted into the basis as the
+    marker of a removed constraint. Dropping its row then restores the
+    tableau as it was before the constraint was added.
+
+    */
+    fn drop_artificial_variable(&mut self, art: Symbol) {
+        if self.m_rows.remove(art).is_none() {
+            if let Some((leaving, mut leaving_row)) = self.get_marker_leaving_row(art) {
+                leaving_row.solve_for_symbols(leaving, art, &mut self.m_buffer);
+                self.substitute(art, &leaving_row);
+            }
+        }
+        self.m_objective.remove(art);
+        for level in self.m_objective_levels.iter_mut() {
+            level.remove(art);
+        }
+    }
+
+    ///
+    fn remove_constraint_effects(&mut self, tag: Tag) {
+        if tag.marker.kind() == SymbolKind::Error {
+            self.remove_marker_effects(tag.marker, tag.strength);
+        }
+        if let Some(symbol) = tag.other {
+            if symbol.kind() == SymbolKind::Error {
+                self.remove_marker_effects(symbol, tag.strength);
+            }
+        }
+    }
+
+    /* Remove the effects of an error marker on the objective function.
+
+    */
+    fn remove_marker_effects(&mut self, marker: Symbol, strength: f64) {
+        self.insert_marker_effects(marker, strength, -1.0);
+    }
+
+    /* Add the effects of an error marker on the objective function.
+
+    This is the counterpart of remove_constraint_effects used when the
+    strength of a constraint already in the tableau changes.
+
+    */
+    fn add_constraint_effects(&mut self, tag: Tag) {
+        if tag.marker.kind() == SymbolKind::Error {
+            self.insert_marker_effects(tag.marker, tag.strength, 1.0);
+        }
+        if let Some(symbol) = tag.other {
+            if symbol.kind() == SymbolKind::Error {
+                self.insert_marker_effects(symbol, tag.strength, 1.0);
+            }
+        }
+    }
+
+    /* Insert the effects of an error marker multiplied by a factor in the objective function.
+
+    If the marker is basic, its row is inserted in the objective function.
+
+    */
+    fn insert_marker_effects(&mut self, marker: Symbol, strength: f64, factor: f64) {
+        match self.m_rows.get(marker) {
+            Some(row) => {
+                let eps = self.m_config.zero_tolerance();
+                self.m_objective
+                    .insert_row(row, factor * strength, eps, &mut self.m_buffer);
+                for (level, weight) in self
+                    .m_objective_levels
+                    .iter_mut()
+                    .zip(strength::split(strength).iter())
+                {
+                    if *weight != 0.0 {
+                        level.insert_row(row, factor * weight, eps, &mut self.m_buffer);
+                    }
+                }
+            }
+            None => {
+                self.insert_objective_symbol(marker, strength, factor);
+            }
+        }
+    }
+
+    /* Insert the terms of an explicit objective multiplied by a factor in the objective function.
+
+    The symbols of the basic variables are substituted by their row.
+
+    */
+    fn insert_objective_effects(&mut self, info: &ObjectiveInfo<V>, factor: f64) {
+        for term in info.expression.terms().iter() {
+            if !near_zero(term.coefficient(), self.m_config.zero_tolerance()) {
+                let symbol = self.get_var_symbol(term.variable());
+                self.insert_marker_effects(
+                    symbol,
+                    info.strength,
+                    factor * info.sense.factor() * term.coefficient(),
+                );
+            }
+        }
+    }
+
+    /// Add a symbol weighted by a strength and a factor to the objective function.
+    ///
+    /// In lexicographic mode, the strength is split among the levels.
+    ///
+    fn insert_objective_symbol(&mut self, symbol: Symbol, strength: f64, factor: f64) {
+        let eps = self.m_config.zero_tolerance();
+        self.m_objective
+            .insert_symbol(symbol, factor * strength, eps);
+        let weights = strength::split(strength);
+        for (level, weight) in self.m_objective_levels.iter_mut().zip(weights.iter()) {
+            if *weight != 0.0 {
+                level.insert_symbol(symbol, factor * weight, eps);
+            }
+        }
+    }
+
+    /// Create the per level objective rows used by a strength mode.
+    ///
+    fn objective_levels(mode: StrengthMode) -> Vec<Row> {
+        match mode {
+            StrengthMode::Weighted => Vec::new(),
+            StrengthMode::Lexicographic => (0..strength::LEVELS).map(|_| Row::new(0.0)).collect(),
+        }
+    }
+
+    /// Get the coefficients of a symbol in each level of the objective function.
+    ///
+    fn objective_levels_for(&self, symbol: Symbol) -> [f64; strength::LEVELS] {
+        let mut coefficients = [0.0; strength::LEVELS];
+        for (c, level) in coefficients.iter_mut().zip(self.m_objective_levels.iter()) {
+            *c = level.coefficient_for(symbol);
+        }
+        coefficients
+    }
+
+    /// Substitute the parametric symbol with the given row.
+    ///
+    /// This method will substitute all instances of the parametric symbol
+    /// in the tableau and the objective function with the given row.
+    ///
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        let eps = self.m_config.zero_tolerance();
+        self.m_rows.substitute(
+            symbol,
+            row,
+            eps,
+            &mut self.m_buffer,
+            &mut self.m_infeasible_rows,
+        );
+        self.m_objective
+            .substitute(symbol, row, eps, &mut self.m_buffer);
+        for level in self.m_objective_levels.iter_mut() {
+            level.substitute(symbol, row, eps, &mut self.m_buffer);
+        }
+        if let Some(art_row) = &mut self.m_artificial {
+            art_row.substitute(symbol, row, eps, &mut self.m_buffer);
+        }
+    }
+
+    /// Optimize the system for the given objective function.
+    ///
+    /// This method performs iterations of Phase 2 of the simplex method
+    /// until the objective function reaches a minimum. When too many
+    /// consecutive degenerate pivots are performed, the solver switches to
+    /// Bland's rule to guarantee termination.
+    ///
+    fn optimize(&mut self, target: OptimizationTarget) -> SolverResult<V> {
+        let mut pivots = 0;
+        let mut degenerate_pivots = 0;
+        let mut refreshed = false;
+        loop {
+            let bland = degenerate_pivots >= DEGENERATE_PIVOTS_THRESHOLD;
+            let (entering, direction) = match target {
+                OptimizationTarget::Objective if !self.m_objective_levels.is_empty() => {
+                    self.get_lexicographic_entering_symbol(bland)
+                }
+                OptimizationTarget::Objective => self.get_entering_symbol(&self.m_objective),
+                OptimizationTarget::Artificial(_) => {
+                    self.get_entering_symbol(self.m_artificial.as_ref().unwrap())
+                }
+            };
+            if entering.kind() == SymbolKind::Invalid {
+                break;
+            }
+            self.count_pivot(&mut pivots)?;
+            if let Some(leaving) = self.get_leaving_row(entering, direction, bland) {
+                // A limiting bound enters the tableau and its slack leaves the basis.
+                let leaving_symbol = match leaving {
+                    Leaving::Row(symbol) => symbol,
+                    Leaving::Bound(symbol, side) => self.activate_bound(symbol, side),
+                };
+                // Unwrapping is safe since the leaving symbol is basic.
+                let mut leaving_row = self.m_rows.remove(leaving_symbol).unwrap();
+
+                // A pivot on a row with a zero constant does not improve the objective.
+                if near_zero(*leaving_row.constant(), self.m_config.zero_tolerance()) {
+                    degenerate_pivots += 1;
+                } else {
+                    degenerate_pivots = 0;
+                }
+
+                // pivot the entering symbol into the basis
+                leaving_row.solve_for_symbols(leaving_symbol, entering, &mut self.m_buffer);
+                self.substitute(entering, &leaving_row);
+                self.m_rows.insert(entering, leaving_row);
+                refreshed = false;
+            } else if !refreshed {
+                // The coefficient of the entering symbol may only be made of rounding errors,
+                // recompute the objective before concluding that it is unbounded.
+                self.refresh_objective(&target);
+                refreshed = true;
+            } else {
+                return Err(KiwiError::UnboundedObjective);
+            }
+        }
+
+        // The ratio test only keeps satisfied the bounds which were
+        // satisfied, the others are added to the tableau now.
+        if let OptimizationTarget::Objective = target {
+            self.enforce_bounds()?;
+            self.release_bounds();
+        }
+        Ok(())
+    }
+
+    /* Recompute an objective function from the tableau.
+
+    The objective functions are updated by substitution at each pivot and
+    accumulate rounding errors, which become significant when strong
+    constraints with large coefficients are added and removed. The
+    objective is rebuilt from the effects of the constraints and of the
+    explicit objectives, and the artificial objective from the row of the
+    artificial symbol.
+
+    */
+    fn refresh_objective(&mut self, target: &OptimizationTarget) {
+        match *target {
+            OptimizationTarget::Objective => {
+                self.m_objective = Row::new(1.0);
+                self.m_objective_levels = Self::objective_levels(self.m_config.strength_mode());
+                let tags: Vec<Tag> = self.m_cns.iter().map(|(_c, tag)| *tag).collect();
+                for tag in tags {
+                    self.add_constraint_effects(tag);
+                }
+                let objectives = std::mem::take(&mut self.m_objectives);
+                for (_handle, info) in objectives.iter() {
+                    self.insert_objective_effects(info, 1.0);
+                }
+                self.m_objectives = objectives;
+            }
+            OptimizationTarget::Artificial(art) => {
+                let artificial = match self.m_rows.get(art) {
+                    Some(row) => row.clone(),
+                    None => {
+                        let mut row = Row::new(0.0);
+                        row.insert_symbol(art, 1.0, self.m_config.zero_tolerance());
+                        row
+                    }
+                };
+                self.m_artificial = Some(artificial);
+            }
+        }
+    }
+
+    /// Compute the entering variable for a pivot operation.
+
+    /// This method will return first symbol in the objective function which
+    /// is non-dummy and has a coefficient less than zero, or which is
+    /// external and has a non-zero coefficient. If no symbol meets the
+    /// criteria, it means the objective function is at a minimum, and an
+    /// invalid symbol is returned.
+    ///
+    /// The non-basic external symbols are free variables, which improve the
+    /// objective by decreasing when their coefficient is positive. The
+    /// direction in which the entering symbol moves is returned with it.
+    ///
+    /// Since the cells of a row are sorted by symbol, the first candidate is
+    /// also the lowest symbol which is the entering rule of Bland's rule.
+    ///
+    fn get_entering_symbol(&self, objective: &Row) -> (Symbol, f64) {
+        let eps = self.m_config.zero_tolerance();
+        for (s, c) in objective.cells().iter() {
+            match s.kind() {
+                SymbolKind::Dummy => {}
+                SymbolKind::External if *c > eps => return (*s, -1.0),
+                _ if *c < 0.0 => return (*s, 1.0),
+                _ => {}
+            }
+        }
+        (Symbol::new(SymbolKind::Invalid, 0), 1.0)
+    }
+
+    /// Compute the entering variable for a pivot operation in lexicographic mode.
+    ///
+    /// This method will return a non-dummy symbol whose coefficients in the
+    /// objective levels are lexicographically negative, that is whose first
+    /// non-zero coefficient is negative, or an external symbol whose
+    /// coefficients are lexicographically positive, which then decreases.
+    /// Symbols improving the highest level are preferred, unless Bland's rule
+    /// is requested in which case the lowest symbol is returned. If no symbol
+    /// meets the criteria, an invalid symbol is returned.
+    ///
+    fn get_lexicographic_entering_symbol(&self, bland: bool) -> (Symbol, f64) {
+        let eps = self.m_config.zero_tolerance();
+        let direction_of = |s: Symbol| {
+            if s.kind() == SymbolKind::Dummy {
+                return None;
+            }
+            let levels = self.objective_levels_for(s);
+            let zero = [0.0; strength::LEVELS];
+            if compare_levels(&levels, &zero, 0.0) == Ordering::Less {
+                Some(1.0)
+            } else if s.kind() == SymbolKind::External
+                && compare_levels(&levels, &zero, eps) == Ordering::Greater
+            {
+                Some(-1.0)
+            } else {
+                None
+            }
+        };
+        if bland {
+            let mut candidates: Vec<Symbol> = self
+                .m_objective_levels
+                .iter()
+                .flat_map(|level| level.cells().iter().map(|(s, _c)| *s))
+                .collect();
+            candidates.sort();
+            candidates.dedup();
+            for s in candidates {
+                if let Some(direction) = direction_of(s) {
+                    return (s, direction);
+                }
+            }
+        } else {
+            for level in self.m_objective_levels.iter() {
+                for (s, c) in level.cells().iter() {
+                    let improving = *c < 0.0 || (s.kind() == SymbolKind::External && *c > 0.0);
+                    if improving {
+                        if let Some(direction) = direction_of(*s) {
+                            return (*s, direction);
+                        }
+                    }
+                }
+            }
+        }
+        (Symbol::new(SymbolKind::Invalid, 0), 1.0)
+    }
+
+    /* Compute the entering symbol for the dual optimize operation.
+
+    This method will return the symbol in the row which has a positive
+    coefficient and yields the minimum ratio for its respective symbol
+    in the objective function. The provided row *must* be infeasible.
+    If no symbol is found which meats the criteria, an invalid symbol
+    is returned.
+
+    When using Bland's rule, ratios that only differ by rounding errors
+    are considered equal and the lowest symbol is selected.
+
+    */
+    fn get_dual_entering_symbol(&self, row: &Row, bland: bool) -> Option<Symbol> {
+        if !self.m_objective_levels.is_empty() {
+            return self.get_lexicographic_dual_entering_symbol(row, bland);
+        }
+        let eps = self.m_config.zero_tolerance();
+        let pivot_eps = self.m_config.pivot_tolerance();
+        let mut entering = None;
+        let mut ratio = f64::MAX;
+        for (symbol, coeff) in row.cells().iter() {
+            if *coeff > pivot_eps && symbol.kind() != SymbolKind::Dummy {
+                let coefficient = self.m_objective.coefficient_for(*symbol);
+                let r = coefficient / coeff;
+                if r < ratio && !(bland && near_zero(ratio - r, eps)) {
+                    ratio = r;
+                    entering = Some(*symbol);
+                }
+            }
+        }
+        entering
+    }
+
+    /// Compute the entering symbol for the dual optimize operation in lexicographic mode.
+    ///
+    /// Identical to get_dual_entering_symbol except that the ratios are
+    /// computed for each level of the objective and compared lexicographically.
+    ///
+    fn get_lexicographic_dual_entering_symbol(&self, row: &Row, bland: bool) -> Option<Symbol> {
+        let eps = if bland {
+            self.m_config.zero_tolerance()
+        } else {
+            0.0
+        };
+        let pivot_eps = self.m_config.pivot_tolerance();
+        let mut entering = None;
+        let mut ratio = [f64::MAX; strength::LEVELS];
+        for (symbol, coeff) in row.cells().iter() {
+            if *coeff > pivot_eps && symbol.kind() != SymbolKind::Dummy {
+                let mut r = self.objective_levels_for(*symbol);
+                for c in r.iter_mut() {
+                    *c /= coeff;
+                }
+                if compare_levels(&r, &ratio, eps) == Ordering::Less {
+                    ratio = r;
+                    entering = Some(*symbol);
+                }
+            }
+        }
+        entering
+    }
+
+    /// Compute the row which holds the exit symbol for a pivot.
+    ///
+    /// This method will the exit symbol and the row containing it. If no
+    /// appropriate exit symbol is found, None will be returned. This indicates
+    /// that the objective function is unbounded.
+    ///
+    /// The direction is the one returned with the entering symbol: the
+    /// coefficients of the rows are multiplied by it so that a free entering
+    /// symbol which decreases is limited by the rows it drives negative.
+    ///
+    /// When using Bland's rule, ratios that only differ by rounding errors
+    /// are considered equal and the row with the lowest symbol is selected.
+    ///
+    /// The bounds which are not in the tableau also limit the step, those of
+    /// the basic variables as well as those of the entering symbol. If one
+    /// of them is the most limiting, it is returned and the caller adds it
+    /// to the tableau before pivoting on the row of its slack.
+    ///
+    fn get_leaving_row(
+        &mut self,
+        entering: Symbol,
+        direction: f64,
+        bland: bool,
+    ) -> Option<Leaving> {
+        let eps = self.m_config.zero_tolerance();
+        let pivot_eps = self.m_config.pivot_tolerance();
+        let mut ratio = f64::MAX;
+        let mut found: Option<Leaving> = None;
+        // The entering symbol is non-basic, its value is zero and moves along the direction.
+        let candidates = std::iter::once((entering, 0.0, direction)).chain(
+            self.m_rows
+                .column(entering)
+                .map(|(s, r)| (s, *r.constant(), r.coefficient_for(entering) * direction)),
+        );
+        for (s, constant, temp) in candidates {
+            if s.kind() != SymbolKind::External {
+                if s != entering && temp < -pivot_eps {
+                    let temp_ratio = -constant / temp;
+                    if temp_ratio < ratio && !(bland && near_zero(ratio - temp_ratio, eps)) {
+                        ratio = temp_ratio;
+                        found = Some(Leaving::Row(s));
+                    }
+                }
+            } else if let Some(info) = self.m_bounds.get(&s) {
+                // Bounded variable: the step is limited by the distance to the bound
+                // it moves toward, unless that bound is already in the tableau or
+                // violated (it is then enforced once the optimization completes).
+                let side = if temp < -pivot_eps {
+                    BoundSide::Lower
+                } else if temp > pivot_eps {
+                    BoundSide::Upper
+                } else {
+                    continue;
+                };
+                if info.constraint(side).is_some() || info.is_violated(side, constant, 0.0) {
+                    continue;
+                }
+                let temp_ratio = (info.limit(side) - constant) / temp;
+                if temp_ratio < ratio && !(bland && near_zero(ratio - temp_ratio, eps)) {
+                    ratio = temp_ratio;
+                    found = Some(Leaving::Bound(s, side));
+                }
+            }
+        }
+        found
+    }
+
+    ///
+    /* Compute the leaving row for a marker variable.
+
+    This method will return an iterator to the row in the row map
+    which holds the given marker variable. The row will be chosen
+    according to the following precedence:
+
+    0) A row whose basic variable is a dummy. Such a row stands for a
+       redundant equality and has a zero constant, so pivoting on it
+       leaves the value of every other row unchanged.
+
+    1) The row with a restricted basic varible and a negative coefficient
+       for the marker with the smallest ratio of -constant / coefficient.
+
+    2) The row with a restricted basic variable and the smallest ratio
+       of constant / coefficient.
+
+    3) The last unrestricted row which contains the marker.
+
+    If the marker does not exist in any row, the row map end() iterator
+    will be returned. This indicates an internal solver error since
+    the marker *should* exist somewhere in the tableau.
+
+    */
+    fn get_marker_leaving_row(&mut self, marker: Symbol) -> Option<(Symbol, Row)> {
+        let mut r1 = f64::MAX;
+        let mut r2 = f64::MAX;
+        let mut first: Option<Symbol> = None;
+        let mut second: Option<Symbol> = None;
+        let mut third: Option<Symbol> = None;
+        let pivot_eps = self.m_config.pivot_tolerance();
+        for (symbol, row) in self.m_rows.column(marker) {
+            let c = row.coefficient_for(marker);
+            if c.abs() <= pivot_eps {
+                continue;
+            }
+            if symbol.kind() == SymbolKind::Dummy {
+                first = Some(symbol);
+                break;
+            } else if symbol.kind() == SymbolKind::External {
+                third = Some(symbol);
+            } else if c < 0.0 {
+                let r = -(*row.constant()) / c;
+                if r < r1 {
+                    r1 = r;
+                    first = Some(symbol);
+                }
+            } else {
+                let r = (*row.constant()) / c;
+                if r < r2 {
+                    r2 = r;
+                    second = Some(symbol);
+                }
+            }
+        }
+
+        // If we have a symbol it exists in the mapping so unwraping is safe
+        // The following cannot currently use || to reduce the redundancy
+        if let Some(leaving) = first {
+            return Some((leaving, self.m_rows.remove(leaving).unwrap()));
+        }
+        if let Some(leaving) = second {
+            return Some((leaving, self.m_rows.remove(leaving).unwrap()));
+        }
+        if let Some(leaving) = third {
+            return Some((leaving, self.m_rows.remove(leaving).unwrap()));
+        }
+        None
+    }
+
+    /// Get the first Slack or Error symbol in the row.
+    ///
+    /// If no such symbol is present, and Invalid symbol will be returned.
+    ///
+    fn any_pivotable_symbol(&self, row: &Row) -> Symbol {
+        for (s, _coeff) in row.cells().iter() {
+            if s.kind() == SymbolKind::Slack || s.kind() == SymbolKind::Error {
+                return *s;
+            }
+        }
+        Symbol::new(SymbolKind::Invalid, 0)
+    }
+
+    /* Reconsider the active alternative of each disjunction.
+
+    The alternatives are evaluated again whenever the edit values or the
+    constraints change, since an inactive alternative may have become
+    cheaper even if the cost of the active one did not change. Only the
+    hysteresis of switch_alternative prevents flip-flopping.
+
+    If the evaluation fails, the active alternative is restored before the
+    error is returned.
+
+    */
+    fn reevaluate_disjunctions(&mut self) -> SolverResult<V> {
+        let handles: Vec<DisjunctionHandle> = self
+            .m_disjunctions
+            .iter()
+            .map(|(handle, _)| *handle)
+            .collect();
+        for handle in handles.iter() {
+            // Unwrapping is safe since the handles were just collected.
+            let info = &self.m_disjunctions[handle];
+            if info.alternatives.len() < 2 {
+                continue;
+            }
+            let alternatives = info.alternatives.clone();
+            let active = info.active;
+            let current = self.objective_value();
+
+            match self.switch_alternative(&alternatives, active, &current) {
+                Ok(chosen) => self.m_disjunctions[handle].active = chosen,
+                Err(error) => {
+                    self.restore_alternative(*handle, &alternatives, active);
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /* Replace the active alternative of a disjunction by the best one.
+
+    The active alternative is removed and the other ones are evaluated.
+    The best of them is added if it improves the current objective value
+    by more than the hysteresis, otherwise the active alternative is added
+    back. The index of the alternative in the tableau is returned.
+
+    */
+    fn switch_alternative(
+        &mut self,
+        alternatives: &[Vec<Constraint<V>>],
+        active: usize,
+        current: &[f64; strength::LEVELS],
+    ) -> Result<usize, KiwiError<V>> {
+        let eps = self.m_config.feasibility_tolerance();
+        let hysteresis = self.m_config.disjunction_hysteresis();
+        self.remove_constraints(&alternatives[active])?;
+        let mut best: Option<(usize, [f64; strength::LEVELS])> = None;
+        for (index, alternative) in alternatives.iter().enumerate() {
+            if index == active {
+                continue;
+            }
+            if let Some(value) = self.evaluate_alternative(alternative)? {
+                let better = match &best {
+                    Some((_, best_value)) => {
+                        compare_levels(&value, best_value, eps) == Ordering::Less
+                    }
+                    None => true,
+                };
+                if better {
+                    best = Some((index, value));
+                }
+            }
+        }
+        let chosen = match best {
+            Some((index, value)) if improves(&value, current, hysteresis, eps) => index,
+            _ => active,
+        };
+        self.add_constraints(&alternatives[chosen])?;
+        Ok(chosen)
+    }
+
+    /* Put the active alternative of a disjunction back after a failed switch.
+
+    The constraints of the other alternatives left in the tableau are
+    removed and the missing constraints of the active alternative are
+    added back. If the active alternative cannot be restored either, the
+    disjunction is removed from the solver.
+
+    */
+    fn restore_alternative(
+        &mut self,
+        handle: DisjunctionHandle,
+        alternatives: &[Vec<Constraint<V>>],
+        active: usize,
+    ) {
+        let leftovers: Vec<Constraint<V>> = alternatives
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != active)
+            .flat_map(|(_, alternative)| alternative.iter())
+            .filter(|c| self.has_constraint(c) && !alternatives[active].contains(c))
+            .cloned()
+            .collect();
+        let missing: Vec<Constraint<V>> = alternatives[active]
+            .iter()
+            .filter(|c| !self.has_constraint(c))
+            .cloned()
+            .collect();
+        let restored = self
+            .remove_constraints(&leftovers)
+            .and_then(|()| self.add_constraints(&missing));
+        if restored.is_err() {
+            let added: Vec<Constraint<V>> = alternatives[active]
+                .iter()
+                .filter(|c| self.has_constraint(c))
+                .cloned()
+                .collect();
+            // The original error is reported to the caller, the tableau is only left
+            // without the constraints of the disjunction.
+            self.remove_constraints(&added).ok();
+            self.m_disjunctions.remove(&handle);
+        }
+    }
+
+    /* Get the objective value obtained when an alternative is added.
+
+    The alternative is removed before returning. None is returned if the
+    alternative conflicts with the required constraints.
+
+    */
+    fn evaluate_alternative(
+        &mut self,
+        alternative: &[Constraint<V>],
+    ) -> Result<Option<[f64; strength::LEVELS]>, KiwiError<V>> {
+        match self.add_constraints(alternative) {
+            Ok(()) => {
+                let value = self.objective_value();
+                self.remove_constraints(alternative)?;
+                Ok(Some(value))
+            }
+            Err(KiwiError::UnsatisfiableConstraint { .. }) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /* Add several constraints.
+
+    If one of them cannot be added, the ones already added are removed
+    (including the failing one if it entered the tableau before the error,
+    as when the pivot limit is reached).
+
+    */
+    fn add_constraints(&mut self, constraints: &[Constraint<V>]) -> SolverResult<V> {
+        for (index, constraint) in constraints.iter().enumerate() {
+            if let Err(error) = self.insert_constraint(constraint.clone()) {
+                let added = if self.has_constraint(constraint) {
+                    index + 1
+                } else {
+                    index
+                };
+                self.remove_constraints(&constraints[..added])?;
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    /* Remove several constraints, in the reverse order of their addition.
+
+    The rows of the constraints are removed in one batch, the tableau
+    staying feasible, and a single optimization is performed at the end.
+
+    */
+    fn remove_constraints(&mut self, constraints: &[Constraint<V>]) -> SolverResult<V> {
+        for constraint in constraints.iter().rev() {
+            self.remove_constraint_rows(constraint)?;
+        }
+        self.optimize(OptimizationTarget::Objective)
+    }
+
+    /* Select the branch of a rule according to the current solution.
+
+    */
+    fn rule_branch(&self, info: &RuleInfo<V>) -> RuleBranch {
+        let eps = self.m_config.feasibility_tolerance();
+        let value = info
+            .condition
+            .terms()
+            .iter()
+            .map(|term| term.coefficient() * self.variable_value(term.variable()))
+            .sum::<f64>()
+            + info.condition.constant();
+        let holds = match info.op {
+            RelationalOperator::LessEqual => value <= info.threshold + eps,
+            RelationalOperator::GreaterEqual => value >= info.threshold - eps,
+            RelationalOperator::Equal => near_zero(value - info.threshold, eps),
+        };
+        if holds {
+            RuleBranch::Then
+        } else {
+            RuleBranch::Else
+        }
+    }
+
+    /* Replace the constraints of the active branch of a rule.
+
+    If the constraints of the new branch cannot be added, the ones of the
+    previous branch are added back.
+
+    */
+    fn switch_rule(&mut self, rule: RuleHandle, branch: RuleBranch) -> SolverResult<V> {
+        // Unwrapping is safe since the caller ensures the rule exists.
+        let info = &self.m_rules[&rule];
+        let previous = info.constraints(info.branch).clone();
+        let next = info.constraints(branch).clone();
+        self.remove_constraints(&previous)?;
+        if let Err(error) = self.add_constraints(&next) {
+            self.add_constraints(&previous)?;
+            return Err(error);
+        }
+        self.m_rules[&rule].branch = branch;
+        Ok(())
+    }
+
+    /* Explore a node of a branch-and-bound search.
+
+    The node is the current tableau. It is pruned if its objective is not
+    better than the best integer solution found. Otherwise the most
+    fractional integer variable is bounded to the integers surrounding
+    its value by temporary required constraints, starting with the
+    closest one, and the resulting nodes are explored.
+
+    */
+    fn branch(&mut self, search: &mut BranchSearch<V>) -> SolverResult<V> {
+        if search.nodes >= self.m_config.max_branch_nodes() {
+            search.limited = true;
+            return Ok(());
+        }
+        search.nodes += 1;
+
+        let eps = self.m_config.feasibility_tolerance();
+        let objective = self.objective_value();
+        if let Some(best) = &search.best_objective {
+            if compare_levels(&objective, best, eps) != Ordering::Less {
+                return Ok(());
+            }
+        }
+
+        let mut fractional: Option<(V, f64)> = None;
+        let mut distance = eps;
+        for (variable, symbol) in self.m_integers.iter() {
+            let value = self.symbol_value(*symbol);
+            if (value - value.round()).abs() > distance {
+                distance = (value - value.round()).abs();
+                fractional = Some((variable.clone(), value));
+            }
+        }
+        let (variable, value) = match fractional {
+            Some(fractional) => fractional,
+            None => {
+                search.best_objective = Some(objective);
+                // Integer variables are within the tolerance of an integer, remove
+                // the rounding errors so that they are pixel exact.
+                search.best_values = self
+                    .m_vars
+                    .iter()
+                    .map(|(variable, symbol)| {
+                        let value = self.symbol_value(*symbol);
+                        if self.m_integers.contains_key(variable) {
+                            (variable.clone(), value.round())
+                        } else {
+                            (variable.clone(), value)
+                        }
+                    })
+                    .collect();
+                return Ok(());
+            }
+        };
+
+        let mut branches = [
+            (RelationalOperator::LessEqual, value.floor()),
+            (RelationalOperator::GreaterEqual, value.ceil()),
+        ];
+        if value - value.floor() > 0.5 {
+            branches.swap(0, 1);
+        }
+        for (op, bound) in branches.iter() {
+            let constraint = Constraint::new(
+                Expression::new(vec![Term::new(variable.clone(), 1.0)], -bound),
+                *op,
+                strength::REQUIRED,
+            );
+            let result = match self.insert_constraint(constraint.clone()) {
+                Ok(()) => self.branch(search),
+                Err(KiwiError::UnsatisfiableConstraint { .. }) => Ok(()),
+                Err(error) => Err(error),
+            };
+            // The branching constraint is removed on every exit path, before
+            // the error of the sub-tree (if any) is propagated.
+            if self.has_constraint(&constraint) {
+                self.delete_constraint(&constraint)?;
+            }
+            result?;
+            if search.limited {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the current value of the objective, level by level in lexicographic mode.
+    ///
+    fn objective_value(&self) -> [f64; strength::LEVELS] {
+        let mut value = [0.0; strength::LEVELS];
+        if self.m_objective_levels.is_empty() {
+            value[0] = *self.m_objective.constant();
+        } else {
+            for (v, level) in value.iter_mut().zip(self.m_objective_levels.iter()) {
+                *v = *level.constant();
+            }
+        }
+        value
+    }
+
+    /* Collect the required equalities which can be kept exact when rounding.
+
+    The variables which are not rounded are replaced by their current
+    value.
+
+    */
+    fn rounding_equations(&self, values: &AssocVec<V, f64>) -> Vec<RoundingEquation<V>> {
+        let eps = self.m_config.feasibility_tolerance();
+        let mut equations = Vec::new();
+        for (constraint, tag) in self.m_cns.iter() {
+            if constraint.op() != RelationalOperator::Equal || tag.strength < strength::REQUIRED {
+                continue;
+            }
+            let expression = constraint.expression();
+            let mut terms: Vec<(V, f64)> = Vec::new();
+            let mut constant = tag.constant;
+            for term in expression.terms().iter() {
+                if !values.contains_key(term.variable()) {
+                    constant += term.coefficient() * self.variable_value(term.variable());
+                    continue;
+                }
+                // Keep the order of the expression so that the result is reproducible.
+                match terms.iter_mut().find(|(v, _)| v == term.variable()) {
+                    Some((_, coefficient)) => *coefficient += term.coefficient(),
+                    None => terms.push((term.variable().clone(), term.coefficient())),
+                }
+            }
+            terms.retain(|(_, coefficient)| !near_zero(*coefficient, eps));
+            if !terms.is_empty()
+                && terms
+                    .iter()
+                    .all(|(_, coefficient)| near_zero(coefficient.abs() - 1.0, eps))
+                && near_zero(constant - constant.round(), eps)
+            {
+                equations.push(RoundingEquation {
+                    constraint: constraint.clone(),
+                    terms: terms
+                        .into_iter()
+                        .map(|(variable, coefficient)| (variable, coefficient.signum()))
+                        .collect(),
+                    constant: constant.round(),
+                });
+            }
+        }
+        equations
+    }
+
+    /// Get the current value of a variable (zero if it is unknown to the solver).
+    ///
+    fn variable_value(&self, variable: &V) -> f64 {
+        match self.m_vars.get(variable) {
+            Some(symbol) => self.symbol_value(*symbol),
+            None => 0.0,
+        }
+    }
+
+    /// Get the current value of a symbol (zero if it is not basic).
+    ///
+    fn symbol_value(&self, symbol: Symbol) -> f64 {
+        match self.m_rows.get(symbol) {
+            Some(row) => *row.constant(),
+            None => 0.0,
+        }
+    }
+
+    ///
+    #[inline]
+    fn next_symbol_id(&mut self) -> u64 {
+        self.m_id_tick += 1;
+        self.m_id_tick
+    }
+}
+
+impl Solver<VarId> {
+    /* Allocate a new variable in the solver arena.
+
+    The name and the value (initially zero) of the variable are stored by
+    the solver, and the returned handle is only meaningful for this
+    solver. The variable is only added to the tableau once it is used in a
+    constraint, an edit or a stay.
+
+    */
+    pub fn new_variable(&mut self, name: &str) -> VarId {
+        let variable = VarId::new(self.m_names.len());
+        self.m_names.push(String::from(name));
+        self.m_values.push(0.0);
+        variable
+    }
+
+    /* Access the name of a variable allocated by the solver.
+
+    None is returned if the variable was not allocated by the solver.
+
+    */
+    pub fn name(&self, variable: VarId) -> Option<&str> {
+        self.m_names.get(variable.index()).map(String::as_str)
+    }
+
+    /* Set the name of a variable allocated by the solver and return the previous one.
+
+    Throws
+    ------
+    UnknownVariable
+        The given variable was not allocated by the solver.
+
+    */
+    pub fn set_name(&mut self, variable: VarId, name: &str) -> Result<String, KiwiError<VarId>> {
+        match self.m_names.get_mut(variable.index()) {
+            Some(current) => Ok(std::mem::replace(current, String::from(name))),
+            None => Err(KiwiError::UnknownVariable { variable }),
+        }
+    }
+
+    /* Access the value of a variable allocated by the solver.
+
+    As for Variable, the value is only updated by update_variable,
+    solve_integer and round_solution. None is returned if the variable was
+    not allocated by the solver.
+
+    */
+    pub fn value(&self, variable: VarId) -> Option<f64> {
+        self.m_values.get(variable.index()).copied()
+    }
+
+    /* Set the value of a variable allocated by the solver and return the previous one.
+
+    As for Variable, this does not affect the tableau but is used as the
+    target of the stay added afterward for the variable.
+
+    Throws
+    ------
+    UnknownVariable
+        The given variable was not allocated by the solver.
+
+    */
+    pub fn set_value(&mut self, variable: VarId, value: f64) -> Result<f64, KiwiError<VarId>> {
+        match self.m_values.get_mut(variable.index()) {
+            Some(current) => Ok(std::mem::replace(current, value)),
+            None => Err(KiwiError::UnknownVariable { variable }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Solver;
+    use crate::config::{SolverConfig, StrengthMode};
+    use crate::constraint::{Constraint, RelationalOperator};
+    use crate::errors::KiwiError;
+    use crate::integer::IntegerStatus;
+    use crate::objective::Sense;
+    use crate::rounding::RoundingPolicy;
+    use crate::rule::RuleBranch;
+    use crate::strength::{create, split, MEDIUM, REQUIRED, STRONG, WEAK};
+    use crate::variable::{VarId, Variable};
+
+    #[test]
+    fn test_pivot_limit() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(&x - 10.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        let rows = solver.m_rows.len();
+        let mut config = *solver.config();
+        config.set_max_pivots(Some(0));
+        solver.set_config(config);
+        assert_eq!(solver.config().max_pivots(), Some(0));
+        let lower = Constraint::new(&x - 20.0, RelationalOperator::GreaterEqual, REQUIRED);
+        match solver.add_constraint(lower.clone()) {
+            Err(KiwiError::PivotLimitReached { limit }) => assert_eq!(limit, 0),
+            _ => panic!("The pivot limit should have been reached."),
+        }
+
+        // The row of the artificial variable is dropped and the solver remains usable.
+        assert_eq!(solver.m_rows.len(), rows);
+        assert!(!solver.has_constraint(&lower));
+        config.set_max_pivots(None);
+        solver.set_config(config);
+        solver.update_variable();
+        assert!((*x.value() - 10.0).abs() < 1e-8);
+        solver.add_constraint(lower).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_pivot_limit_rollback() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(&x - 10.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        let rows = solver.m_rows.len();
+        let mut config = *solver.config();
+        config.set_max_pivots(Some(0));
+        solver.set_config(config);
+
+        // The row is added directly but its optimization needs a pivot.
+        let lower = Constraint::new(&x - 20.0, RelationalOperator::GreaterEqual, STRONG);
+        match solver.add_constraint(lower.clone()) {
+            Err(KiwiError::PivotLimitReached { limit }) => assert_eq!(limit, 0),
+            _ => panic!("The pivot limit should have been reached."),
+        }
+        assert!(!solver.has_constraint(&lower));
+        assert_eq!(solver.m_rows.len(), rows);
+        solver.update_variable();
+        assert!((*x.value() - 10.0).abs() < 1e-8);
+
+        config.set_max_pivots(None);
+        solver.set_config(config);
+        solver.add_constraint(lower).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_degenerate_spacings() {
+        let mut config = SolverConfig::new();
+        config.set_max_pivots(Some(1000));
+        let mut solver = Solver::with_config(config);
+        let vars: Vec<Variable> = (0..50).map(|_| Variable::new()).collect();
+        solver
+            .add_constraint(Constraint::new(
+                &vars[0] + 0.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        for pair in vars.windows(2) {
+            solver
+                .add_constraint(Constraint::new(
+                    &pair[1] - &pair[0],
+                    RelationalOperator::GreaterEqual,
+                    REQUIRED,
+                ))
+                .unwrap();
+        }
+        for v in vars.iter() {
+            solver
+                .add_constraint(Constraint::new(v + 0.0, RelationalOperator::Equal, WEAK))
+                .unwrap();
+        }
+        solver
+            .add_constraint(Constraint::new(
+                &vars[49] - 100.0,
+                RelationalOperator::Equal,
+                STRONG,
+            ))
+            .unwrap();
+        solver.update_variable();
+        for v in vars[..49].iter() {
+            assert!(v.value().abs() < 1e-8);
+        }
+        assert!((*vars[49].value() - 100.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_small_tolerances() {
+        // Coefficients below the default tolerance are ignored.
+        let x = Variable::new_with_name("x");
+        let mut solver = Solver::new();
+        solver
+            .add_constraint(Constraint::new(
+                &x * 1e-9 - 5e-9,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert_eq!(*x.value(), 0.0);
+
+        let mut config = SolverConfig::new();
+        config.set_zero_tolerance(1e-15);
+        config.set_feasibility_tolerance(1e-15);
+        let mut solver = Solver::with_config(config);
+        solver
+            .add_constraint(Constraint::new(
+                &x * 1e-9 - 5e-9,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_remove_edit_rounding() {
+        // Removing the edit of x leaves a rounding residue in the objective, which must not be
+        // mistaken for a direction in which the objective is unbounded.
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        let mut solver = Solver::new();
+        solver.add_edit_variable(&x, MEDIUM).unwrap();
+        let c = Constraint::new(
+            &x * -14.25 - &y * 3.25 + 129.0,
+            RelationalOperator::LessEqual,
+            MEDIUM,
+        );
+        solver.add_constraint(c.clone()).unwrap();
+        solver.add_edit_variable(&y, WEAK).unwrap();
+        solver.suggest_value(&x, -57.0).unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &x * -14.25 + &y * 9.75 + 10279.0,
+                RelationalOperator::Equal,
+                STRONG,
+            ))
+            .unwrap();
+        solver.remove_constraint(&c).unwrap();
+        solver.remove_edit_variable(&y).unwrap();
+        solver.remove_edit_variable(&x).unwrap();
+        solver.update_variable();
+        assert!((*x.value() * -14.25 + *y.value() * 9.75 + 10279.0).abs() < 1e-6);
+    }
+
+    /// Pull a variable towards 0 with a strong constraint and towards 100 with many weak ones.
+    fn strong_against_weak(mode: StrengthMode, count: usize, weak: f64) -> f64 {
+        let mut config = SolverConfig::new();
+        config.set_strength_mode(mode);
+        let mut solver = Solver::with_config(config);
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(&x + 0.0, RelationalOperator::Equal, STRONG))
+            .unwrap();
+        for _ in 0..count {
+            solver
+                .add_constraint(Constraint::new(&x - 100.0, RelationalOperator::Equal, weak))
+                .unwrap();
+        }
+        solver.update_variable();
+        let value = *x.value();
+        value
+    }
+
+    #[test]
+    fn test_weighted_strengths() {
+        // The heaviest strength of the weak level (1000 would be a medium strength).
+        let heavy_weak = create(0.0, 0.0, 999.0, 1.0);
+        assert_eq!(split(heavy_weak), [0.0, 0.0, 999.0]);
+        assert!(
+            (strong_against_weak(StrengthMode::Weighted, 2000, heavy_weak) - 100.0).abs() < 1e-8
+        );
+    }
+
+    #[test]
+    fn test_lexicographic_strengths() {
+        let heavy_weak = create(0.0, 0.0, 999.0, 1.0);
+        assert!(strong_against_weak(StrengthMode::Lexicographic, 10_000, heavy_weak).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_lexicographic_edit() {
+        let mut config = SolverConfig::new();
+        config.set_strength_mode(StrengthMode::Lexicographic);
+        let mut solver = Solver::with_config(config);
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        solver
+            .add_constraint(Constraint::new(
+                &y - &x,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        for _ in 0..100 {
+            solver
+                .add_constraint(Constraint::new(&y + 0.0, RelationalOperator::Equal, WEAK))
+                .unwrap();
+        }
+        solver.add_edit_variable(&x, STRONG).unwrap();
+        solver.suggest_value(&x, 50.0).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 50.0).abs() < 1e-8);
+        assert!((*y.value() - 50.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_set_strength() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let low = Constraint::new(&x + 0.0, RelationalOperator::Equal, MEDIUM);
+        let high = Constraint::new(&x - 100.0, RelationalOperator::GreaterEqual, WEAK);
+        solver.add_constraint(low.clone()).unwrap();
+        solver.add_constraint(high.clone()).unwrap();
+        solver.update_variable();
+        assert!(x.value().abs() < 1e-8);
+
+        solver.set_strength(&high, STRONG).unwrap();
+        assert_eq!(solver.constraint_strength(&high), Some(STRONG));
+        assert_eq!(high.strength(), WEAK);
+        solver.update_variable();
+        assert!((*x.value() - 100.0).abs() < 1e-8);
+
+        let very_strong = create(2.0, 0.0, 0.0, 1.0);
+        solver.set_strength(&low, very_strong).unwrap();
+        solver.update_variable();
+        assert!(x.value().abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_set_strength_errors() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let required = Constraint::new(&x + 0.0, RelationalOperator::GreaterEqual, REQUIRED);
+        let weak = Constraint::new(&x - 10.0, RelationalOperator::Equal, WEAK);
+        match solver.set_strength(&weak, STRONG) {
+            Err(KiwiError::UnknownConstraint { .. }) => (),
+            _ => panic!("The constraint should be unknown."),
+        }
+        solver.add_constraint(required.clone()).unwrap();
+        solver.add_constraint(weak.clone()).unwrap();
+        match solver.set_strength(&required, STRONG) {
+            Err(KiwiError::BadRequiredStrength) => (),
+            _ => panic!("The strength of a required constraint cannot change."),
+        }
+        match solver.set_strength(&weak, REQUIRED) {
+            Err(KiwiError::BadRequiredStrength) => (),
+            _ => panic!("A constraint cannot become required."),
+        }
+        assert_eq!(solver.constraint_strength(&weak), Some(WEAK));
+        assert_eq!(
+            solver.constraint_strength(&Constraint::from(&weak, WEAK)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_set_strength_shared() {
+        let x = Variable::new_with_name("x");
+        let c1 = Constraint::new(&x - 10.0, RelationalOperator::Equal, STRONG);
+        let c2 = Constraint::new(&x - 20.0, RelationalOperator::Equal, MEDIUM);
+        let mut first = Solver::new();
+        let mut second = Solver::new();
+        for solver in [&mut first, &mut second].iter_mut() {
+            solver.add_constraint(c1.clone()).unwrap();
+            solver.add_constraint(c2.clone()).unwrap();
+        }
+
+        // Changing the strength in one solver does not affect the other one.
+        first.set_strength(&c1, WEAK).unwrap();
+        assert_eq!(second.constraint_strength(&c1), Some(STRONG));
+        second.update_variable();
+        assert!((*x.value() - 10.0).abs() < 1e-8);
+        second.remove_constraint(&c1).unwrap();
+        second.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+
+        first.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+        first.remove_constraint(&c1).unwrap();
+        first.remove_constraint(&c2).unwrap();
+    }
+
+    #[test]
+    fn test_set_constant() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let width = Variable::new_with_name("width");
+        let container = Variable::new_with_name("container");
+        // x + width <= container - margin
+        let cn = Constraint::new(
+            &x + &width - &container + 10.0,
+            RelationalOperator::LessEqual,
+            REQUIRED,
+        );
+        solver.add_constraint(cn.clone()).unwrap();
+        for (v, value) in [(&x, 20.0), (&container, 200.0)].iter() {
+            solver
+                .add_constraint(Constraint::new(
+                    *v - *value,
+                    RelationalOperator::Equal,
+                    STRONG,
+                ))
+                .unwrap();
+        }
+        solver
+            .add_constraint(Constraint::new(
+                &width - 1000.0,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert!((*width.value() - 170.0).abs() < 1e-8);
+
+        solver.set_constant(&cn, 30.0).unwrap();
+        assert_eq!(solver.constraint_constant(&cn), Some(30.0));
+        assert_eq!(cn.expression().constant(), 10.0);
+        solver.update_variable();
+        assert!((*width.value() - 150.0).abs() < 1e-8);
+
+        solver.set_constant(&cn, 0.0).unwrap();
+        solver.update_variable();
+        assert!((*width.value() - 180.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_set_constant_equality() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        let required = Constraint::new(&x - 10.0, RelationalOperator::Equal, REQUIRED);
+        let strong = Constraint::new(&y - &x - 5.0, RelationalOperator::Equal, STRONG);
+        solver.add_constraint(required.clone()).unwrap();
+        solver.add_constraint(strong.clone()).unwrap();
+        solver.set_constant(&required, -20.0).unwrap();
+        solver.set_constant(&strong, -7.0).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+        assert!((*y.value() - 27.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_set_constant_unsatisfiable() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let lower = Constraint::new(&x + 0.0, RelationalOperator::GreaterEqual, REQUIRED);
+        let upper = Constraint::new(&x - 10.0, RelationalOperator::LessEqual, REQUIRED);
+        solver.add_constraint(lower).unwrap();
+        solver.add_constraint(upper.clone()).unwrap();
+        solver
+            .add_constraint(Constraint::new(&x - 5.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        match solver.set_constant(&upper, 5.0) {
+            Err(KiwiError::UnsatisfiableConstraint { .. }) => (),
+            _ => panic!("x <= -5 cannot be satisfied."),
+        }
+        assert_eq!(solver.constraint_constant(&upper), Some(-10.0));
+        solver.update_variable();
+        assert!((*x.value() - 5.0).abs() < 1e-8);
+
+        match solver.set_constant(
+            &Constraint::new(&x + 0.0, RelationalOperator::Equal, WEAK),
+            1.0,
+        ) {
+            Err(KiwiError::UnknownConstraint { .. }) => (),
+            _ => panic!("The constraint should be unknown."),
+        }
+    }
+
+    #[test]
+    fn test_set_constant_shared() {
+        let x = Variable::new_with_name("x");
+        let c1 = Constraint::new(&x - 10.0, RelationalOperator::GreaterEqual, REQUIRED);
+        let c2 = Constraint::new(&x + 0.0, RelationalOperator::Equal, WEAK);
+        let mut first = Solver::new();
+        let mut second = Solver::new();
+        for solver in [&mut first, &mut second].iter_mut() {
+            solver.add_constraint(c1.clone()).unwrap();
+            solver.add_constraint(c2.clone()).unwrap();
+        }
+
+        // Changing the constant in one solver does not affect the other one.
+        first.set_constant(&c1, -20.0).unwrap();
+        assert_eq!(second.constraint_constant(&c1), Some(-10.0));
+        second.set_constant(&c1, -30.0).unwrap();
+        second.update_variable();
+        assert!((*x.value() - 30.0).abs() < 1e-8);
+        first.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+
+        second.set_constant(&c1, -10.0).unwrap();
+        second.update_variable();
+        assert!((*x.value() - 10.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_objective() {
+        let mut solver = Solver::new();
+        let left = Variable::new_with_name("left");
+        let width = Variable::new_with_name("width");
+        solver
+            .add_constraint(Constraint::new(
+                &left + 0.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &width - 10.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &left + &width - 500.0,
+                RelationalOperator::LessEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &width - 100.0,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert!((*width.value() - 100.0).abs() < 1e-8);
+
+        let narrow = solver
+            .add_objective(&width + 0.0, Sense::Minimize, MEDIUM)
+            .unwrap();
+        assert!(solver.has_objective(narrow));
+        solver.update_variable();
+        assert!((*width.value() - 10.0).abs() < 1e-8);
+
+        solver.remove_objective(narrow).unwrap();
+        assert!(!solver.has_objective(narrow));
+        solver.update_variable();
+        assert!((*width.value() - 100.0).abs() < 1e-8);
+
+        let wide = solver
+            .add_objective(&width + &left, Sense::Maximize, MEDIUM)
+            .unwrap();
+        solver.update_variable();
+        assert!((*width.value() - 500.0).abs() < 1e-8);
+
+        solver.remove_objective(wide).unwrap();
+        match solver.remove_objective(wide) {
+            Err(KiwiError::UnknownObjective { objective }) => assert_eq!(objective, wide),
+            _ => panic!("The objective should be unknown."),
+        }
+    }
+
+    #[test]
+    fn test_unbounded_objective() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(
+                &x - 10.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(&x - 20.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        match solver.add_objective(&x + 0.0, Sense::Maximize, STRONG) {
+            Err(KiwiError::UnboundedObjective) => (),
+            _ => panic!("The objective should be unbounded."),
+        }
+        solver.update_variable();
+        assert!((*x.value() - 20.0).abs() < 1e-8);
+
+        match solver.add_objective(&x + 0.0, Sense::Minimize, REQUIRED) {
+            Err(KiwiError::BadRequiredStrength) => (),
+            _ => panic!("An objective cannot be required."),
+        }
+    }
+
+    #[test]
+    fn test_unbounded_free_objective() {
+        for mode in [StrengthMode::Weighted, StrengthMode::Lexicographic].iter() {
+            let mut config = SolverConfig::new();
+            config.set_strength_mode(*mode);
+
+            // An unconstrained variable can decrease without bound.
+            let mut solver = Solver::with_config(config);
+            let x = Variable::new_with_name("x");
+            match solver.add_objective(&x + 0.0, Sense::Minimize, WEAK) {
+                Err(KiwiError::UnboundedObjective) => (),
+                _ => panic!("The objective should be unbounded."),
+            }
+            solver
+                .add_constraint(Constraint::new(
+                    &x + 5.0,
+                    RelationalOperator::GreaterEqual,
+                    REQUIRED,
+                ))
+                .unwrap();
+            solver
+                .add_objective(&x + 0.0, Sense::Minimize, WEAK)
+                .unwrap();
+            solver.update_variable();
+            assert!((*x.value() + 5.0).abs() < 1e-8);
+
+            // The parametric variable of the equality can decrease without bound.
+            let mut solver = Solver::with_config(config);
+            let x = Variable::new_with_name("x");
+            let y = Variable::new_with_name("y");
+            solver
+                .add_constraint(Constraint::new(
+                    &x + &y - 10.0,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                ))
+                .unwrap();
+            match solver.add_objective(&y + 0.0, Sense::Maximize, WEAK) {
+                Err(KiwiError::UnboundedObjective) => (),
+                _ => panic!("The objective should be unbounded."),
+            }
+            solver
+                .add_constraint(Constraint::new(
+                    &y - 4.0,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                ))
+                .unwrap();
+            solver
+                .add_objective(&y + 0.0, Sense::Maximize, WEAK)
+                .unwrap();
+            solver.update_variable();
+            assert!((*x.value() - 6.0).abs() < 1e-8);
+            assert!((*y.value() - 4.0).abs() < 1e-8);
         }
-        Ok(())
     }
 
-    /* Choose the subject for solving for the row.
+    #[test]
+    fn test_stay() {
+        let mut solver = Solver::new();
+        let a = Variable::new_with_name("a");
+        let b = Variable::new_with_name("b");
+        solver
+            .add_constraint(Constraint::new(
+                &b - &a - 10.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        b.set_value(50.0);
+        solver.add_stay(&a, WEAK).unwrap();
+        solver.add_stay(&b, WEAK).unwrap();
+        assert!(solver.has_stay(&b));
+        match solver.add_stay(&b, WEAK) {
+            Err(KiwiError::DuplicateStayVariable { .. }) => (),
+            _ => panic!("The stay should be a duplicate."),
+        }
+        solver.add_edit_variable(&a, STRONG).unwrap();
 
-    This method will choose the best subject for using as the solve
-    target for the row. An invalid symbol will be returned if there
-    is no valid target.
+        solver.suggest_value(&a, 20.0).unwrap();
+        solver.update_variable();
+        assert!((*a.value() - 20.0).abs() < 1e-8);
+        assert!((*b.value() - 50.0).abs() < 1e-8);
 
-    The symbols are chosen according to the following precedence:
+        solver.suggest_value(&a, 45.0).unwrap();
+        solver.update_variable();
+        assert!((*b.value() - 55.0).abs() < 1e-8);
 
-    1) The first symbol representing an external variable.
-    2) A negative slack or error tag variable.
+        // b stays at its last solved value instead of its initial one.
+        solver.suggest_value(&a, 0.0).unwrap();
+        solver.update_variable();
+        assert!(a.value().abs() < 1e-8);
+        assert!((*b.value() - 55.0).abs() < 1e-8);
 
-    If a subject cannot be found, an invalid symbol will be returned.
+        solver.remove_stay(&b).unwrap();
+        assert!(!solver.has_stay(&b));
+        match solver.remove_stay(&b) {
+            Err(KiwiError::UnknownStayVariable { .. }) => (),
+            _ => panic!("The stay should be unknown."),
+        }
+    }
 
-    */
-    fn choose_subject(&self, row: &Row, tag: &Tag) -> Symbol {
-        for (symbol, coeff) in row.cells().iter() {
-            if symbol.kind() == SymbolKind::External {
-                return symbol.clone();
-            }
+    #[test]
+    fn test_suggest_values() {
+        let mut solver = Solver::new();
+        let left = Variable::new_with_name("left");
+        let top = Variable::new_with_name("top");
+        let width = Variable::new_with_name("width");
+        solver
+            .add_constraint(Constraint::new(
+                &width - 10.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        for v in [&left, &top].iter() {
+            solver.add_edit_variable(v, STRONG).unwrap();
         }
+        solver
+            .suggest_values(&[(left.clone(), 10.0), (top.clone(), 20.0)])
+            .unwrap();
+        solver.update_variable();
+        assert!((*left.value() - 10.0).abs() < 1e-8);
+        assert!((*top.value() - 20.0).abs() < 1e-8);
 
-        if tag.marker.kind() == SymbolKind::Slack
-            || tag.marker.kind() == SymbolKind::Error && row.coefficient_for(&tag.marker) < 0.0
-        {
-            return tag.marker.clone();
+        match solver.suggest_values(&[(left.clone(), 30.0), (width.clone(), 40.0)]) {
+            Err(KiwiError::UnknownEditVariable { variable }) => assert_eq!(variable, width),
+            _ => panic!("width is not an edit variable."),
         }
+        solver.update_variable();
+        assert!((*left.value() - 10.0).abs() < 1e-8);
 
-        match tag.other {
-            Some(ref symbol) => {
-                if symbol.kind() == SymbolKind::Slack
-                    || symbol.kind() == SymbolKind::Error && row.coefficient_for(&symbol) < 0.0
-                {
-                    return symbol.clone();
-                }
-            }
-            None => (),
+        solver
+            .suggest_values(&[(left.clone(), 30.0), (left.clone(), 50.0)])
+            .unwrap();
+        solver.update_variable();
+        assert!((*left.value() - 50.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        solver
+            .add_constraint(Constraint::new(
+                &y - &x * 2.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.add_edit_variable(&x, STRONG).unwrap();
+        solver.set_bounds(&x, 0.0, 100.0, REQUIRED).unwrap();
+        assert!(solver.has_bounds(&x));
+        assert!(!solver.has_bounds(&y));
+
+        solver.suggest_value(&x, 150.0).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 100.0).abs() < 1e-8);
+        assert!((*y.value() - 200.0).abs() < 1e-8);
+
+        solver.suggest_value(&x, -5.0).unwrap();
+        solver.update_variable();
+        assert!(x.value().abs() < 1e-8);
+
+        // Once the bounds are inactive, they leave the tableau.
+        let rows = solver.m_rows.len();
+        solver.suggest_value(&x, 50.0).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 50.0).abs() < 1e-8);
+        assert!(solver.m_rows.len() < rows);
+
+        match solver.set_bounds(&x, 10.0, 0.0, REQUIRED) {
+            Err(KiwiError::InvalidBounds { .. }) => (),
+            _ => panic!("The bounds should be invalid."),
         }
-        Symbol::new(SymbolKind::Invalid, 0)
+        solver.remove_bounds(&x).unwrap();
+        assert!(!solver.has_bounds(&x));
+        match solver.remove_bounds(&x) {
+            Err(KiwiError::UnknownBounds { .. }) => (),
+            _ => panic!("The bounds should be unknown."),
+        }
+        solver.suggest_value(&x, 150.0).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 150.0).abs() < 1e-8);
     }
 
-    /// Get the symbol for the given variable.
-    ///
-    /// If a symbol does not exist for the variable, one will be created.
-    ///
-    fn get_var_symbol(&mut self, variable: &Variable) -> Symbol {
-        match self.m_vars.get(variable) {
-            Some(symbol) => symbol.clone(),
-            None => {
-                let symbol = Symbol::new(SymbolKind::External, self.next_symbol_id());
-                self.m_vars.insert(variable.clone(), symbol.clone());
-                symbol
-            }
+    #[test]
+    fn test_bounds_tableau_size() {
+        let count = 100;
+        let mut bounded = Solver::new();
+        let mut explicit = Solver::new();
+        for i in 0..count {
+            let v = Variable::new();
+            let target = Constraint::new(&v - 10.0 * i as f64, RelationalOperator::Equal, WEAK);
+            bounded.add_constraint(target.clone()).unwrap();
+            bounded.set_bounds(&v, 0.0, 1920.0, REQUIRED).unwrap();
+            explicit.add_constraint(target).unwrap();
+            explicit
+                .add_constraint(Constraint::new(
+                    &v + 0.0,
+                    RelationalOperator::GreaterEqual,
+                    REQUIRED,
+                ))
+                .unwrap();
+            explicit
+                .add_constraint(Constraint::new(
+                    &v - 1920.0,
+                    RelationalOperator::LessEqual,
+                    REQUIRED,
+                ))
+                .unwrap();
         }
+        // Only the bound of the first variable (whose target is 0) is active.
+        assert!(bounded.m_rows.len() <= count + 1);
+        assert_eq!(explicit.m_rows.len(), 3 * count);
     }
 
-    ///
-    fn all_dummies(&self, row: &Row) -> bool {
-        for (symbol, _coeff) in row.cells().iter() {
-            if symbol.kind() != SymbolKind::Dummy {
-                return false;
+    #[test]
+    fn test_bounds_unsatisfiable() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(
+                &x - 200.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        match solver.set_bounds(&x, 0.0, 100.0, REQUIRED) {
+            Err(KiwiError::UnsatisfiableConstraint { .. }) => (),
+            _ => panic!("The bounds should be unsatisfiable."),
+        }
+        assert!(!solver.has_bounds(&x));
+        solver.update_variable();
+        assert!((*x.value() - 200.0).abs() < 1e-8);
+
+        let y = Variable::new_with_name("y");
+        solver
+            .add_constraint(Constraint::new(&y - 50.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        solver.set_bounds(&y, 0.0, 100.0, REQUIRED).unwrap();
+        let too_large = Constraint::new(&y - 300.0, RelationalOperator::Equal, REQUIRED);
+        match solver.add_constraint(too_large.clone()) {
+            Err(KiwiError::UnsatisfiableConstraint { constraint }) => {
+                assert_eq!(constraint, too_large)
             }
+            _ => panic!("The constraint should be unsatisfiable."),
         }
-        true
+        assert!(!solver.has_constraint(&too_large));
+        solver.update_variable();
+        assert!((*y.value() - 50.0).abs() < 1e-8, "{}", y.value());
+
+        // A non-required constraint is clamped instead.
+        solver
+            .add_constraint(Constraint::new(
+                &y - 300.0,
+                RelationalOperator::Equal,
+                STRONG,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert!((*y.value() - 100.0).abs() < 1e-8);
     }
 
-    ///
-    fn clear_rows(&mut self) {
-        self.m_rows.clear();
+    #[test]
+    fn test_bounds_objective() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        solver
+            .add_constraint(Constraint::new(
+                &x + &y - 150.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.set_bounds(&x, 0.0, 100.0, REQUIRED).unwrap();
+        solver.set_bounds(&y, 0.0, f64::INFINITY, REQUIRED).unwrap();
+        solver
+            .add_objective(&x + 0.0, Sense::Maximize, MEDIUM)
+            .unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 100.0).abs() < 1e-8);
+        assert!((*y.value() - 50.0).abs() < 1e-8);
     }
 
-    ///
-    fn add_with_artificial_variable(&mut self, row: Row) -> bool {
-        // Create and add the artificial variable to the tableau
-        let art = Symbol::new(SymbolKind::Slack, self.next_symbol_id());
-        self.m_rows.insert(art.clone(), row.clone());
-        self.m_artificial = Some(row);
+    #[test]
+    fn test_weak_bounds() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(
+                &x - 200.0,
+                RelationalOperator::Equal,
+                MEDIUM,
+            ))
+            .unwrap();
+        solver.set_bounds(&x, 0.0, 100.0, STRONG).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 100.0).abs() < 1e-8);
 
-        // Optimize the artificial objective. This is successful
-        // only if the artificial objective is optimized to zero.
-        // Using unwrap here is safe since we just set the artificial row
-        self.optimize(OptimizationTarget::Artificial);
-        let success = near_zero(*self.m_artificial.as_ref().unwrap().constant());
-        self.m_artificial = None;
+        solver.set_bounds(&x, 0.0, 100.0, WEAK).unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 200.0).abs() < 1e-8);
+    }
 
-        // If the artificial variable is not basic, pivot the row so that
-        // it becomes basic. If the row is constant, exit early.
-        if self.m_rows.contains_key(&art) {
-            let mut art_row = self.m_rows.remove(&art).unwrap();
-            if art_row.cells().is_empty() {
-                return success;
+    #[test]
+    fn test_bounds_released() {
+        for &bound_strength in [REQUIRED, STRONG].iter() {
+            let mut solver = Solver::new();
+            let x = Variable::new_with_name("x");
+            let y = Variable::new_with_name("y");
+            solver
+                .add_constraint(Constraint::new(
+                    &y - &x * 2.0,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                ))
+                .unwrap();
+            solver.add_edit_variable(&x, MEDIUM).unwrap();
+            solver.set_bounds(&x, 0.0, 100.0, bound_strength).unwrap();
+            solver.set_bounds(&y, -50.0, 150.0, bound_strength).unwrap();
+            let constraints = solver.m_cns.len();
+            let rows = solver.m_rows.len();
+
+            // The bounds which became active are released once they stop binding.
+            for _ in 0..10 {
+                for &value in [150.0, -20.0, 50.0].iter() {
+                    solver.suggest_value(&x, value).unwrap();
+                    solver.update_variable();
+                    let expected = value.clamp(0.0, 75.0);
+                    assert!((*x.value() - expected).abs() < 1e-8, "{}", x.value());
+                }
+                assert_eq!(solver.m_cns.len(), constraints);
+                assert_eq!(solver.m_rows.len(), rows);
             }
-            let entering = self.any_pivotable_symbol(&art_row);
-            if entering.kind() == SymbolKind::Invalid {
-                return false;
-            } // unsatisfiable (will this ever happen?)
-            art_row.solve_for_symbols(&art, &entering);
-            self.substitute(&entering, &art_row);
-            self.m_rows.insert(entering, art_row);
         }
+    }
 
-        // Remove the artificial variable from the tableau.
-        for (s, r) in self.m_rows.iter_mut() {
-            r.remove(&art);
-        }
-        self.m_objective.remove(&art);
-        success
+    #[test]
+    fn test_bounds_entering() {
+        // The variable is not in any row, only its own bound limits the step.
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver.set_bounds(&x, -10.0, 100.0, REQUIRED).unwrap();
+        assert!(solver.m_cns.is_empty());
+        solver
+            .add_objective(&x + 0.0, Sense::Maximize, MEDIUM)
+            .unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 100.0).abs() < 1e-8);
+        assert_eq!(solver.m_cns.len(), 1);
     }
 
-    ///
-    fn remove_constraint_effects(&mut self, constraint: &Constraint, tag: &Tag) {
-        if tag.marker.kind() == SymbolKind::Error {
-            self.remove_marker_effects(&tag.marker, constraint.strength());
+    #[test]
+    fn test_solve_integer() {
+        let mut solver = Solver::new();
+        let columns: Vec<Variable> = (0..3).map(|_| Variable::new()).collect();
+        solver
+            .add_constraint(Constraint::new(
+                &columns[0] + &columns[1] + &columns[2] - 100.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        for pair in columns.windows(2) {
+            solver
+                .add_constraint(Constraint::new(
+                    &pair[0] - &pair[1],
+                    RelationalOperator::Equal,
+                    WEAK,
+                ))
+                .unwrap();
         }
-        if let Some(symbol) = &tag.other {
-            if symbol.kind() == SymbolKind::Error {
-                self.remove_marker_effects(symbol, constraint.strength());
-            }
+        for column in columns.iter() {
+            assert!(!solver.set_integer(column, true).unwrap());
+            assert!(solver.is_integer(column));
         }
-    }
 
-    /* Remove the effects of an error marker on the objective function.
+        assert_eq!(solver.solve_integer().unwrap(), IntegerStatus::Optimal);
+        let mut total = 0.0;
+        for column in columns.iter() {
+            let value = *column.value();
+            assert!((value - value.round()).abs() < 1e-8);
+            assert!(value == 33.0 || value == 34.0);
+            total += value;
+        }
+        assert!((total - 100.0).abs() < 1e-8);
 
-    */
-    fn remove_marker_effects(&mut self, marker: &Symbol, strength: f64) {
-        match self.m_rows.get(marker) {
-            Some(row) => {
-                self.m_objective.insert_row(row, -strength);
+        // The branching constraints are removed from the tableau.
+        solver.update_variable();
+        assert!((*columns[0].value() - 100.0 / 3.0).abs() < 1e-8);
+
+        assert!(solver.set_integer(&columns[0], false).unwrap());
+        assert!(!solver.is_integer(&columns[0]));
+    }
+
+    #[test]
+    fn test_solve_integer_pivot_limit() {
+        let mut failures = 0;
+        for limit in 0..20 {
+            let mut solver = Solver::new();
+            let columns: Vec<Variable> = (0..3).map(|_| Variable::new()).collect();
+            solver
+                .add_constraint(Constraint::new(
+                    &columns[0] + &columns[1] + &columns[2] - 100.0,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                ))
+                .unwrap();
+            for pair in columns.windows(2) {
+                solver
+                    .add_constraint(Constraint::new(
+                        &pair[0] - &pair[1],
+                        RelationalOperator::Equal,
+                        WEAK,
+                    ))
+                    .unwrap();
             }
-            None => {
-                self.m_objective.insert_symbol(marker, -strength);
+            for column in columns.iter() {
+                solver.set_integer(column, true).unwrap();
             }
+            let constraints = solver.m_cns.len();
+            let mut config = *solver.config();
+            config.set_max_pivots(Some(limit));
+            solver.set_config(config);
+            match solver.solve_integer() {
+                Err(KiwiError::PivotLimitReached { .. }) => failures += 1,
+                result => assert!(result.is_ok()),
+            }
+
+            // No branching constraint is left behind.
+            assert_eq!(solver.m_cns.len(), constraints);
+            config.set_max_pivots(None);
+            solver.set_config(config);
+            solver.update_variable();
+            assert!((*columns[0].value() - 100.0 / 3.0).abs() < 1e-8);
         }
+        assert!(failures > 0);
     }
 
-    /// Substitute the parametric symbol with the given row.
-    ///
-    /// This method will substitute all instances of the parametric symbol
-    /// in the tableau and the objective function with the given row.
-    ///
-    fn substitute(&mut self, symbol: &Symbol, row: &Row) {
-        for (s, r) in self.m_rows.iter_mut() {
-            r.substitute(symbol, row);
-            if s.kind() != SymbolKind::External && *r.constant() < 0.0 {
-                self.m_infeasible_rows.push(s.clone());
-            }
+    #[test]
+    fn test_solve_integer_limits() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        let y = Variable::new_with_name("y");
+        solver
+            .add_constraint(Constraint::new(&x - 10.4, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        solver.set_integer(&x, true).unwrap();
+        assert_eq!(solver.solve_integer().unwrap(), IntegerStatus::Optimal);
+        assert_eq!(*x.value(), 10.0);
+
+        // The second node finds x = 10 but cannot prove it is optimal.
+        let mut config = *solver.config();
+        config.set_max_branch_nodes(2);
+        solver.set_config(config);
+        assert_eq!(
+            solver.solve_integer().unwrap(),
+            IntegerStatus::NodeLimitReached
+        );
+        assert_eq!(*x.value(), 10.0);
+
+        // The root node is fractional and no other node can be explored.
+        config.set_max_branch_nodes(1);
+        solver.set_config(config);
+        x.set_value(0.0);
+        match solver.solve_integer() {
+            Err(KiwiError::BranchNodeLimitReached { limit }) => assert_eq!(limit, 1),
+            _ => panic!("The node limit should be reached before finding a solution."),
         }
-        self.m_objective.substitute(symbol, row);
-        if let Some(art_row) = &mut self.m_artificial {
-            art_row.substitute(symbol, row);
+        assert_eq!(*x.value(), 0.0);
+
+        config.set_max_branch_nodes(1000);
+        solver.set_config(config);
+        solver
+            .add_constraint(Constraint::new(
+                &y * 2.0 - 3.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.set_integer(&y, true).unwrap();
+        match solver.solve_integer() {
+            Err(KiwiError::IntegerInfeasible) => (),
+            _ => panic!("No integer solution should exist."),
         }
     }
 
-    fn optimize(&mut self, target: OptimizationTarget) -> SolverResult {
-        loop {
-            let entering = self.get_entering_symbol(match target {
-                OptimizationTarget::Objective => &self.m_objective,
-                OptimizationTarget::Artificial => &self.m_artificial.as_ref().unwrap(),
-            });
-            if entering.kind() == SymbolKind::Invalid {
-                return Ok(());
-            }
-            if let Some((leaving_symbol, mut leaving_row)) = self.get_leaving_row(&entering) {
-                // pivot the entering symbol into the basis
-                leaving_row.solve_for_symbols(&leaving_symbol, &entering);
-                self.substitute(&entering, &leaving_row);
-                self.m_rows.insert(entering.clone(), leaving_row);
-            } else {
-                return Err(KiwiError::InternalSolverError {
-                    msg: String::from("The objective is unbounded."),
-                });
-            }
+    #[test]
+    fn test_round_solution() {
+        let mut solver = Solver::new();
+        let container = Variable::new_with_name("container");
+        let edges: Vec<Variable> = (0..4).map(|_| Variable::new()).collect();
+        let widths: Vec<Variable> = (0..3).map(|_| Variable::new()).collect();
+        solver
+            .add_constraint(Constraint::new(
+                &container - 100.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &edges[0] + 0.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &edges[3] - &container,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        for i in 0..3 {
+            solver
+                .add_constraint(Constraint::new(
+                    &edges[i] + &widths[i] - &edges[i + 1],
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                ))
+                .unwrap();
         }
+        for i in 0..2 {
+            solver
+                .add_constraint(Constraint::new(
+                    &widths[i] - &widths[i + 1],
+                    RelationalOperator::Equal,
+                    STRONG,
+                ))
+                .unwrap();
+        }
+        let free = Variable::new_with_name("free");
+        solver
+            .add_constraint(Constraint::new(
+                &free - 2.5,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+
+        let mut rounded: Vec<Variable> = edges.clone();
+        rounded.extend(widths.iter().cloned());
+        rounded.push(free.clone());
+        assert!(solver
+            .round_solution(&rounded, RoundingPolicy::Floor)
+            .is_empty());
+        for variable in rounded.iter() {
+            assert_eq!(variable.value().fract(), 0.0);
+        }
+        assert_eq!(*edges[0].value(), 0.0);
+        assert_eq!(*edges[3].value(), 100.0);
+        let mut total = 0.0;
+        for i in 0..3 {
+            assert_eq!(
+                *edges[i].value() + *widths[i].value(),
+                *edges[i + 1].value()
+            );
+            assert!(*widths[i].value() == 33.0 || *widths[i].value() == 34.0);
+            total += *widths[i].value();
+        }
+        assert_eq!(total, 100.0);
+        assert_eq!(*free.value(), 2.0);
+
+        // Only the given variables are updated.
+        assert_eq!(*container.value(), 0.0);
     }
 
-    /// Compute the entering variable for a pivot operation.
+    fn tooltip_solver(hysteresis: f64) -> (Solver, Variable, Variable) {
+        let mut config = SolverConfig::new();
+        config.set_disjunction_hysteresis(hysteresis);
+        let mut solver = Solver::with_config(config);
+        let anchor = Variable::new_with_name("anchor");
+        let tooltip = Variable::new_with_name("tooltip");
+        solver
+            .add_constraint(Constraint::new(
+                &tooltip + 0.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &tooltip - 480.0,
+                RelationalOperator::LessEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.add_edit_variable(&anchor, STRONG).unwrap();
+        solver.suggest_value(&anchor, 100.0).unwrap();
+        (solver, anchor, tooltip)
+    }
 
-    /// This method will return first symbol in the objective function which
-    /// is non-dummy and has a coefficient less than zero. If no symbol meets
-    /// the criteria, it means the objective function is at a minimum, and an
-    /// invalid symbol is returned.
-    ///
-    fn get_entering_symbol(&self, objective: &Row) -> Symbol {
-        for (s, c) in objective.cells().iter() {
-            if s.kind() != SymbolKind::Dummy && *c < 0.0 {
-                return s.clone();
-            }
+    #[test]
+    fn test_disjunction() {
+        let (mut solver, anchor, tooltip) = tooltip_solver(1.0);
+        let above = Constraint::new(
+            &tooltip + 20.0 - &anchor,
+            RelationalOperator::Equal,
+            REQUIRED,
+        );
+        let below = Constraint::new(
+            &tooltip - &anchor - 10.0,
+            RelationalOperator::Equal,
+            REQUIRED,
+        );
+        let handle = solver
+            .add_disjunction(vec![vec![above.clone()], vec![below.clone()]])
+            .unwrap();
+        assert!(solver.has_disjunction(handle));
+        assert_eq!(solver.active_alternative(handle), Some(0));
+        assert!(solver.has_constraint(&above));
+        assert!(!solver.has_constraint(&below));
+        solver.update_variable();
+        assert!((*tooltip.value() - 80.0).abs() < 1e-8);
+
+        // There is no room above the anchor anymore.
+        solver.suggest_value(&anchor, 10.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(1));
+        solver.update_variable();
+        assert!((*anchor.value() - 10.0).abs() < 1e-8);
+        assert!((*tooltip.value() - 20.0).abs() < 1e-8);
+
+        // Both alternatives are equivalent, the tooltip stays below.
+        solver.suggest_value(&anchor, 100.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(1));
+
+        solver.suggest_value(&anchor, 200.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(1));
+
+        solver.suggest_value(&anchor, 475.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(0));
+
+        solver.remove_disjunction(handle).unwrap();
+        assert!(!solver.has_disjunction(handle));
+        assert!(!solver.has_constraint(&above));
+        match solver.remove_disjunction(handle) {
+            Err(KiwiError::UnknownDisjunction { .. }) => (),
+            _ => panic!("The disjunction should be unknown."),
         }
-        Symbol::new(SymbolKind::Invalid, 0)
     }
 
-    /* Compute the entering symbol for the dual optimize operation.
+    #[test]
+    fn test_disjunction_error() {
+        let (mut solver, anchor, tooltip) = tooltip_solver(1.0);
+        solver.suggest_value(&anchor, 5.0).unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &tooltip + 0.0,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+        let above = Constraint::new(
+            &tooltip + 20.0 - &anchor,
+            RelationalOperator::Equal,
+            REQUIRED,
+        );
+        let below = Constraint::new(
+            &tooltip - &anchor - 10.0,
+            RelationalOperator::Equal,
+            REQUIRED,
+        );
+        let handle = solver
+            .add_disjunction(vec![vec![above.clone()], vec![below.clone()]])
+            .unwrap();
 
-    This method will return the symbol in the row which has a positive
-    coefficient and yields the minimum ratio for its respective symbol
-    in the objective function. The provided row *must* be infeasible.
-    If no symbol is found which meats the criteria, an invalid symbol
-    is returned.
+        // The evaluation of the alternatives reaches the pivot limit, and so does the
+        // restoration of the active one: the disjunction is removed.
+        let mut config = *solver.config();
+        config.set_max_pivots(Some(1));
+        solver.set_config(config);
+        match solver.suggest_value(&anchor, 500.0) {
+            Err(KiwiError::PivotLimitReached { .. }) => (),
+            _ => panic!("The pivot limit should have been reached."),
+        }
+        assert!(!solver.has_disjunction(handle));
+        assert!(!solver.has_constraint(&above));
+        assert!(!solver.has_constraint(&below));
 
-    */
-    fn get_dual_entering_symbol(&self, row: &Row) -> Option<Symbol> {
-        let mut entering = None;
-        let mut ratio = f64::MAX;
-        for (symbol, coeff) in row.cells().iter() {
-            if *coeff > 0.0 && symbol.kind() != SymbolKind::Dummy {
-                let coefficient = self.m_objective.coefficient_for(symbol);
-                let r = coefficient / coeff;
-                if r < ratio {
-                    ratio = r;
-                    entering = Some(symbol.clone());
-                }
-            }
+        config.set_max_pivots(None);
+        solver.set_config(config);
+        solver.suggest_value(&anchor, 500.0).unwrap();
+        solver.update_variable();
+        assert!((*anchor.value() - 500.0).abs() < 1e-8);
+        assert!(tooltip.value().abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_disjunction_hysteresis() {
+        let (mut solver, anchor, tooltip) = tooltip_solver(1.0e9);
+        let handle = solver
+            .add_disjunction(vec![
+                vec![Constraint::new(
+                    &tooltip + 20.0 - &anchor,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                )],
+                vec![Constraint::new(
+                    &tooltip - &anchor - 10.0,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                )],
+            ])
+            .unwrap();
+        solver.suggest_value(&anchor, 10.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(0));
+        solver.update_variable();
+        assert!((*anchor.value() - 20.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_disjunction_inactive_cheaper() {
+        let mut solver = Solver::new();
+        let a = Variable::new_with_name("a");
+        let b = Variable::new_with_name("b");
+        let e = Variable::new_with_name("e");
+        for v in [&a, &b].iter() {
+            solver
+                .add_constraint(Constraint::new(*v + 0.0, RelationalOperator::Equal, MEDIUM))
+                .unwrap();
         }
-        entering
+        solver.add_edit_variable(&e, STRONG).unwrap();
+        solver.suggest_value(&e, 10.0).unwrap();
+        let handle = solver
+            .add_disjunction(vec![
+                vec![Constraint::new(
+                    &a - 5.0,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                )],
+                vec![Constraint::new(
+                    &b - &e,
+                    RelationalOperator::Equal,
+                    REQUIRED,
+                )],
+            ])
+            .unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(0));
+
+        // The cost of the active alternative does not change but the other one
+        // becomes free.
+        solver.suggest_value(&e, 0.0).unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(1));
+
+        // Adding a constraint also re-evaluates the alternatives.
+        solver
+            .add_constraint(Constraint::new(
+                &b - 30.0,
+                RelationalOperator::Equal,
+                STRONG,
+            ))
+            .unwrap();
+        assert_eq!(solver.active_alternative(handle), Some(0));
+        solver.update_variable();
+        assert!((*a.value() - 5.0).abs() < 1e-8);
     }
 
-    /// Compute the row which holds the exit symbol for a pivot.
-    ///
-    /// This method will the exit symbol and the row containing it. If no
-    /// appropriate exit symbol is found, None will be returned. This indicates
-    /// that the objective function is unbounded.
-    ///
-    /// The leaving row is removed from the row map.
-    ///
-    fn get_leaving_row(&mut self, entering: &Symbol) -> Option<(Symbol, Row)> {
-        let mut ratio = f64::MAX;
-        let mut found: Option<Symbol> = None;
-        for (s, r) in self.m_rows.iter() {
-            if s.kind() != SymbolKind::External {
-                let temp = r.coefficient_for(entering);
-                if temp < 0.0 {
-                    let temp_ratio = -(*r.constant()) / temp;
-                    if temp_ratio < ratio {
-                        ratio = temp_ratio;
-                        found = Some(s.clone());
-                    }
-                }
-            }
+    #[test]
+    fn test_unsatisfiable_disjunction() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(
+                &x - 5.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        let one = Constraint::new(&x - 1.0, RelationalOperator::Equal, REQUIRED);
+        let two = Constraint::new(&x - 2.0, RelationalOperator::Equal, REQUIRED);
+        match solver.add_disjunction(vec![vec![one.clone()], vec![two.clone()]]) {
+            Err(KiwiError::UnsatisfiableDisjunction) => (),
+            _ => panic!("The disjunction should be unsatisfiable."),
         }
-        if let Some(symbol) = found {
-            // Unwrapping is safe since we know the symbol exist in the map.
-            let row = self.m_rows.remove(&symbol).unwrap();
-            Some((symbol, row))
-        } else {
-            None
+        assert!(!solver.has_constraint(&one));
+        assert!(!solver.has_constraint(&two));
+        solver.update_variable();
+        assert!((*x.value() - 5.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_groups() {
+        let mut solver = Solver::new();
+        let sidebar = Variable::new_with_name("sidebar");
+        let content = Variable::new_with_name("content");
+        solver
+            .add_constraint(Constraint::new(
+                &sidebar + &content - 800.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                &sidebar - 100.0,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+        let wide = Constraint::new(&sidebar - 200.0, RelationalOperator::Equal, STRONG);
+        let minimum = Constraint::new(&content - 500.0, RelationalOperator::GreaterEqual, REQUIRED);
+        let regular = solver
+            .add_group(vec![wide.clone(), minimum.clone()])
+            .unwrap();
+        assert!(solver.has_group(regular));
+        assert!(solver.is_group_enabled(regular));
+        assert!(solver.group_contains(regular, &wide));
+        assert_eq!(solver.group_constraints(regular).unwrap().len(), 2);
+        solver.update_variable();
+        assert!((*sidebar.value() - 200.0).abs() < 1e-8);
+
+        solver.disable_group(regular).unwrap();
+        assert!(!solver.is_group_enabled(regular));
+        assert!(!solver.has_constraint(&wide));
+        assert!(!solver.has_constraint(&minimum));
+        solver.update_variable();
+        assert!((*sidebar.value() - 100.0).abs() < 1e-8);
+        solver.disable_group(regular).unwrap();
+
+        solver.enable_group(regular).unwrap();
+        assert!(solver.has_constraint(&wide));
+        solver.update_variable();
+        assert!((*sidebar.value() - 200.0).abs() < 1e-8);
+
+        // A required constraint added meanwhile prevents enabling the group.
+        solver.disable_group(regular).unwrap();
+        let large = Constraint::new(&sidebar - 400.0, RelationalOperator::Equal, REQUIRED);
+        solver.add_constraint(large.clone()).unwrap();
+        match solver.enable_group(regular) {
+            Err(KiwiError::UnsatisfiableConstraint { .. }) => (),
+            _ => panic!("The group should conflict with the constraint."),
+        }
+        assert!(!solver.is_group_enabled(regular));
+        assert!(!solver.has_constraint(&wide));
+
+        solver.remove_group(regular).unwrap();
+        assert!(!solver.has_group(regular));
+        assert!(!solver.group_contains(regular, &wide));
+        match solver.enable_group(regular) {
+            Err(KiwiError::UnknownGroup { .. }) => (),
+            _ => panic!("The group should be unknown."),
         }
     }
 
-    ///
-    /* Compute the leaving row for a marker variable.
+    #[test]
+    fn test_rule() {
+        let mut solver = Solver::new();
+        let width = Variable::new_with_name("width");
+        let second = Variable::new_with_name("second");
+        solver.add_edit_variable(&width, STRONG).unwrap();
+        solver.suggest_value(&width, 800.0).unwrap();
+        let stacked = Constraint::new(&second + 0.0, RelationalOperator::Equal, REQUIRED);
+        let side_by_side =
+            Constraint::new(&second - &width * 0.5, RelationalOperator::Equal, REQUIRED);
+        let rule = solver
+            .add_rule(
+                &width + 0.0,
+                RelationalOperator::LessEqual,
+                600.0,
+                vec![stacked.clone()],
+                vec![side_by_side.clone()],
+            )
+            .unwrap();
+        assert!(solver.has_rule(rule));
+        assert_eq!(solver.active_branch(rule), Some(RuleBranch::Else));
+        solver.update_variable();
+        assert!((*second.value() - 400.0).abs() < 1e-8);
 
-    This method will return an iterator to the row in the row map
-    which holds the given marker variable. The row will be chosen
-    according to the following precedence:
+        solver.suggest_value(&width, 500.0).unwrap();
+        assert_eq!(solver.active_branch(rule), Some(RuleBranch::Then));
+        assert!(solver.has_constraint(&stacked));
+        assert!(!solver.has_constraint(&side_by_side));
+        solver.update_variable();
+        assert!(second.value().abs() < 1e-8);
 
-    1) The row with a restricted basic varible and a negative coefficient
-       for the marker with the smallest ratio of -constant / coefficient.
+        solver.remove_rule(rule).unwrap();
+        assert!(!solver.has_rule(rule));
+        assert!(!solver.has_constraint(&stacked));
+        match solver.remove_rule(rule) {
+            Err(KiwiError::UnknownRule { .. }) => (),
+            _ => panic!("The rule should be unknown."),
+        }
+    }
 
-    2) The row with a restricted basic variable and the smallest ratio
-       of constant / coefficient.
+    #[test]
+    fn test_rule_oscillation() {
+        let mut solver = Solver::new();
+        let x = Variable::new_with_name("x");
+        solver
+            .add_constraint(Constraint::new(&x - 40.0, RelationalOperator::Equal, WEAK))
+            .unwrap();
+        // Each branch moves x to the other side of the threshold.
+        let rule = solver.add_rule(
+            &x + 0.0,
+            RelationalOperator::LessEqual,
+            50.0,
+            vec![Constraint::new(
+                &x - 100.0,
+                RelationalOperator::Equal,
+                STRONG,
+            )],
+            vec![Constraint::new(&x + 0.0, RelationalOperator::Equal, STRONG)],
+        );
+        match rule {
+            Err(KiwiError::RuleOscillation) => (),
+            _ => panic!("The rule should oscillate."),
+        }
 
-    3) The last unrestricted row which contains the marker.
+        // The constraints of the rule are removed with it.
+        solver.update_variable();
+        assert!((*x.value() - 40.0).abs() < 1e-8);
+        solver
+            .add_constraint(Constraint::new(&x - 7.0, RelationalOperator::Equal, MEDIUM))
+            .unwrap();
+        solver.update_variable();
+        assert!((*x.value() - 7.0).abs() < 1e-8);
+    }
 
-    If the marker does not exist in any row, the row map end() iterator
-    will be returned. This indicates an internal solver error since
-    the marker *should* exist somewhere in the tableau.
+    #[test]
+    fn test_sparse_blocks() {
+        // Independent blocks of variables give a large and sparse tableau, for which the
+        // rows containing a symbol are found through the column index.
+        let (blocks, size) = (300, 5);
+        let mut solver = Solver::new();
+        let mut variables = Vec::new();
+        let mut constraints = Vec::new();
+        for b in 0..blocks {
+            let block: Vec<Variable> = (0..size).map(|_| Variable::new()).collect();
+            let origin = 10.0 * b as f64;
+            let mut block_constraints = vec![Constraint::new(
+                &block[0] - origin,
+                RelationalOperator::Equal,
+                REQUIRED,
+            )];
+            for i in 1..size {
+                let gap = &block[i] - &block[i - 1];
+                block_constraints.push(Constraint::new(
+                    gap.clone() - 10.0,
+                    RelationalOperator::GreaterEqual,
+                    REQUIRED,
+                ));
+                block_constraints.push(Constraint::new(
+                    gap - 20.0,
+                    RelationalOperator::Equal,
+                    WEAK,
+                ));
+            }
+            for c in block_constraints.iter() {
+                solver.add_constraint(c.clone()).unwrap();
+            }
+            variables.push(block);
+            constraints.push(block_constraints);
+        }
+        let edited: Vec<usize> = (0..blocks).step_by(15).collect();
+        for &b in edited.iter() {
+            solver
+                .add_edit_variable(&variables[b][size - 1], STRONG)
+                .unwrap();
+        }
+        for round in 0..10 {
+            for &b in edited.iter() {
+                let target = 10.0 * b as f64 + 100.0 + 10.0 * round as f64;
+                solver
+                    .suggest_value(&variables[b][size - 1], target)
+                    .unwrap();
+            }
+        }
+        assert!(solver.m_rows.is_indexed());
+        solver.update_variable();
 
-    */
-    fn get_marker_leaving_row(&mut self, marker: &Symbol) -> Option<(Symbol, Row)> {
-        let mut r1 = f64::MAX;
-        let mut r2 = f64::MAX;
-        let mut first: Option<Symbol> = None;
-        let mut second: Option<Symbol> = None;
-        let mut third: Option<Symbol> = None;
-        for (symbol, row) in self.m_rows.iter() {
-            let c = row.coefficient_for(marker);
-            if c == 0.0 {
-                continue;
+        let check = |b: usize, block: &[Variable]| {
+            let origin = 10.0 * b as f64;
+            assert!((*block[0].value() - origin).abs() < 1e-8);
+            for i in 1..size {
+                assert!(*block[i].value() - *block[i - 1].value() >= 10.0 - 1e-8);
             }
-            if symbol.kind() == SymbolKind::External {
-                third = Some(symbol.clone());
-            } else if c < 0.0 {
-                let r = -(*row.constant()) / c;
-                if r < r1 {
-                    r1 = r;
-                    first = Some(symbol.clone());
-                }
+            if edited.contains(&b) {
+                assert!((*block[size - 1].value() - origin - 190.0).abs() < 1e-8);
             } else {
-                let r = (*row.constant()) / c;
-                if r < r2 {
-                    r2 = r;
-                    second = Some(symbol.clone());
+                for (i, v) in block.iter().enumerate() {
+                    assert!((*v.value() - origin - 20.0 * i as f64).abs() < 1e-8);
                 }
             }
+        };
+        for (b, block) in variables.iter().enumerate() {
+            check(b, block);
         }
 
-        // If we have a symbol it exists in the mapping so unwraping is safe
-        // The following cannot currently use || to reduce the redundancy
-        if let Some(leaving) = first {
-            return Some((leaving.clone(), self.m_rows.remove(&leaving).unwrap()));
-        }
-        if let Some(leaving) = second {
-            return Some((leaving.clone(), self.m_rows.remove(&leaving).unwrap()));
+        // Removing half of the blocks leaves the other ones untouched.
+        for b in (0..blocks).filter(|b| b % 2 == 0) {
+            if edited.contains(&b) {
+                solver
+                    .remove_edit_variable(&variables[b][size - 1])
+                    .unwrap();
+            }
+            for c in constraints[b].iter() {
+                solver.remove_constraint(c).unwrap();
+            }
         }
-        if let Some(leaving) = third {
-            return Some((leaving.clone(), self.m_rows.remove(&leaving).unwrap()));
+        solver.update_variable();
+        for (b, block) in variables.iter().enumerate().filter(|(b, _)| b % 2 == 1) {
+            check(b, block);
         }
-        None
     }
 
-    /// Get the first Slack or Error symbol in the row.
-    ///
-    /// If no such symbol is present, and Invalid symbol will be returned.
-    ///
-    fn any_pivotable_symbol(&self, row: &Row) -> Symbol {
-        for (s, _coeff) in row.cells().iter() {
-            if s.kind() == SymbolKind::Slack || s.kind() == SymbolKind::Error {
-                return s.clone();
+    #[test]
+    fn test_var_id() {
+        let mut solver = Solver::new();
+        let left = solver.new_variable("left");
+        let width = solver.new_variable("width");
+        let right = solver.new_variable("right");
+        assert_eq!(solver.name(width), Some("width"));
+        assert_eq!(solver.set_name(width, "w").unwrap(), "width");
+        assert_eq!(solver.name(width), Some("w"));
+
+        solver
+            .add_constraint(Constraint::new(
+                right - left - width,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                width - 100.0,
+                RelationalOperator::GreaterEqual,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver
+            .add_constraint(Constraint::new(
+                left - 10.0,
+                RelationalOperator::Equal,
+                WEAK,
+            ))
+            .unwrap();
+        solver.add_edit_variable(&right, STRONG).unwrap();
+        solver.suggest_value(&right, 300.0).unwrap();
+        solver.update_variable();
+        assert!((solver.value(left).unwrap() - 10.0).abs() < 1e-8);
+        assert!((solver.value(width).unwrap() - 290.0).abs() < 1e-8);
+        assert!((solver.value(right).unwrap() - 300.0).abs() < 1e-8);
+
+        match solver.add_edit_variable(&right, STRONG) {
+            Err(e @ KiwiError::DuplicateEditVariable { .. }) => {
+                assert_eq!(
+                    format!("{}", e),
+                    "The edit variable v2 has already been added to the solver.."
+                );
             }
+            _ => panic!("right is already an edit variable."),
         }
-        Symbol::new(SymbolKind::Invalid, 0)
+
+        // The stays use the values stored by the solver.
+        let top = solver.new_variable("top");
+        assert_eq!(solver.set_value(top, 50.0).unwrap(), 0.0);
+        solver.add_stay(&top, WEAK).unwrap();
+        solver.update_variable();
+        assert!((solver.value(top).unwrap() - 50.0).abs() < 1e-8);
+
+        // The variables allocated before a reset remain valid.
+        solver.reset();
+        solver
+            .add_constraint(Constraint::new(
+                2.0 * top - 20.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert!((solver.value(top).unwrap() - 10.0).abs() < 1e-8);
+        assert_eq!(solver.name(top), Some("top"));
     }
 
-    ///
-    #[inline]
-    fn next_symbol_id(&mut self) -> u64 {
-        self.m_id_tick += 1;
-        self.m_id_tick
+    #[test]
+    fn test_unknown_var_id() {
+        let mut other = Solver::new();
+        other.new_variable("a");
+        let foreign = other.new_variable("b");
+
+        let mut solver = Solver::new();
+        let x = solver.new_variable("x");
+        let is_unknown = |result: Result<(), KiwiError<VarId>>| match result {
+            Err(KiwiError::UnknownVariable { variable }) => variable == foreign,
+            _ => false,
+        };
+
+        let cn = Constraint::new(x + foreign, RelationalOperator::Equal, REQUIRED);
+        assert!(is_unknown(solver.add_constraint(cn.clone())));
+        assert!(!solver.has_constraint(&cn));
+        assert!(is_unknown(solver.add_edit_variable(&foreign, STRONG)));
+        assert!(!solver.has_edit_variable(&foreign));
+        assert!(is_unknown(solver.add_stay(&foreign, WEAK)));
+        assert!(is_unknown(solver.set_bounds(&foreign, 0.0, 1.0, REQUIRED)));
+        assert!(is_unknown(
+            solver
+                .add_objective(foreign + 0.0, Sense::Minimize, WEAK)
+                .map(|_| ())
+        ));
+        assert!(is_unknown(solver.set_integer(&foreign, true).map(|_| ())));
+        assert!(is_unknown(solver.set_value(foreign, 1.0).map(|_| ())));
+        assert!(is_unknown(solver.set_name(foreign, "b").map(|_| ())));
+        assert_eq!(solver.value(foreign), None);
+        assert_eq!(solver.name(foreign), None);
+        assert_eq!(
+            format!("{}", KiwiError::UnknownVariable { variable: foreign }),
+            "The variable v1 was not allocated by the solver."
+        );
+
+        // The rejected variable did not reach the tableau.
+        solver
+            .add_constraint(Constraint::new(
+                x - 3.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        solver.update_variable();
+        assert_eq!(solver.value(x), Some(3.0));
+    }
+
+    #[test]
+    fn test_var_id_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Solver<VarId>>();
+        assert_send::<KiwiError<VarId>>();
+
+        let mut solver = Solver::new();
+        let x = solver.new_variable("x");
+        solver
+            .add_constraint(Constraint::new(
+                x - 5.0,
+                RelationalOperator::Equal,
+                REQUIRED,
+            ))
+            .unwrap();
+        let solver = std::thread::spawn(move || {
+            solver.update_variable();
+            solver
+        })
+        .join()
+        .unwrap();
+        assert_eq!(solver.value(x), Some(5.0));
     }
 }
@@ -6,6 +6,10 @@
 //! user defined strength need to be notably different from existing strength to have any noticeable
 //! effect.
 //!
+//! When a strict priority between strengths is needed, the solver can be configured to use
+//! StrengthMode::Lexicographic. In that mode, strengths are split back into their strong, medium
+//! and weak components (see `split`) and each level is optimized before the next one.
+//!
 
 // XXX The following function is a candidate to become a const func when the feature will be
 // stabilized
@@ -19,6 +23,23 @@ pub fn create(a: f64, b: f64, c: f64, weight: f64) -> f64 {
     result
 }
 
+/// Number of levels used when strengths are compared lexicographically.
+pub const LEVELS: usize = 3;
+
+/// Split a strength into its strong, medium and weak components.
+///
+/// This is the inverse of create for a unit weight. Since a strength is a single floating
+/// point number, fractional components spill into the lower levels: for example
+/// create(0.5, 0.0, 0.0, 1.0) is split into [0.0, 500.0, 0.0].
+#[inline]
+pub fn split(strength: f64) -> [f64; LEVELS] {
+    let strength = clip(strength);
+    let a = f64::min(1000.0, (strength / 1e6).floor());
+    let remainder = strength - a * 1e6;
+    let b = f64::min(1000.0, (remainder / 1e3).floor());
+    [a, b, remainder - b * 1e3]
+}
+
 /// Strength used for absolutely required constraints.
 ///
 /// All strength values are clipped below its value.
@@ -49,7 +70,7 @@ pub fn clip(strength: f64) -> f64 {
 #[cfg(test)]
 mod tests {
 
-    use super::{clip, create, MEDIUM, REQUIRED, STRONG, WEAK};
+    use super::{clip, create, split, MEDIUM, REQUIRED, STRONG, WEAK};
 
     #[test]
     fn test_create() {
@@ -63,6 +84,15 @@ mod tests {
         assert!(s > WEAK);
     }
 
+    #[test]
+    fn test_split() {
+        assert_eq!(split(STRONG), [1.0, 0.0, 0.0]);
+        assert_eq!(split(MEDIUM), [0.0, 1.0, 0.0]);
+        assert_eq!(split(WEAK), [0.0, 0.0, 1.0]);
+        assert_eq!(split(create(2.0, 3.0, 4.0, 1.0)), [2.0, 3.0, 4.0]);
+        assert_eq!(split(create(0.5, 0.0, 0.0, 1.0)), [0.0, 500.0, 0.0]);
+    }
+
     #[test]
     fn test_clip() {
         assert_eq!(clip(-10.0), 0.0);
@@ -1,89 +1,147 @@
 //! Symbols are used to represent in a compact and efficient manner the state of teh solver.
 
 use std::cmp;
-
-// We use an enum wrapped in a struct since we need to compare Symbol of different kind
+use std::fmt;
+use std::hash;
 
 /// Kind of symbol that can exist in the solver.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
 pub enum SymbolKind {
     /// Invalid symbol are used as place holder when a symbol cannot be found.
-    Invalid,
+    Invalid = 0,
     /// External symbol ("v"): correspond to a user created variable.
-    External,
+    External = 1,
     /// Slack symbol ("s"): used to represent inequalities
-    Slack,
+    Slack = 2,
     /// Error symbol ("e"): used to represent non-required constraints
-    Error,
+    Error = 3,
     /// Dummy symbol ("d"): always zero, used to keep track of the impact of an
     /// external variable in the tableau.
-    Dummy,
+    Dummy = 4,
 }
 
+/// Number of low bits of a symbol holding its id, the kind being stored in the high bits.
+const ID_BITS: u32 = 61;
+
+/// Mask selecting the id of a symbol.
+const ID_MASK: u64 = (1 << ID_BITS) - 1;
+
 /// Symbol used to represent the state of the solver
 ///
 /// # Note
 ///
 /// Since solving the system requires a large number of manipulation of the symbols
-/// the operations have to compile down to an efficient representation. In Kiwi, symbols
-/// compile down to u64 meaning that a vector of them fits in a CPU cache line.
+/// the operations have to compile down to an efficient representation. As in Kiwi, symbols
+/// compile down to u64 meaning that a vector of them fits in a CPU cache line: the kind is
+/// packed in the 3 high bits and the id in the remaining ones.
+///
+/// Symbols are compared, ordered and hashed by id only (the ids are unique whatever the
+/// kind), so that the order of the rows and cells does not depend on the kinds.
 ///
-#[derive(Debug, Clone)]
+#[derive(Clone, Copy)]
 pub struct Symbol {
-    m_id: u64,
-    m_type: SymbolKind,
+    m_bits: u64,
 }
 
 impl Symbol {
     /// Create a new symbol of a given kind and id.
+    #[inline]
     pub fn new(t: SymbolKind, id: u64) -> Symbol {
+        debug_assert!(id <= ID_MASK, "symbol id out of range");
         Symbol {
-            m_id: id,
-            m_type: t,
+            m_bits: ((t as u64) << ID_BITS) | (id & ID_MASK),
         }
     }
 
     /// Access the symbol id.
-    pub fn id(&self) -> &u64 {
-        &self.m_id
+    #[inline]
+    pub fn id(self) -> u64 {
+        self.m_bits & ID_MASK
     }
 
     /// Access the symbol kind.
-    pub fn kind(&self) -> SymbolKind {
-        self.m_type
+    #[inline]
+    pub fn kind(self) -> SymbolKind {
+        // The high bits always hold a valid kind, the table lookup avoids a branch.
+        const KINDS: [SymbolKind; 8] = [
+            SymbolKind::Invalid,
+            SymbolKind::External,
+            SymbolKind::Slack,
+            SymbolKind::Error,
+            SymbolKind::Dummy,
+            SymbolKind::Invalid,
+            SymbolKind::Invalid,
+            SymbolKind::Invalid,
+        ];
+        KINDS[(self.m_bits >> ID_BITS) as usize]
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Symbol")
+            .field("id", &self.id())
+            .field("kind", &self.kind())
+            .finish()
     }
 }
 
 impl cmp::PartialEq for Symbol {
+    #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.m_id == other.m_id
+        self.id() == other.id()
     }
 }
 
 impl cmp::Eq for Symbol {}
 
 impl cmp::Ord for Symbol {
+    #[inline]
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.m_id.cmp(&other.m_id)
+        self.id().cmp(&other.id())
     }
 }
 
 impl cmp::PartialOrd for Symbol {
+    #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
+impl hash::Hash for Symbol {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.id())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::{Symbol, SymbolKind};
+    use std::mem::size_of;
 
     #[test]
     fn test_symbol_creation() {
         let symbol = Symbol::new(SymbolKind::External, 1);
         assert_eq!(symbol.kind(), SymbolKind::External);
-        assert_eq!(symbol.id(), &1u64);
+        assert_eq!(symbol.id(), 1u64);
+
+        let kinds = [
+            SymbolKind::Invalid,
+            SymbolKind::External,
+            SymbolKind::Slack,
+            SymbolKind::Error,
+            SymbolKind::Dummy,
+        ];
+        for kind in kinds.iter() {
+            let symbol = Symbol::new(*kind, (1 << 61) - 1);
+            assert_eq!(symbol.kind(), *kind);
+            assert_eq!(symbol.id(), (1 << 61) - 1);
+        }
+        assert_eq!(size_of::<Symbol>(), size_of::<u64>());
     }
 
     #[test]
@@ -92,5 +150,11 @@ mod tests {
         let s2 = Symbol::new(SymbolKind::External, 2);
         assert_ne!(s1, s2);
         assert!(s1 < s2);
+
+        // The kind does not take part in the order.
+        let s3 = Symbol::new(SymbolKind::Slack, 1);
+        let s4 = Symbol::new(SymbolKind::Dummy, 3);
+        assert!(s3 < s2);
+        assert!(s2 < s4);
     }
 }
@@ -8,104 +8,118 @@ use super::constraint::{Constraint, RelationalOperator};
 use super::expression::Expression;
 use super::strength::{MEDIUM, REQUIRED, STRONG, WEAK};
 use super::term::Term;
-use super::variable::Variable;
+use super::variable::{VarId, Variable};
 use std::iter;
 use std::ops;
 
 // XXX The following could be optimized to reduce cloning when we get owned value
 // as input.
 
-// Variable *, /, - operation
-
-impl_op_ex_commutative!(*|lhs: &Variable, rhs: &f64| -> Term { Term::new(lhs.clone(), *rhs) });
-impl_op_ex_commutative!(/ |lhs: &Variable, rhs: &f64| -> Term {lhs * 1.0/(*rhs) });
-impl_op_ex!(-|lhs: &Variable| -> Term { lhs * -1.0 });
-
-// Term *, /, - operation
-impl_op_ex_commutative!(*|lhs: &Term, rhs: &f64| -> Term {
-    Term::new(lhs.variable().clone(), lhs.coefficient() * rhs)
-});
-impl_op_ex_commutative!(/ |lhs: &Term, rhs: &f64| -> Term { lhs * (1.0/rhs) });
-impl_op_ex!(-|lhs: &Term| -> Term { lhs * -1.0 });
-
-// Expression *, /, - operation
-impl_op_ex_commutative!(*|lhs: &Expression, rhs: &f64| -> Expression {
-    Expression::new(
-        lhs.terms()
-            .iter()
-            .map(|item| Term::new(item.variable().clone(), item.coefficient() * rhs))
-            .collect(),
-        lhs.constant() * rhs,
-    )
-});
-impl_op_ex_commutative!(/ |lhs: &Expression, rhs: &f64| -> Expression { lhs * (1.0/rhs) });
-impl_op_ex!(-|lhs: &Expression| -> Expression { lhs * -1.0 });
-
-// Expression add and subtract
-impl_op_ex!(+ |lhs: &Expression, rhs: &Expression| -> Expression {
-    Expression::new(
-        lhs.terms().iter().cloned().chain(
-         rhs.terms().iter().cloned()).collect(),
-         lhs.constant() + rhs.constant())});
-impl_op_ex!(-|lhs: &Expression, rhs: &Expression| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Expression, rhs: &Term| -> Expression {
-    Expression::new(
-        lhs.terms().iter().cloned().chain(
-         iter::once(rhs.clone())).collect(),
-         lhs.constant())});
-impl_op_ex!(-|lhs: &Expression, rhs: &Term| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &Term, rhs: &Expression| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Expression, rhs: &Variable| -> Expression {
-    Expression::new(
-        lhs.terms().iter().cloned().chain(
-         iter::once(Term::new( rhs.clone(), 1.0))).collect(),
-         lhs.constant())});
-impl_op_ex!(-|lhs: &Expression, rhs: &Variable| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &Variable, rhs: &Expression| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Expression, rhs: &f64| -> Expression {
-    Expression::new(
-        lhs.terms().clone(),
-         lhs.constant() + rhs)});
-impl_op_ex!(-|lhs: &Expression, rhs: &f64| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &f64, rhs: &Expression| -> Expression { lhs + (-rhs) });
-
-// Term add and subtract
-impl_op_ex!(+ |lhs: &Term, rhs: &Term| -> Expression {
-    Expression::new(
-        vec!(lhs.clone(), rhs.clone()),
-        0.0)});
-impl_op_ex!(-|lhs: &Term, rhs: &Term| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Term, rhs: &Variable| -> Expression {
-    Expression::new(
-        vec!(lhs.clone(), Term::new(rhs.clone(), 1.0)),
-        0.0)});
-impl_op_ex!(-|lhs: &Term, rhs: &Variable| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &Variable, rhs: &Term| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Term, rhs: &f64| -> Expression {
-    Expression::new(
-        vec!(lhs.clone()),
-        *rhs)});
-impl_op_ex!(-|lhs: &Term, rhs: &f64| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &f64, rhs: &Term| -> Expression { lhs + (-rhs) });
-
-// Variable add and subtract
-impl_op_ex!(+ |lhs: &Variable, rhs: &Variable| -> Expression {
-    Expression::new(
-        vec!(Term::new(lhs.clone(), 1.0), Term::new(rhs.clone(), 1.0)),
-        0.0)});
-impl_op_ex!(-|lhs: &Variable, rhs: &Variable| -> Expression { lhs + (-rhs) });
-
-impl_op_ex_commutative!(+ |lhs: &Variable, rhs: &f64| -> Expression {
-    Expression::new(
-        vec!(Term::new(lhs.clone(), 1.0)),
-        *rhs)});
-impl_op_ex!(-|lhs: &Variable, rhs: &f64| -> Expression { lhs + (-rhs) });
-impl_op_ex!(-|lhs: &f64, rhs: &Variable| -> Expression { lhs + (-rhs) });
+// The operations are implemented for each kind of variable handle since impl_ops does not
+// support generic parameters.
+macro_rules! impl_variable_ops {
+    ($var:ident) => {
+        // Variable *, /, - operation
+
+        impl_op_ex_commutative!(*|lhs: &$var, rhs: &f64| -> Term<$var> { Term::new(lhs.clone(), *rhs) });
+        impl_op_ex_commutative!(/ |lhs: &$var, rhs: &f64| -> Term<$var> {lhs * 1.0/(*rhs) });
+        impl_op_ex!(-|lhs: &$var| -> Term<$var> { lhs * -1.0 });
+
+        // Term *, /, - operation
+        impl_op_ex_commutative!(*|lhs: &Term<$var>, rhs: &f64| -> Term<$var> {
+            Term::new(lhs.variable().clone(), lhs.coefficient() * rhs)
+        });
+        impl_op_ex_commutative!(/ |lhs: &Term<$var>, rhs: &f64| -> Term<$var> { lhs * (1.0/rhs) });
+        impl_op_ex!(-|lhs: &Term<$var>| -> Term<$var> { lhs * -1.0 });
+
+        // Expression *, /, - operation
+        impl_op_ex_commutative!(*|lhs: &Expression<$var>, rhs: &f64| -> Expression<$var> {
+            Expression::new(
+                lhs.terms()
+                    .iter()
+                    .map(|item| Term::new(item.variable().clone(), item.coefficient() * rhs))
+                    .collect(),
+                lhs.constant() * rhs,
+            )
+        });
+        impl_op_ex_commutative!(/ |lhs: &Expression<$var>, rhs: &f64| -> Expression<$var> { lhs * (1.0/rhs) });
+        impl_op_ex!(-|lhs: &Expression<$var>| -> Expression<$var> { lhs * -1.0 });
+
+        // Expression add and subtract
+        impl_op_ex!(+ |lhs: &Expression<$var>, rhs: &Expression<$var>| -> Expression<$var> {
+            Expression::new(
+                lhs.terms().iter().cloned().chain(
+                 rhs.terms().iter().cloned()).collect(),
+                 lhs.constant() + rhs.constant())});
+        impl_op_ex!(-|lhs: &Expression<$var>, rhs: &Expression<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &Expression<$var>, rhs: &Term<$var>| -> Expression<$var> {
+            Expression::new(
+                lhs.terms().iter().cloned().chain(
+                 iter::once(rhs.clone())).collect(),
+                 lhs.constant())});
+        impl_op_ex!(-|lhs: &Expression<$var>, rhs: &Term<$var>| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &Term<$var>, rhs: &Expression<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &Expression<$var>, rhs: &$var| -> Expression<$var> {
+            Expression::new(
+                lhs.terms().iter().cloned().chain(
+                 iter::once(Term::new( rhs.clone(), 1.0))).collect(),
+                 lhs.constant())});
+        impl_op_ex!(-|lhs: &Expression<$var>, rhs: &$var| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &$var, rhs: &Expression<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &Expression<$var>, rhs: &f64| -> Expression<$var> {
+            Expression::new(
+                lhs.terms().clone(),
+                 lhs.constant() + rhs)});
+        impl_op_ex!(-|lhs: &Expression<$var>, rhs: &f64| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &f64, rhs: &Expression<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        // Term add and subtract
+        impl_op_ex!(+ |lhs: &Term<$var>, rhs: &Term<$var>| -> Expression<$var> {
+            Expression::new(
+                vec!(lhs.clone(), rhs.clone()),
+                0.0)});
+        impl_op_ex!(-|lhs: &Term<$var>, rhs: &Term<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &Term<$var>, rhs: &$var| -> Expression<$var> {
+            Expression::new(
+                vec!(lhs.clone(), Term::new(rhs.clone(), 1.0)),
+                0.0)});
+        impl_op_ex!(-|lhs: &Term<$var>, rhs: &$var| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &$var, rhs: &Term<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &Term<$var>, rhs: &f64| -> Expression<$var> {
+            Expression::new(
+                vec!(lhs.clone()),
+                *rhs)});
+        impl_op_ex!(-|lhs: &Term<$var>, rhs: &f64| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &f64, rhs: &Term<$var>| -> Expression<$var> { lhs + (-rhs) });
+
+        // Variable add and subtract
+        impl_op_ex!(+ |lhs: &$var, rhs: &$var| -> Expression<$var> {
+            Expression::new(
+                vec!(Term::new(lhs.clone(), 1.0), Term::new(rhs.clone(), 1.0)),
+                0.0)});
+        impl_op_ex!(-|lhs: &$var, rhs: &$var| -> Expression<$var> { lhs + (-rhs) });
+
+        impl_op_ex_commutative!(+ |lhs: &$var, rhs: &f64| -> Expression<$var> {
+            Expression::new(
+                vec!(Term::new(lhs.clone(), 1.0)),
+                *rhs)});
+        impl_op_ex!(-|lhs: &$var, rhs: &f64| -> Expression<$var> { lhs + (-rhs) });
+        impl_op_ex!(-|lhs: &f64, rhs: &$var| -> Expression<$var> { lhs + (-rhs) });
+
+        // Constraint strength modifier
+        impl_op_ex_commutative!(| |lhs: &Constraint<$var>, rhs: &f64| -> Constraint<$var> {
+            Constraint::from(lhs, *rhs)
+        });
+    };
+}
+
+impl_variable_ops!(Variable);
+impl_variable_ops!(VarId);
 
 // Constraints creation
 
@@ -211,6 +225,3 @@ impl IntoConstraint for Variable {
         Expression::new(vec![Term::new(self, 1.0)], 0.0)
     }
 }
-
-// Constraint strength modifier
-impl_op_ex_commutative!(| |lhs: &Constraint, rhs: &f64| -> Constraint { Constraint::from(lhs, *rhs) });
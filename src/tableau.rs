@@ -0,0 +1,475 @@
+//! Rows of the simplex tableau indexed by basic symbol and by column.
+//!
+//! Most pivots only touch the rows in which the entering symbol appears. When the rows are
+//! sparse, the tableau maintains for each parametric symbol the basic symbols whose row may
+//! contain it, so that substitutions and the searches over a column do not scan every row.
+//!
+//! Recording a cell in a column touches memory unrelated to the rows being merged, and dense
+//! rows gain many cells on each substitution. The tableau hence counts the cells inserted by
+//! the substitutions and only keeps the index while it costs less than scanning the rows.
+//! Both ways visit the rows in the same order, so the pivots do not depend on the choice.
+//!
+
+use crate::assoc_vec::AssocVec;
+use crate::row::{Row, RowBuffer};
+use crate::symbol::{Symbol, SymbolKind};
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Hasher for symbols, whose ids are consecutive integers hashed on the hot path of the pivots.
+#[derive(Default)]
+struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.write_u64(self.0 ^ u64::from(*byte));
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        // Fibonacci hashing spreads consecutive ids over the high bits.
+        self.0 = i.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    }
+}
+
+type Columns = HashMap<Symbol, Vec<Symbol>, BuildHasherDefault<IdHasher>>;
+
+/// Number of substitutions between two decisions to maintain the column index or not.
+const WINDOW: usize = 64;
+
+/// Cost of recording a cell in a column relative to checking a row while scanning.
+const LINK_COST: usize = 4;
+
+/// Number of rows below which scanning the rows is always cheap enough.
+const MIN_INDEXED_ROWS: usize = 256;
+
+/// Rows of the tableau keyed by their basic symbol.
+///
+/// The rows can only be modified through the tableau, which records the cells inserted in the
+/// rows in the column index.
+///
+/// The column of a parametric symbol lists every basic symbol whose row contains it, but may
+/// also list rows which do not contain it anymore (or do not exist anymore), possibly several
+/// times. Such entries are dropped when the column is used, and the whole index is rebuilt
+/// from the rows when its size doubled since the last rebuild.
+///
+#[derive(Default)]
+pub struct Tableau {
+    m_rows: AssocVec<Symbol, Row>,
+    // Column index, empty when not maintained.
+    m_columns: Columns,
+    m_indexed: bool,
+    // Number of entries in the columns, and that number after the last rebuild.
+    m_entries: usize,
+    m_rebuilt_entries: usize,
+    // Substitutions and inserted cells since the last decision to maintain the index or not.
+    m_substitutions: usize,
+    m_insertions: usize,
+}
+
+impl Tableau {
+    pub fn new() -> Tableau {
+        Tableau {
+            m_rows: AssocVec::new(),
+            m_columns: Columns::default(),
+            m_indexed: false,
+            m_entries: 0,
+            m_rebuilt_entries: 0,
+            m_substitutions: 0,
+            m_insertions: 0,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.m_rows.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_indexed(&self) -> bool {
+        self.m_indexed
+    }
+
+    #[inline]
+    pub fn contains_key(&self, symbol: Symbol) -> bool {
+        self.m_rows.contains_key(&symbol)
+    }
+
+    #[inline]
+    pub fn get(&self, symbol: Symbol) -> Option<&Row> {
+        self.m_rows.get(&symbol)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, (Symbol, Row)> {
+        self.m_rows.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.m_rows.clear();
+        self.set_indexed(false);
+        self.m_substitutions = 0;
+        self.m_insertions = 0;
+    }
+
+    /// Insert the row of a basic symbol, returning the previous row of the symbol if any.
+    pub fn insert(&mut self, symbol: Symbol, row: Row) -> Option<Row> {
+        self.m_insertions += row.cells().len();
+        if self.m_indexed {
+            for (s, _) in row.cells().iter() {
+                Tableau::link(&mut self.m_columns, &mut self.m_entries, *s, symbol);
+            }
+        }
+        let old = self.m_rows.insert(symbol, row);
+        self.rebuild_if_oversized();
+        old
+    }
+
+    /// Remove the row of a basic symbol.
+    ///
+    /// The entries of the symbol in the columns are dropped when the columns are used.
+    ///
+    #[inline]
+    pub fn remove(&mut self, symbol: Symbol) -> Option<Row> {
+        self.m_rows.remove(&symbol)
+    }
+
+    /// Iterate over the rows containing a parametric symbol, ordered by basic symbol.
+    pub fn column(&mut self, symbol: Symbol) -> ColumnIter<'_> {
+        if !self.m_indexed {
+            return ColumnIter::Scan(self.m_rows.iter(), symbol);
+        }
+        self.compact(symbol);
+        let basics = match self.m_columns.get(&symbol) {
+            Some(column) => column.iter(),
+            None => [].iter(),
+        };
+        ColumnIter::Indexed(basics, &self.m_rows)
+    }
+
+    /// Add to the constant of a row, returning the new constant.
+    pub fn add(&mut self, symbol: Symbol, value: f64) -> Option<f64> {
+        self.m_rows.get_mut(&symbol).map(|row| *row.add(value))
+    }
+
+    /// Shift the value of a parametric symbol by delta in every row containing it.
+    ///
+    /// The restricted rows which become infeasible are pushed on infeasible.
+    ///
+    pub fn shift(&mut self, symbol: Symbol, delta: f64, infeasible: &mut Vec<Symbol>) {
+        let mut shift_row = |s: Symbol, row: &mut Row| {
+            let coeff = row.coefficient_for(symbol);
+            if coeff != 0.0 && *row.add(delta * coeff) < 0.0 && s.kind() != SymbolKind::External {
+                infeasible.push(s);
+            }
+        };
+        if !self.m_indexed {
+            for (s, row) in self.m_rows.iter_mut() {
+                shift_row(*s, row);
+            }
+            return;
+        }
+        self.compact(symbol);
+        if let Some(column) = self.m_columns.get(&symbol) {
+            for s in column.iter() {
+                shift_row(*s, &mut self.m_rows[s]);
+            }
+        }
+    }
+
+    /// Substitute a parametric symbol with the given row in every row containing it.
+    ///
+    /// The rows are visited in the order of their basic symbol. The restricted rows which
+    /// become infeasible are pushed on infeasible.
+    ///
+    pub fn substitute(
+        &mut self,
+        symbol: Symbol,
+        row: &Row,
+        eps: f64,
+        buffer: &mut RowBuffer,
+        infeasible: &mut Vec<Symbol>,
+    ) {
+        let mut insertions = 0;
+        if self.m_indexed {
+            if let Some(mut column) = self.m_columns.remove(&symbol) {
+                self.m_entries -= column.len();
+                column.sort();
+                column.dedup();
+                for s in column.iter() {
+                    let r = match self.m_rows.get_mut(s) {
+                        Some(r) if r.cells().contains_key(&symbol) => r,
+                        _ => continue,
+                    };
+                    let columns = &mut self.m_columns;
+                    let entries = &mut self.m_entries;
+                    r.substitute_with(symbol, row, eps, buffer, |cell, inserted| {
+                        if inserted {
+                            insertions += 1;
+                            Tableau::link(columns, entries, cell, *s);
+                        }
+                    });
+                    if s.kind() != SymbolKind::External && *r.constant() < 0.0 {
+                        infeasible.push(*s);
+                    }
+                }
+            }
+        } else {
+            for (s, r) in self.m_rows.iter_mut() {
+                if !r.cells().contains_key(&symbol) {
+                    continue;
+                }
+                r.substitute_with(symbol, row, eps, buffer, |_, inserted| {
+                    if inserted {
+                        insertions += 1;
+                    }
+                });
+                if s.kind() != SymbolKind::External && *r.constant() < 0.0 {
+                    infeasible.push(*s);
+                }
+            }
+        }
+        self.m_insertions += insertions;
+        self.m_substitutions += 1;
+        if self.m_substitutions == WINDOW {
+            // Maintain the index if the cells inserted by a substitution cost less to record
+            // than scanning the rows.
+            let indexed = self.m_rows.len() >= MIN_INDEXED_ROWS
+                && self.m_insertions * LINK_COST < self.m_rows.len() * WINDOW;
+            self.set_indexed(indexed);
+            self.m_substitutions = 0;
+            self.m_insertions = 0;
+        }
+        self.rebuild_if_oversized();
+    }
+
+    /// Remove a parametric symbol from every row containing it.
+    pub fn remove_symbol(&mut self, symbol: Symbol) {
+        if !self.m_indexed {
+            for (_, row) in self.m_rows.iter_mut() {
+                row.remove(symbol);
+            }
+        } else if let Some(column) = self.m_columns.remove(&symbol) {
+            self.m_entries -= column.len();
+            for s in column.iter() {
+                if let Some(row) = self.m_rows.get_mut(s) {
+                    row.remove(symbol);
+                }
+            }
+        }
+    }
+
+    /// Start or stop maintaining the column index.
+    fn set_indexed(&mut self, indexed: bool) {
+        if indexed != self.m_indexed {
+            self.m_indexed = indexed;
+            self.rebuild();
+        }
+    }
+
+    /// Record that the row of a basic symbol contains a parametric symbol.
+    #[inline]
+    fn link(columns: &mut Columns, entries: &mut usize, symbol: Symbol, basic: Symbol) {
+        columns.entry(symbol).or_default().push(basic);
+        *entries += 1;
+    }
+
+    /// Drop the stale and duplicated entries of the column of a symbol and sort it.
+    fn compact(&mut self, symbol: Symbol) {
+        let rows = &self.m_rows;
+        if let Some(column) = self.m_columns.get_mut(&symbol) {
+            let len = column.len();
+            column.sort();
+            column.dedup();
+            column.retain(|s| {
+                rows.get(s)
+                    .is_some_and(|row| row.cells().contains_key(&symbol))
+            });
+            self.m_entries -= len - column.len();
+            if column.is_empty() {
+                self.m_columns.remove(&symbol);
+            }
+        }
+    }
+
+    /// Rebuild the columns from the rows if they doubled in size since the last rebuild.
+    fn rebuild_if_oversized(&mut self) {
+        if self.m_entries > 2 * self.m_rebuilt_entries + 64 {
+            self.rebuild();
+        }
+    }
+
+    /// Rebuild the columns from the rows, or only clear them if the index is not maintained.
+    fn rebuild(&mut self) {
+        self.m_columns.clear();
+        self.m_entries = 0;
+        if self.m_indexed {
+            for (basic, row) in self.m_rows.iter() {
+                for (s, _) in row.cells().iter() {
+                    Tableau::link(&mut self.m_columns, &mut self.m_entries, *s, *basic);
+                }
+            }
+        }
+        self.m_rebuilt_entries = self.m_entries;
+    }
+}
+
+/// Iterator over the rows containing a parametric symbol (see Tableau::column).
+pub enum ColumnIter<'a> {
+    Indexed(std::slice::Iter<'a, Symbol>, &'a AssocVec<Symbol, Row>),
+    Scan(std::slice::Iter<'a, (Symbol, Row)>, Symbol),
+}
+
+impl<'a> Iterator for ColumnIter<'a> {
+    type Item = (Symbol, &'a Row);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ColumnIter::Indexed(basics, rows) => basics.next().map(|s| (*s, &rows[s])),
+            ColumnIter::Scan(rows, symbol) => rows
+                .find(|(_, row)| row.cells().contains_key(symbol))
+                .map(|(s, row)| (*s, row)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Tableau;
+    use crate::row::{Row, RowBuffer};
+    use crate::symbol::{Symbol, SymbolKind};
+
+    fn slack(id: u64) -> Symbol {
+        Symbol::new(SymbolKind::Slack, id)
+    }
+
+    fn row(constant: f64, cells: &[(u64, f64)]) -> Row {
+        let mut row = Row::new(constant);
+        for (id, coefficient) in cells.iter() {
+            row.insert_symbol(slack(*id), *coefficient, 1e-8);
+        }
+        row
+    }
+
+    fn tableau(indexed: bool) -> Tableau {
+        let mut tableau = Tableau::new();
+        tableau.set_indexed(indexed);
+        tableau
+    }
+
+    fn column(tableau: &mut Tableau, id: u64) -> Vec<u64> {
+        tableau.column(slack(id)).map(|(s, _)| s.id()).collect()
+    }
+
+    /// Check that every row is listed in the columns of its cells if the index is maintained.
+    fn check_columns(tableau: &Tableau) {
+        if !tableau.m_indexed {
+            assert!(tableau.m_columns.is_empty());
+            return;
+        }
+        for (basic, row) in tableau.m_rows.iter() {
+            for (symbol, _) in row.cells().iter() {
+                assert!(tableau.m_columns[symbol].contains(basic));
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        for indexed in [false, true].iter() {
+            let mut tableau = tableau(*indexed);
+            tableau.insert(slack(1), row(1.0, &[(10, 1.0), (11, 2.0)]));
+            tableau.insert(slack(2), row(2.0, &[(11, 1.0), (12, -1.0)]));
+            check_columns(&tableau);
+            assert_eq!(column(&mut tableau, 11), vec![1, 2]);
+            assert_eq!(column(&mut tableau, 13), Vec::<u64>::new());
+
+            // Replacing a row leaves stale entries which are dropped when using the column.
+            tableau.insert(slack(1), row(1.0, &[(12, 1.0)]));
+            check_columns(&tableau);
+            assert_eq!(column(&mut tableau, 10), Vec::<u64>::new());
+            assert_eq!(column(&mut tableau, 12), vec![1, 2]);
+
+            assert!(tableau.remove(slack(2)).is_some());
+            assert!(tableau.remove(slack(2)).is_none());
+            check_columns(&tableau);
+            assert_eq!(column(&mut tableau, 11), Vec::<u64>::new());
+            assert_eq!(column(&mut tableau, 12), vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_rebuild() {
+        let mut tableau = tableau(true);
+        tableau.insert(slack(1), row(1.0, &[(10, 1.0)]));
+        // Replacing a row many times does not let the stale entries accumulate.
+        for i in 0..1000 {
+            tableau.insert(slack(2), row(1.0, &[(10, i as f64 + 1.0)]));
+        }
+        check_columns(&tableau);
+        assert!(tableau.m_columns[&slack(10)].len() <= 2 * 2 + 64);
+        assert_eq!(column(&mut tableau, 10), vec![1, 2]);
+
+        tableau.set_indexed(false);
+        check_columns(&tableau);
+        tableau.set_indexed(true);
+        check_columns(&tableau);
+        assert_eq!(tableau.m_columns[&slack(10)].len(), 2);
+    }
+
+    #[test]
+    fn test_substitute() {
+        for indexed in [false, true].iter() {
+            let mut buffer = RowBuffer::new();
+            let mut infeasible = Vec::new();
+            let mut tableau = tableau(*indexed);
+            tableau.insert(slack(1), row(1.0, &[(10, 1.0), (11, 1.0)]));
+            tableau.insert(slack(2), row(2.0, &[(10, 2.0), (12, 1.0)]));
+            tableau.insert(slack(3), row(-3.0, &[(12, 1.0)]));
+
+            // x10 = -2 - x11 + x13, which cancels x11 in the first row.
+            tableau.substitute(
+                slack(10),
+                &row(-2.0, &[(11, -1.0), (13, 1.0)]),
+                1e-8,
+                &mut buffer,
+                &mut infeasible,
+            );
+            check_columns(&tableau);
+            assert_eq!(column(&mut tableau, 10), Vec::<u64>::new());
+            assert_eq!(column(&mut tableau, 11), vec![2]);
+            assert_eq!(column(&mut tableau, 13), vec![1, 2]);
+            assert_eq!(*tableau.get(slack(1)).unwrap().constant(), -1.0);
+            // The rows which do not contain the symbol are left untouched.
+            assert_eq!(infeasible, vec![slack(1), slack(2)]);
+
+            tableau.remove_symbol(slack(12));
+            check_columns(&tableau);
+            assert!(tableau.get(slack(3)).unwrap().cells().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_shift() {
+        for indexed in [false, true].iter() {
+            let mut infeasible = Vec::new();
+            let mut tableau = tableau(*indexed);
+            tableau.insert(slack(1), row(1.0, &[(10, 1.0)]));
+            tableau.insert(slack(2), row(1.0, &[(10, -1.0)]));
+            tableau.insert(slack(3), row(1.0, &[(11, 1.0)]));
+            tableau.shift(slack(10), 2.0, &mut infeasible);
+            assert_eq!(*tableau.get(slack(1)).unwrap().constant(), 3.0);
+            assert_eq!(*tableau.get(slack(2)).unwrap().constant(), -1.0);
+            assert_eq!(*tableau.get(slack(3)).unwrap().constant(), 1.0);
+            assert_eq!(infeasible, vec![slack(2)]);
+        }
+    }
+}
@@ -3,14 +3,14 @@ use std::fmt;
 
 /// Term represent a variable with a multiplicative coefficient.
 #[derive(Debug, Clone)]
-pub struct Term {
-    m_variable: Variable,
+pub struct Term<V = Variable> {
+    m_variable: V,
     m_coefficient: f64,
 }
 
-impl Term {
-    /// Create a term from a Variable and a floating point coefficient.
-    pub fn new(variable: Variable, coefficient: f64) -> Term {
+impl<V> Term<V> {
+    /// Create a term from a variable handle and a floating point coefficient.
+    pub fn new(variable: V, coefficient: f64) -> Term<V> {
         Term {
             m_variable: variable,
             m_coefficient: coefficient,
@@ -18,7 +18,7 @@ impl Term {
     }
 
     /// Access the term variable.
-    pub fn variable(&self) -> &Variable {
+    pub fn variable(&self) -> &V {
         &self.m_variable
     }
 
@@ -26,20 +26,18 @@ impl Term {
     pub fn coefficient(&self) -> f64 {
         self.m_coefficient
     }
+}
 
+impl Term {
     /// Compute the product of the coefficient and variable value.
     pub fn value(&self) -> f64 {
         self.m_coefficient * *self.m_variable.value()
     }
 }
 
-impl fmt::Display for Term {
+impl<V: fmt::Display> fmt::Display for Term<V> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_args!(
-            "{} * {}",
-            self.m_coefficient,
-            self.m_variable.name()
-        ))
+        f.write_fmt(format_args!("{} * {}", self.m_coefficient, self.m_variable))
     }
 }
 
@@ -71,6 +69,6 @@ mod tests {
     fn test_display() {
         let v = Variable::new_with_name("test");
         let t = Term::new(v, 2.0);
-        assert_eq!(format!("{}", t), "2.0 * test")
+        assert_eq!(format!("{}", t), "2 * test")
     }
 }
@@ -1,20 +1,23 @@
 //! Utility functions.
 
+/// Default tolerance used to decide whether a floating point value is zero.
+pub(crate) const EPSILON: f64 = 1.0e-8;
+
 /// Check if a floating point value is close to zero
 #[inline]
-pub(crate) fn near_zero(value: f64) -> bool {
-    let eps = 1.0e-8;
+pub(crate) fn near_zero(value: f64, eps: f64) -> bool {
     value.abs() < eps
 }
 
 #[cfg(test)]
 mod test {
 
-    use super::near_zero;
+    use super::{near_zero, EPSILON};
 
     #[test]
     fn test_near_zero() {
-        assert!(near_zero(1e-9));
-        assert!(!near_zero(1.0));
+        assert!(near_zero(1e-9, EPSILON));
+        assert!(!near_zero(1.0, EPSILON));
+        assert!(!near_zero(1e-9, 1e-12));
     }
 }
@@ -1,9 +1,34 @@
 use std::cell::{Ref, RefCell};
 use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
 use std::hash;
 use std::rc::Rc;
 use std::string::String;
 
+mod private {
+    /// Access to the value of a variable handle, only implemented in this crate.
+    pub trait Sealed {
+        /// Read the value of the variable, values holding the values of the solver arena.
+        fn load(&self, values: &[f64]) -> f64;
+
+        /// Write the value of the variable, values holding the values of the solver arena.
+        fn store(&self, values: &mut [f64], value: f64);
+
+        /// Test whether the value of the variable is held by the solver arena, if it uses one.
+        fn is_known(&self, values: &[f64]) -> bool;
+    }
+}
+
+pub(crate) use private::Sealed;
+
+/// Handle identifying a variable in expressions, constraints and solvers.
+///
+/// Variable handles carry their own name and value, shared by all their clones and
+/// updated by the solver. VarId handles are allocated by a `Solver<VarId>` which stores
+/// the names and values in its own arena.
+pub trait VariableHandle: Clone + Ord + hash::Hash + fmt::Debug + fmt::Display + Sealed {}
+
 /// Internal data of a variable.
 #[derive(Debug)]
 struct VariableData {
@@ -65,6 +90,12 @@ impl Variable {
     }
 }
 
+impl Default for Variable {
+    fn default() -> Variable {
+        Variable::new()
+    }
+}
+
 impl cmp::PartialEq for Variable {
     fn eq(&self, other: &Self) -> bool {
         Rc::ptr_eq(&self.m_variable, &other.m_variable)
@@ -91,10 +122,83 @@ impl hash::Hash for Variable {
     }
 }
 
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+impl Sealed for Variable {
+    #[inline]
+    fn load(&self, _values: &[f64]) -> f64 {
+        *self.value()
+    }
+
+    #[inline]
+    fn store(&self, _values: &mut [f64], value: f64) {
+        self.set_value(value);
+    }
+
+    #[inline]
+    fn is_known(&self, _values: &[f64]) -> bool {
+        true
+    }
+}
+
+impl VariableHandle for Variable {}
+
+/// Lightweight handle of a variable allocated by a solver.
+///
+/// The name and value of the variable are stored by the solver which created it (see
+/// `Solver::<VarId>::new_variable`), so a VarId is Copy and building expressions from it
+/// does not touch any reference count. A VarId must only be used with the solver which
+/// allocated it: the solver reports the handles it did not allocate as unknown variables, but
+/// cannot tell apart the handles of another solver with as many variables. The handles remain
+/// valid across a reset of the solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarId(u32);
+
+impl VarId {
+    /// Create a handle from the index of the variable in the solver arena.
+    pub(crate) fn new(index: usize) -> VarId {
+        VarId(u32::try_from(index).expect("too many variables allocated by the solver"))
+    }
+
+    /// Access the index of the variable in the solver arena.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for VarId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl Sealed for VarId {
+    #[inline]
+    fn load(&self, values: &[f64]) -> f64 {
+        values[self.index()]
+    }
+
+    #[inline]
+    fn store(&self, values: &mut [f64], value: f64) {
+        values[self.index()] = value;
+    }
+
+    #[inline]
+    fn is_known(&self, values: &[f64]) -> bool {
+        self.index() < values.len()
+    }
+}
+
+impl VariableHandle for VarId {}
+
 #[cfg(test)]
 mod tests {
 
-    use super::Variable;
+    use super::{Sealed, VarId, Variable};
 
     #[test]
     fn test_variable_access() {
@@ -121,4 +225,18 @@ mod tests {
         assert_eq!(*var.name(), "test2");
         assert_eq!(*var2.name(), "test2");
     }
+
+    #[test]
+    fn test_var_id() {
+        let a = VarId::new(0);
+        let b = VarId::new(2);
+        assert_eq!(b.index(), 2);
+        assert!(a < b);
+        assert_eq!(format!("{}", b), "v2");
+
+        let mut values = vec![0.0; 3];
+        b.store(&mut values, 4.0);
+        assert_eq!(b.load(&values), 4.0);
+        assert_eq!(values, vec![0.0, 0.0, 4.0]);
+    }
 }
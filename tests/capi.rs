@@ -0,0 +1,71 @@
+//! Compile and run the C test program of the C API against the library.
+
+#![cfg(feature = "capi")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Directory holding the header generated by the build script.
+fn include_dir() -> PathBuf {
+    PathBuf::from(env!("OUT_DIR")).join("include")
+}
+
+/// Check that the committed copy of the header matches the generated one.
+///
+/// Run the test with KIWI_UPDATE_HEADER=1 to update the copy after changing the C API.
+#[test]
+fn test_header_up_to_date() {
+    let generated = fs::read_to_string(include_dir().join("kiwi.h")).unwrap();
+    let committed = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/kiwi.h");
+    if env::var_os("KIWI_UPDATE_HEADER").is_some() {
+        fs::write(&committed, &generated).unwrap();
+    }
+    assert!(
+        fs::read_to_string(&committed).ok().as_deref() == Some(generated.as_str()),
+        "include/kiwi.h is out of date, update it with \
+         KIWI_UPDATE_HEADER=1 cargo test --features capi --test capi"
+    );
+}
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Test executables live in target/<profile>/deps, where cargo also builds the cdylib of
+    // the library for the tests (it is only copied to target/<profile> by cargo build).
+    let exe = env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let program = lib_dir.join("test_capi");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(include_dir())
+        .arg(manifest_dir.join("tests/capi/test_capi.c"))
+        .arg("-o")
+        .arg(&program)
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lkiwi_rs")
+        .arg("-lm")
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "failed to compile the C test program");
+
+    // Cargo adds target/<profile> to the library path, which could hold a library built
+    // with other features: rely on the rpath instead.
+    let output = Command::new(&program)
+        .env_remove("LD_LIBRARY_PATH")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "C test program failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,177 @@
+/* Test program of the C API, compiled and run by tests/capi.rs. */
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "kiwi.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                       \
+    do {                                                                       \
+        if (!(condition)) {                                                    \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,   \
+                    #condition);                                               \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+#define CHECK_RESULT(call, expected)                                           \
+    do {                                                                       \
+        KiwiResult result_ = (call);                                           \
+        if (result_ != (expected)) {                                           \
+            fprintf(stderr, "%s:%d: %s returned '%s'\n", __FILE__, __LINE__,   \
+                    #call, kiwi_result_message(result_));                      \
+            failures++;                                                        \
+        }                                                                      \
+    } while (0)
+
+static int close_to(double a, double b) { return fabs(a - b) < 1e-8; }
+
+static void test_variable(void) {
+    KiwiVariable *v = kiwi_variable_new("foo");
+    char name[16];
+
+    CHECK(kiwi_variable_name(v, name, sizeof(name)) == 3);
+    CHECK(strcmp(name, "foo") == 0);
+    kiwi_variable_set_name(v, "width");
+    CHECK(kiwi_variable_name(v, NULL, 0) == 5);
+    CHECK(kiwi_variable_value(v) == 0.0);
+    kiwi_variable_del(v);
+}
+
+static void test_managing_constraints(void) {
+    KiwiSolver *solver = kiwi_solver_new();
+    KiwiVariable *v = kiwi_variable_new("v");
+    KiwiTerm terms[] = {{v, 1.0}};
+    /* v >= 1 and v <= 0 */
+    KiwiConstraint *c1 =
+        kiwi_constraint_new(terms, 1, -1.0, KIWI_OP_GREATER_EQUAL, KIWI_STRENGTH_REQUIRED);
+    KiwiConstraint *c2 =
+        kiwi_constraint_new(terms, 1, 0.0, KIWI_OP_LESS_EQUAL, KIWI_STRENGTH_REQUIRED);
+
+    CHECK(!kiwi_solver_has_constraint(solver, c1));
+    CHECK_RESULT(kiwi_solver_add_constraint(solver, c1), KIWI_RESULT_OK);
+    CHECK(kiwi_solver_has_constraint(solver, c1));
+    CHECK_RESULT(kiwi_solver_add_constraint(solver, c1), KIWI_RESULT_DUPLICATE_CONSTRAINT);
+    CHECK_RESULT(kiwi_solver_remove_constraint(solver, c2), KIWI_RESULT_UNKNOWN_CONSTRAINT);
+    CHECK_RESULT(kiwi_solver_add_constraint(solver, c2),
+                 KIWI_RESULT_UNSATISFIABLE_CONSTRAINT);
+    CHECK(!kiwi_solver_has_constraint(solver, c2));
+
+    CHECK_RESULT(kiwi_solver_remove_constraint(solver, c1), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_add_constraint(solver, c2), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_update_variables(solver), KIWI_RESULT_OK);
+    CHECK(kiwi_variable_value(v) <= 0.0);
+    CHECK(!kiwi_constraint_violated(c2));
+
+    CHECK_RESULT(kiwi_solver_add_constraint(solver, NULL), KIWI_RESULT_NULL_HANDLE);
+    terms[0].variable = NULL;
+    CHECK(kiwi_constraint_new(terms, 1, 0.0, KIWI_OP_EQUAL, KIWI_STRENGTH_WEAK) == NULL);
+
+    kiwi_constraint_del(c1);
+    kiwi_constraint_del(c2);
+    kiwi_variable_del(v);
+    kiwi_solver_del(solver);
+}
+
+static void test_managing_edit_variables(void) {
+    KiwiSolver *solver = kiwi_solver_new();
+    KiwiVariable *v1 = kiwi_variable_new("foo");
+    KiwiVariable *v2 = kiwi_variable_new("bar");
+
+    CHECK(!kiwi_solver_has_edit_variable(solver, v1));
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, v1, KIWI_STRENGTH_WEAK), KIWI_RESULT_OK);
+    CHECK(kiwi_solver_has_edit_variable(solver, v1));
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, v1, KIWI_STRENGTH_MEDIUM),
+                 KIWI_RESULT_DUPLICATE_EDIT_VARIABLE);
+    CHECK_RESULT(kiwi_solver_remove_edit_variable(solver, v2),
+                 KIWI_RESULT_UNKNOWN_EDIT_VARIABLE);
+    CHECK_RESULT(kiwi_solver_remove_edit_variable(solver, v1), KIWI_RESULT_OK);
+    CHECK(!kiwi_solver_has_edit_variable(solver, v1));
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, v1, KIWI_STRENGTH_REQUIRED),
+                 KIWI_RESULT_BAD_REQUIRED_STRENGTH);
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, v2, 10.0),
+                 KIWI_RESULT_UNKNOWN_EDIT_VARIABLE);
+
+    kiwi_solver_reset(solver);
+    CHECK(!kiwi_solver_has_edit_variable(solver, v1));
+
+    kiwi_variable_del(v1);
+    kiwi_variable_del(v2);
+    kiwi_solver_del(solver);
+}
+
+static void test_handling_infeasible_constraints(void) {
+    KiwiSolver *solver = kiwi_solver_new();
+    KiwiVariable *xm = kiwi_variable_new("xm");
+    KiwiVariable *xl = kiwi_variable_new("xl");
+    KiwiVariable *xr = kiwi_variable_new("xr");
+    KiwiConstraint *constraints[4];
+    size_t i;
+
+    /* 2 * xm == xl + xr */
+    KiwiTerm middle[] = {{xm, 2.0}, {xl, -1.0}, {xr, -1.0}};
+    /* xl + 20 <= xr */
+    KiwiTerm order[] = {{xl, 1.0}, {xr, -1.0}};
+    /* xl >= -10 and xr <= 100 */
+    KiwiTerm left[] = {{xl, 1.0}};
+    KiwiTerm right[] = {{xr, 1.0}};
+
+    constraints[0] = kiwi_constraint_new(middle, 3, 0.0, KIWI_OP_EQUAL, KIWI_STRENGTH_REQUIRED);
+    constraints[1] =
+        kiwi_constraint_new(order, 2, 20.0, KIWI_OP_LESS_EQUAL, KIWI_STRENGTH_REQUIRED);
+    constraints[2] =
+        kiwi_constraint_new(left, 1, 10.0, KIWI_OP_GREATER_EQUAL, KIWI_STRENGTH_REQUIRED);
+    constraints[3] =
+        kiwi_constraint_new(right, 1, -100.0, KIWI_OP_LESS_EQUAL, KIWI_STRENGTH_REQUIRED);
+
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, xm, KIWI_STRENGTH_STRONG), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, xl, KIWI_STRENGTH_WEAK), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_add_edit_variable(solver, xr, KIWI_STRENGTH_WEAK), KIWI_RESULT_OK);
+    for (i = 0; i < 4; i++) {
+        CHECK_RESULT(kiwi_solver_add_constraint(solver, constraints[i]), KIWI_RESULT_OK);
+        /* The solver keeps its own reference to the constraint. */
+        kiwi_constraint_del(constraints[i]);
+    }
+
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, xm, 40.0), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, xr, 50.0), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, xl, 30.0), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, xm, 60.0), KIWI_RESULT_OK);
+    /* Infeasible suggestion triggering a dual optimization. */
+    CHECK_RESULT(kiwi_solver_suggest_value(solver, xm, 90.0), KIWI_RESULT_OK);
+    CHECK_RESULT(kiwi_solver_update_variables(solver), KIWI_RESULT_OK);
+
+    CHECK(close_to(kiwi_variable_value(xl) + kiwi_variable_value(xr),
+                   2.0 * kiwi_variable_value(xm)));
+    CHECK(close_to(kiwi_variable_value(xl), 80.0));
+    CHECK(close_to(kiwi_variable_value(xr), 100.0));
+
+    kiwi_variable_del(xm);
+    kiwi_variable_del(xl);
+    kiwi_variable_del(xr);
+    kiwi_solver_del(solver);
+}
+
+static void test_strength(void) {
+    CHECK(kiwi_strength_create(1.0, 0.0, 0.0, 1.0) == KIWI_STRENGTH_STRONG);
+    CHECK(kiwi_strength_create(0.0, 1.0, 0.0, 1.0) == KIWI_STRENGTH_MEDIUM);
+    CHECK(kiwi_strength_create(0.0, 0.0, 1.0, 1.0) == KIWI_STRENGTH_WEAK);
+    CHECK(kiwi_strength_create(1000.0, 1000.0, 1000.0, 1.0) == KIWI_STRENGTH_REQUIRED);
+}
+
+int main(void) {
+    test_variable();
+    test_managing_constraints();
+    test_managing_edit_variables();
+    test_handling_infeasible_constraints();
+    test_strength();
+    if (failures) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
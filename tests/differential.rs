@@ -0,0 +1,282 @@
+//! Differential tests of the solver against a dense reference linear programming solver.
+//!
+//! Random sequences of operations are applied both to the solver and to a model of the
+//! constraint system. After each operation the solution of the solver must satisfy the
+//! required constraints and reach the minimal weighted error computed by the reference.
+
+mod reference;
+
+use kiwi_rs::strength::{MEDIUM, REQUIRED, STRONG, WEAK};
+use kiwi_rs::{Constraint, Expression, KiwiError, RelationalOperator, Solver, Term, Variable};
+use proptest::prelude::*;
+use reference::{minimal_error, ConstraintSpec, LinearProgram, Solution};
+
+/// Number of variables shared by the generated constraints.
+const VARIABLES: usize = 4;
+
+/// Tolerance used when comparing the solver with the reference.
+const EPS: f64 = 1e-6;
+
+/// Operation applied to the solver.
+#[derive(Clone, Debug)]
+enum Operation {
+    AddConstraint(ConstraintSpec),
+    /// Remove one of the constraints currently in the solver, chosen modulo their number.
+    RemoveConstraint(usize),
+    AddEditVariable(usize, f64),
+    RemoveEditVariable(usize),
+    SuggestValue(usize, f64),
+}
+
+fn operator() -> impl Strategy<Value = RelationalOperator> {
+    prop_oneof![
+        Just(RelationalOperator::LessEqual),
+        Just(RelationalOperator::Equal),
+        Just(RelationalOperator::GreaterEqual),
+    ]
+}
+
+fn strength() -> impl Strategy<Value = f64> {
+    prop_oneof![Just(WEAK), Just(MEDIUM), Just(STRONG)]
+}
+
+fn constraint() -> impl Strategy<Value = ConstraintSpec> {
+    (
+        prop::collection::vec((0..VARIABLES, -3i32..=3), 1..=3),
+        -20i32..=20,
+        operator(),
+        prop_oneof![strength(), Just(REQUIRED)],
+    )
+        .prop_map(|(terms, constant, op, strength)| ConstraintSpec {
+            terms: terms
+                .into_iter()
+                .map(|(variable, coefficient)| (variable, coefficient as f64))
+                .collect(),
+            constant: constant as f64,
+            op,
+            strength,
+        })
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        4 => constraint().prop_map(Operation::AddConstraint),
+        1 => any::<usize>().prop_map(Operation::RemoveConstraint),
+        1 => (0..VARIABLES, strength()).prop_map(|(v, s)| Operation::AddEditVariable(v, s)),
+        1 => (0..VARIABLES).prop_map(Operation::RemoveEditVariable),
+        2 => (0..VARIABLES, -50i32..=50).prop_map(|(v, x)| Operation::SuggestValue(v, x as f64)),
+    ]
+}
+
+/// Model of the constraint system held by the solver.
+struct Model {
+    solver: Solver,
+    variables: Vec<Variable>,
+    constraints: Vec<(Constraint, ConstraintSpec)>,
+    /// Edited variables with the strength of the edit and the suggested value.
+    edits: Vec<(usize, f64, f64)>,
+}
+
+impl Model {
+    fn new() -> Model {
+        Model {
+            solver: Solver::new(),
+            variables: (0..VARIABLES)
+                .map(|i| Variable::new_with_name(&format!("x{}", i)))
+                .collect(),
+            constraints: Vec::new(),
+            edits: Vec::new(),
+        }
+    }
+
+    /// All the constraints of the system, edit variables being variable == value.
+    fn specs(&self) -> Vec<ConstraintSpec> {
+        let mut specs: Vec<ConstraintSpec> = self
+            .constraints
+            .iter()
+            .map(|(_, spec)| spec.clone())
+            .collect();
+        specs.extend(
+            self.edits
+                .iter()
+                .map(|&(variable, strength, value)| ConstraintSpec {
+                    terms: vec![(variable, 1.0)],
+                    constant: -value,
+                    op: RelationalOperator::Equal,
+                    strength,
+                }),
+        );
+        specs
+    }
+
+    fn edit(&self, variable: usize) -> Option<usize> {
+        self.edits.iter().position(|edit| edit.0 == variable)
+    }
+
+    fn apply(&mut self, operation: &Operation) {
+        match operation {
+            Operation::AddConstraint(spec) => {
+                let terms = spec
+                    .terms
+                    .iter()
+                    .map(|(variable, coefficient)| {
+                        Term::new(self.variables[*variable].clone(), *coefficient)
+                    })
+                    .collect();
+                let constraint = Constraint::new(
+                    Expression::new(terms, spec.constant),
+                    spec.op,
+                    spec.strength,
+                );
+                let mut specs = self.specs();
+                specs.push(spec.clone());
+                let feasible = minimal_error(VARIABLES, &specs) != Solution::Infeasible;
+                match self.solver.add_constraint(constraint.clone()) {
+                    Ok(()) => {
+                        assert!(feasible, "solver accepted an infeasible constraint");
+                        self.constraints.push((constraint, spec.clone()));
+                    }
+                    Err(KiwiError::UnsatisfiableConstraint { .. }) => {
+                        assert!(!feasible, "solver rejected a feasible constraint")
+                    }
+                    Err(error) => panic!("unexpected error {}", error),
+                }
+            }
+            Operation::RemoveConstraint(index) => {
+                if !self.constraints.is_empty() {
+                    let (constraint, _) = self.constraints.remove(index % self.constraints.len());
+                    self.solver.remove_constraint(&constraint).unwrap();
+                }
+            }
+            Operation::AddEditVariable(variable, strength) => {
+                let result = self
+                    .solver
+                    .add_edit_variable(&self.variables[*variable], *strength);
+                match self.edit(*variable) {
+                    Some(_) => assert!(matches!(
+                        result,
+                        Err(KiwiError::DuplicateEditVariable { .. })
+                    )),
+                    None => {
+                        assert!(result.is_ok(), "unexpected {:?}", result);
+                        self.edits.push((*variable, *strength, 0.0));
+                    }
+                }
+            }
+            Operation::RemoveEditVariable(variable) => {
+                let result = self.solver.remove_edit_variable(&self.variables[*variable]);
+                match self.edit(*variable) {
+                    Some(index) => {
+                        assert!(result.is_ok(), "unexpected {:?}", result);
+                        self.edits.remove(index);
+                    }
+                    None => assert!(matches!(result, Err(KiwiError::UnknownEditVariable { .. }))),
+                }
+            }
+            Operation::SuggestValue(variable, value) => {
+                let result = self
+                    .solver
+                    .suggest_value(&self.variables[*variable], *value);
+                match self.edit(*variable) {
+                    Some(index) => {
+                        assert!(result.is_ok(), "unexpected {:?}", result);
+                        self.edits[index].2 = *value;
+                    }
+                    None => assert!(matches!(result, Err(KiwiError::UnknownEditVariable { .. }))),
+                }
+            }
+        }
+    }
+
+    /// Compare the current solution of the solver with the reference.
+    fn check(&mut self) {
+        self.solver.update_variable();
+        let values: Vec<f64> = self.variables.iter().map(|v| *v.value()).collect();
+        let specs = self.specs();
+        let mut error = 0.0;
+        for spec in specs.iter() {
+            let violation = spec.violation(&values);
+            if spec.strength >= REQUIRED {
+                assert!(
+                    violation < EPS,
+                    "required constraint {:?} violated by {} at {:?}",
+                    spec,
+                    violation,
+                    values
+                );
+            } else {
+                error += spec.strength * violation;
+            }
+        }
+        match minimal_error(VARIABLES, &specs) {
+            Solution::Optimal(expected) => assert!(
+                (error - expected).abs() <= EPS * f64::max(1.0, expected),
+                "weighted error {} differs from the optimum {} at {:?}",
+                error,
+                expected,
+                values
+            ),
+            solution => panic!("reference found {:?} for an accepted system", solution),
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_against_reference(operations in prop::collection::vec(operation(), 1..16)) {
+        let mut model = Model::new();
+        for operation in operations.iter() {
+            model.apply(operation);
+            model.check();
+        }
+    }
+}
+
+#[test]
+fn test_reference_solver() {
+    // min -x - y with x + 2y <= 4, 3x + y <= 6: optimum at (1.6, 1.2).
+    let mut program = LinearProgram::new();
+    let x = program.add_variable(-1.0);
+    let y = program.add_variable(-1.0);
+    program.add_constraint(&[(x, 1.0), (y, 2.0)], RelationalOperator::LessEqual, 4.0);
+    program.add_constraint(&[(x, 3.0), (y, 1.0)], RelationalOperator::LessEqual, 6.0);
+    match program.solve() {
+        Solution::Optimal(value) => assert!((value + 2.8).abs() < EPS),
+        solution => panic!("unexpected {:?}", solution),
+    }
+
+    // Redundant equalities and an infeasible system.
+    let mut program = LinearProgram::new();
+    let x = program.add_variable(1.0);
+    program.add_constraint(&[(x, 1.0)], RelationalOperator::Equal, 2.0);
+    program.add_constraint(&[(x, 2.0)], RelationalOperator::Equal, 4.0);
+    assert_eq!(program.solve(), Solution::Optimal(2.0));
+    program.add_constraint(&[(x, 1.0)], RelationalOperator::GreaterEqual, 3.0);
+    assert_eq!(program.solve(), Solution::Infeasible);
+
+    // Unbounded direction.
+    let mut program = LinearProgram::new();
+    let x = program.add_variable(-1.0);
+    program.add_constraint(&[(x, 1.0)], RelationalOperator::GreaterEqual, 1.0);
+    assert_eq!(program.solve(), Solution::Unbounded);
+}
+
+#[test]
+fn test_reference_minimal_error() {
+    // x == 10 strong and x <= 5 weak: the weak constraint is violated by 5.
+    let specs = [
+        ConstraintSpec {
+            terms: vec![(0, 1.0)],
+            constant: -10.0,
+            op: RelationalOperator::Equal,
+            strength: STRONG,
+        },
+        ConstraintSpec {
+            terms: vec![(0, 1.0)],
+            constant: -5.0,
+            op: RelationalOperator::LessEqual,
+            strength: WEAK,
+        },
+    ];
+    assert_eq!(minimal_error(1, &specs), Solution::Optimal(5.0 * WEAK));
+}
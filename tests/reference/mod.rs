@@ -0,0 +1,271 @@
+//! Dense reference solver used to check the results of the Cassowary solver.
+//!
+//! The problem solved by the Cassowary solver is rewritten as an explicit linear program: the
+//! required constraints are kept as is and each non-required constraint gets non-negative
+//! error variables whose weighted sum (using the strength of the constraint) is minimized.
+//! The program is solved with a dense two-phase simplex using Bland's rule, which is slow but
+//! simple enough to be trusted on the tiny systems used by the tests.
+//!
+
+use kiwi_rs::strength::REQUIRED;
+use kiwi_rs::RelationalOperator;
+
+/// Tolerance used for the pivots of the reference simplex.
+const EPS: f64 = 1e-9;
+
+/// Description of a constraint: sum(coefficient * variable) + constant op 0.
+#[derive(Clone, Debug)]
+pub struct ConstraintSpec {
+    pub terms: Vec<(usize, f64)>,
+    pub constant: f64,
+    pub op: RelationalOperator,
+    pub strength: f64,
+}
+
+impl ConstraintSpec {
+    /// Value of the expression of the constraint for the given variable values.
+    pub fn value(&self, values: &[f64]) -> f64 {
+        self.constant
+            + self
+                .terms
+                .iter()
+                .map(|(variable, coefficient)| coefficient * values[*variable])
+                .sum::<f64>()
+    }
+
+    /// Amount by which the constraint is violated for the given variable values.
+    pub fn violation(&self, values: &[f64]) -> f64 {
+        let value = self.value(values);
+        match self.op {
+            RelationalOperator::Equal => value.abs(),
+            RelationalOperator::LessEqual => f64::max(value, 0.0),
+            RelationalOperator::GreaterEqual => f64::max(-value, 0.0),
+        }
+    }
+}
+
+/// Outcome of the resolution of a linear program.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Solution {
+    Optimal(f64),
+    Infeasible,
+    Unbounded,
+}
+
+/// Linear constraint on the non-negative variables of a linear program.
+struct Row {
+    coefficients: Vec<f64>,
+    op: RelationalOperator,
+    rhs: f64,
+}
+
+/// Linear program minimizing costs . x under linear constraints with x >= 0.
+#[derive(Default)]
+pub struct LinearProgram {
+    costs: Vec<f64>,
+    rows: Vec<Row>,
+}
+
+impl LinearProgram {
+    pub fn new() -> LinearProgram {
+        LinearProgram::default()
+    }
+
+    /// Add a non-negative variable with the given cost and return its index.
+    pub fn add_variable(&mut self, cost: f64) -> usize {
+        self.costs.push(cost);
+        self.costs.len() - 1
+    }
+
+    /// Add the constraint sum(coefficient * variable) op rhs.
+    pub fn add_constraint(&mut self, terms: &[(usize, f64)], op: RelationalOperator, rhs: f64) {
+        let mut coefficients = vec![0.0; self.costs.len()];
+        for (variable, coefficient) in terms.iter() {
+            coefficients[*variable] += coefficient;
+        }
+        self.rows.push(Row {
+            coefficients,
+            op,
+            rhs,
+        });
+    }
+
+    /// Solve the program with a two-phase simplex.
+    pub fn solve(&self) -> Solution {
+        let n = self.costs.len();
+        let m = self.rows.len();
+
+        // Columns: original variables, one slack per inequality, one artificial per row.
+        let slacks = self
+            .rows
+            .iter()
+            .filter(|row| row.op != RelationalOperator::Equal)
+            .count();
+        let width = n + slacks + m;
+        let mut tableau = vec![vec![0.0; width + 1]; m];
+        let mut basis = vec![0; m];
+        let mut slack = n;
+        for (i, row) in self.rows.iter().enumerate() {
+            // Make the right hand side non-negative.
+            let sign = if row.rhs < 0.0 { -1.0 } else { 1.0 };
+            for (j, coefficient) in row.coefficients.iter().enumerate() {
+                tableau[i][j] = sign * coefficient;
+            }
+            match row.op {
+                RelationalOperator::LessEqual => {
+                    tableau[i][slack] = sign;
+                    slack += 1;
+                }
+                RelationalOperator::GreaterEqual => {
+                    tableau[i][slack] = -sign;
+                    slack += 1;
+                }
+                RelationalOperator::Equal => (),
+            }
+            tableau[i][n + slacks + i] = 1.0;
+            tableau[i][width] = sign * row.rhs;
+            basis[i] = n + slacks + i;
+        }
+
+        // Phase 1: minimize the sum of the artificial variables.
+        let mut phase1 = vec![0.0; width];
+        for cost in phase1[n + slacks..].iter_mut() {
+            *cost = 1.0;
+        }
+        match simplex(&mut tableau, &mut basis, &phase1, width) {
+            Solution::Optimal(value) if value <= 1e-7 => (),
+            _ => return Solution::Infeasible,
+        }
+
+        // Drive the artificial variables out of the basis when possible. The rows where this
+        // is impossible are redundant and never constrain the other variables.
+        for i in 0..m {
+            if basis[i] >= n + slacks {
+                if let Some(j) = (0..n + slacks).find(|&j| tableau[i][j].abs() > EPS) {
+                    pivot(&mut tableau, &mut basis, i, j);
+                }
+            }
+        }
+
+        // Phase 2: minimize the actual costs without using the artificial variables.
+        let mut phase2 = vec![0.0; width];
+        phase2[..n].copy_from_slice(&self.costs);
+        simplex(&mut tableau, &mut basis, &phase2, n + slacks)
+    }
+}
+
+/// Value of the objective for the current basic solution.
+fn objective(tableau: &[Vec<f64>], basis: &[usize], costs: &[f64]) -> f64 {
+    let rhs = costs.len();
+    basis
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| costs[b] * tableau[i][rhs])
+        .sum()
+}
+
+/// Pivot the tableau so that column j becomes basic in row i.
+fn pivot(tableau: &mut [Vec<f64>], basis: &mut [usize], i: usize, j: usize) {
+    let factor = tableau[i][j];
+    for value in tableau[i].iter_mut() {
+        *value /= factor;
+    }
+    let pivot_row = tableau[i].clone();
+    for (k, row) in tableau.iter_mut().enumerate() {
+        if k != i && row[j] != 0.0 {
+            let coefficient = row[j];
+            for (value, p) in row.iter_mut().zip(pivot_row.iter()) {
+                *value -= coefficient * p;
+            }
+        }
+    }
+    basis[i] = j;
+}
+
+/// Run the primal simplex using Bland's rule, only letting the first columns enter the basis.
+fn simplex(
+    tableau: &mut [Vec<f64>],
+    basis: &mut [usize],
+    costs: &[f64],
+    columns: usize,
+) -> Solution {
+    let rhs = costs.len();
+    loop {
+        // Entering column: the first one with a negative reduced cost.
+        let entering = (0..columns).find(|&j| {
+            let reduced = costs[j]
+                - basis
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &b)| costs[b] * tableau[i][j])
+                    .sum::<f64>();
+            reduced < -EPS * f64::max(1.0, costs[j].abs())
+        });
+        let j = match entering {
+            Some(j) => j,
+            None => {
+                let value = objective(tableau, basis, costs);
+                return Solution::Optimal(if value.abs() < EPS { 0.0 } else { value });
+            }
+        };
+
+        // Leaving row: minimum ratio, ties broken by the smallest basic column.
+        let mut leaving: Option<(usize, f64)> = None;
+        for (i, row) in tableau.iter().enumerate() {
+            if row[j] > EPS {
+                let ratio = row[rhs] / row[j];
+                leaving = match leaving {
+                    Some((l, best))
+                        if best < ratio - EPS
+                            || ((best - ratio).abs() <= EPS && basis[l] < basis[i]) =>
+                    {
+                        Some((l, best))
+                    }
+                    _ => Some((i, ratio)),
+                };
+            }
+        }
+        match leaving {
+            Some((i, _)) => pivot(tableau, basis, i, j),
+            None => return Solution::Unbounded,
+        }
+    }
+}
+
+/// Compute the minimal weighted error of a system of constraints.
+///
+/// This is the optimal value of the objective of the Cassowary solver: the sum over the
+/// non-required constraints of their strength times their violation, under the required
+/// constraints.
+pub fn minimal_error(variables: usize, constraints: &[ConstraintSpec]) -> Solution {
+    let mut program = LinearProgram::new();
+    // Free variables are split into a positive and a negative part.
+    let parts: Vec<(usize, usize)> = (0..variables)
+        .map(|_| (program.add_variable(0.0), program.add_variable(0.0)))
+        .collect();
+    for constraint in constraints.iter() {
+        let mut terms = Vec::new();
+        for (variable, coefficient) in constraint.terms.iter() {
+            let (positive, negative) = parts[*variable];
+            terms.push((positive, *coefficient));
+            terms.push((negative, -coefficient));
+        }
+        if constraint.strength < REQUIRED {
+            // Errors measure how far the expression is from satisfying the relation.
+            match constraint.op {
+                RelationalOperator::Equal => {
+                    terms.push((program.add_variable(constraint.strength), -1.0));
+                    terms.push((program.add_variable(constraint.strength), 1.0));
+                }
+                RelationalOperator::LessEqual => {
+                    terms.push((program.add_variable(constraint.strength), -1.0));
+                }
+                RelationalOperator::GreaterEqual => {
+                    terms.push((program.add_variable(constraint.strength), 1.0));
+                }
+            }
+        }
+        program.add_constraint(&terms, constraint.op, -constraint.constant);
+    }
+    program.solve()
+}
@@ -0,0 +1,533 @@
+//! Behavioral tests of the solver ported from the kiwisolver test suite.
+
+use kiwi_rs::strength::{create, MEDIUM, REQUIRED, STRONG, WEAK};
+use kiwi_rs::{Constraint, KiwiError, RelationalOperator, Solver, Variable};
+
+/// Tolerance used when comparing the values computed by the solver.
+const EPS: f64 = 1e-8;
+
+fn assert_near(value: f64, expected: f64) {
+    assert!(
+        (value - expected).abs() < EPS,
+        "{} is not close to {}",
+        value,
+        expected
+    );
+}
+
+fn value(variable: &Variable) -> f64 {
+    *variable.value()
+}
+
+/// Check whether the current values of the variables satisfy a constraint.
+fn is_satisfied(constraint: &Constraint) -> bool {
+    let value = constraint.expression().value();
+    match constraint.op() {
+        RelationalOperator::Equal => value.abs() < EPS,
+        RelationalOperator::LessEqual => value < EPS,
+        RelationalOperator::GreaterEqual => value > -EPS,
+    }
+}
+
+#[test]
+fn test_managing_edit_variable() {
+    let mut solver = Solver::new();
+    let v1 = Variable::new_with_name("foo");
+    let v2 = Variable::new_with_name("bar");
+
+    assert!(!solver.has_edit_variable(&v1));
+    solver.add_edit_variable(&v1, WEAK).unwrap();
+    assert!(solver.has_edit_variable(&v1));
+    match solver.add_edit_variable(&v1, MEDIUM) {
+        Err(KiwiError::DuplicateEditVariable { variable }) => assert_eq!(variable, v1),
+        _ => panic!("expected a duplicate edit variable error"),
+    }
+    match solver.remove_edit_variable(&v2) {
+        Err(KiwiError::UnknownEditVariable { variable }) => assert_eq!(variable, v2),
+        _ => panic!("expected an unknown edit variable error"),
+    }
+    solver.remove_edit_variable(&v1).unwrap();
+    assert!(!solver.has_edit_variable(&v1));
+
+    match solver.add_edit_variable(&v1, REQUIRED) {
+        Err(KiwiError::BadRequiredStrength) => (),
+        _ => panic!("expected a bad required strength error"),
+    }
+    assert!(!solver.has_edit_variable(&v1));
+
+    solver.add_edit_variable(&v2, STRONG).unwrap();
+    assert!(solver.has_edit_variable(&v2));
+    match solver.suggest_value(&v1, 10.0) {
+        Err(KiwiError::UnknownEditVariable { variable }) => assert_eq!(variable, v1),
+        _ => panic!("expected an unknown edit variable error"),
+    }
+
+    solver.reset();
+    assert!(!solver.has_edit_variable(&v2));
+}
+
+#[test]
+fn test_suggesting_values_for_edit_variables() {
+    // Suggest value for an edit variable entering a weak equality
+    let mut solver = Solver::new();
+    let v1 = Variable::new_with_name("foo");
+
+    solver.add_edit_variable(&v1, MEDIUM).unwrap();
+    solver
+        .add_constraint(Constraint::new(&v1 - 1.0, RelationalOperator::Equal, WEAK))
+        .unwrap();
+    solver.suggest_value(&v1, 2.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&v1), 2.0);
+
+    // Suggest a value for an edit variable entering multiple solver rows
+    let mut solver = Solver::new();
+    let v1 = Variable::new_with_name("foo");
+    let v2 = Variable::new_with_name("bar");
+
+    solver.add_edit_variable(&v2, WEAK).unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v1 + &v2,
+            RelationalOperator::Equal,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v2 + 1.0,
+            RelationalOperator::LessEqual,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v2 - 0.0,
+            RelationalOperator::GreaterEqual,
+            WEAK,
+        ))
+        .unwrap();
+    solver.suggest_value(&v2, 0.0).unwrap();
+    solver.update_variable();
+    assert!(value(&v2) <= -1.0 + EPS);
+    assert_near(value(&v1), -value(&v2));
+}
+
+#[test]
+fn test_managing_constraints() {
+    let mut solver = Solver::new();
+    let v = Variable::new_with_name("foo");
+    let c1 = Constraint::new(&v - 1.0, RelationalOperator::GreaterEqual, REQUIRED);
+    let c2 = Constraint::new(&v - 0.0, RelationalOperator::LessEqual, REQUIRED);
+
+    assert!(!solver.has_constraint(&c1));
+    solver.add_constraint(c1.clone()).unwrap();
+    assert!(solver.has_constraint(&c1));
+    match solver.add_constraint(c1.clone()) {
+        Err(KiwiError::DuplicateConstraint { constraint }) => assert_eq!(constraint, c1),
+        _ => panic!("expected a duplicate constraint error"),
+    }
+    match solver.remove_constraint(&c2) {
+        Err(KiwiError::UnknownConstraint { constraint }) => assert_eq!(constraint, c2),
+        _ => panic!("expected an unknown constraint error"),
+    }
+    match solver.add_constraint(c2.clone()) {
+        Err(KiwiError::UnsatisfiableConstraint { constraint }) => assert_eq!(constraint, c2),
+        _ => panic!("expected an unsatisfiable constraint error"),
+    }
+    assert!(!solver.has_constraint(&c2));
+
+    solver.remove_constraint(&c1).unwrap();
+    assert!(!solver.has_constraint(&c1));
+    solver.add_constraint(c2.clone()).unwrap();
+    assert!(solver.has_constraint(&c2));
+    solver.reset();
+    assert!(!solver.has_constraint(&c2));
+}
+
+#[test]
+fn test_solving_under_constrained_system() {
+    let mut solver = Solver::new();
+    let v = Variable::new_with_name("foo");
+    let c = Constraint::new(2.0 * &v + 1.0, RelationalOperator::GreaterEqual, REQUIRED);
+    solver.add_edit_variable(&v, WEAK).unwrap();
+    solver.add_constraint(c.clone()).unwrap();
+    solver.suggest_value(&v, 10.0).unwrap();
+    solver.update_variable();
+
+    assert_near(c.expression().value(), 21.0);
+    assert_near(c.expression().terms()[0].value(), 20.0);
+    assert_near(value(c.expression().terms()[0].variable()), 10.0);
+}
+
+#[test]
+fn test_solving_with_strength() {
+    let v1 = Variable::new_with_name("foo");
+    let v2 = Variable::new_with_name("bar");
+
+    let mut solver = Solver::new();
+    solver
+        .add_constraint(Constraint::new(
+            &v1 + &v2,
+            RelationalOperator::Equal,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v1 - 10.0,
+            RelationalOperator::Equal,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v2 - 0.0,
+            RelationalOperator::GreaterEqual,
+            WEAK,
+        ))
+        .unwrap();
+    solver.update_variable();
+    assert_near(value(&v1), 10.0);
+    assert_near(value(&v2), -10.0);
+
+    solver.reset();
+    solver
+        .add_constraint(Constraint::new(
+            &v1 + &v2,
+            RelationalOperator::Equal,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v1 - 10.0,
+            RelationalOperator::GreaterEqual,
+            MEDIUM,
+        ))
+        .unwrap();
+    solver
+        .add_constraint(Constraint::new(
+            &v2 - 2.0,
+            RelationalOperator::Equal,
+            STRONG,
+        ))
+        .unwrap();
+    solver.update_variable();
+    assert_near(value(&v1), -2.0);
+    assert_near(value(&v2), 2.0);
+}
+
+#[test]
+fn test_handling_infeasible_constraints() {
+    let xm = Variable::new_with_name("xm");
+    let xl = Variable::new_with_name("xl");
+    let xr = Variable::new_with_name("xr");
+    let mut solver = Solver::new();
+
+    solver.add_edit_variable(&xm, STRONG).unwrap();
+    solver.add_edit_variable(&xl, WEAK).unwrap();
+    solver.add_edit_variable(&xr, WEAK).unwrap();
+
+    let constraints = [
+        Constraint::new(2.0 * &xm - &xl - &xr, RelationalOperator::Equal, REQUIRED),
+        Constraint::new(&xl + 20.0 - &xr, RelationalOperator::LessEqual, REQUIRED),
+        Constraint::new(&xl + 10.0, RelationalOperator::GreaterEqual, REQUIRED),
+        Constraint::new(&xr - 100.0, RelationalOperator::LessEqual, REQUIRED),
+    ];
+    for constraint in constraints.iter() {
+        solver.add_constraint(constraint.clone()).unwrap();
+    }
+
+    solver.suggest_value(&xm, 40.0).unwrap();
+    solver.suggest_value(&xr, 50.0).unwrap();
+    solver.suggest_value(&xl, 30.0).unwrap();
+
+    // First update causing a normal update.
+    solver.suggest_value(&xm, 60.0).unwrap();
+
+    // Create an infeasible condition triggering a dual optimization
+    solver.suggest_value(&xm, 90.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&xl) + value(&xr), 2.0 * value(&xm));
+    assert_near(value(&xl), 80.0);
+    assert_near(value(&xr), 100.0);
+    for constraint in constraints.iter() {
+        assert!(is_satisfied(constraint));
+    }
+}
+
+#[test]
+fn test_unsatisfiable_required_constraints() {
+    let x = Variable::new_with_name("x");
+    let y = Variable::new_with_name("y");
+    let mut solver = Solver::new();
+
+    let sum = Constraint::new(&x + &y - 10.0, RelationalOperator::Equal, REQUIRED);
+    let x_min = Constraint::new(&x - 6.0, RelationalOperator::GreaterEqual, REQUIRED);
+    let y_min = Constraint::new(&y - 6.0, RelationalOperator::GreaterEqual, REQUIRED);
+    let x_pref = Constraint::new(&x - 8.0, RelationalOperator::Equal, WEAK);
+    for constraint in [sum.clone(), x_min.clone(), x_pref.clone()].iter() {
+        solver.add_constraint(constraint.clone()).unwrap();
+    }
+    solver.update_variable();
+    assert_near(value(&x), 8.0);
+    assert_near(value(&y), 2.0);
+
+    match solver.add_constraint(y_min.clone()) {
+        Err(KiwiError::UnsatisfiableConstraint { constraint }) => assert_eq!(constraint, y_min),
+        _ => panic!("expected an unsatisfiable constraint error"),
+    }
+    assert!(!solver.has_constraint(&y_min));
+
+    // The failed addition leaves the solver in its previous state.
+    solver.update_variable();
+    assert_near(value(&x), 8.0);
+    assert_near(value(&y), 2.0);
+
+    // Once the conflicting constraint is gone, the constraint can be added.
+    solver.remove_constraint(&x_min).unwrap();
+    solver.add_constraint(y_min.clone()).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 4.0);
+    assert_near(value(&y), 6.0);
+}
+
+#[test]
+fn test_unsatisfiable_edit_suggestion() {
+    let x = Variable::new_with_name("x");
+    let mut solver = Solver::new();
+    solver
+        .add_constraint(Constraint::new(
+            &x - 100.0,
+            RelationalOperator::LessEqual,
+            REQUIRED,
+        ))
+        .unwrap();
+    solver.add_edit_variable(&x, STRONG).unwrap();
+
+    // Suggestions conflicting with required constraints are only followed as far as possible.
+    solver.suggest_value(&x, 150.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 100.0);
+
+    solver.suggest_value(&x, 50.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 50.0);
+}
+
+#[test]
+fn test_suggest_with_strengths() {
+    let x = Variable::new_with_name("x");
+    let mut solver = Solver::new();
+    solver
+        .add_constraint(Constraint::new(
+            &x - 10.0,
+            RelationalOperator::Equal,
+            create(0.0, 1.0, 0.0, 2.0),
+        ))
+        .unwrap();
+
+    // A weaker edit variable cannot move the variable.
+    solver.add_edit_variable(&x, MEDIUM).unwrap();
+    solver.suggest_value(&x, 20.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 10.0);
+
+    // A stronger one wins.
+    solver.remove_edit_variable(&x).unwrap();
+    solver.add_edit_variable(&x, STRONG).unwrap();
+    solver.suggest_value(&x, 20.0).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 20.0);
+
+    // Removing the edit variable gives back control to the constraint.
+    solver.remove_edit_variable(&x).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), 10.0);
+}
+
+/// Build the constraints of a row of boxes packed in a container.
+///
+/// Each box has a required positive width, boxes are placed left to right and the widths
+/// prefer 10 (medium) while the container prefers 100 (weak).
+fn packing_constraints(left: &[Variable], width: &[Variable], right: &Variable) -> Vec<Constraint> {
+    let mut constraints = Vec::new();
+    constraints.push(Constraint::new(
+        &left[0] - 0.0,
+        RelationalOperator::Equal,
+        REQUIRED,
+    ));
+    for i in 0..left.len() {
+        let end = if i + 1 < left.len() {
+            &left[i + 1]
+        } else {
+            right
+        };
+        constraints.push(Constraint::new(
+            &left[i] + &width[i] - end,
+            RelationalOperator::Equal,
+            REQUIRED,
+        ));
+        constraints.push(Constraint::new(
+            &width[i] - 0.0,
+            RelationalOperator::GreaterEqual,
+            REQUIRED,
+        ));
+        constraints.push(Constraint::new(
+            &width[i] - 10.0,
+            RelationalOperator::Equal,
+            MEDIUM,
+        ));
+    }
+    constraints.push(Constraint::new(
+        right - 100.0,
+        RelationalOperator::Equal,
+        WEAK,
+    ));
+    constraints
+}
+
+#[test]
+fn test_removing_constraints_in_arbitrary_order() {
+    let count = 5;
+    let left: Vec<Variable> = (0..count)
+        .map(|i| Variable::new_with_name(&format!("left{}", i)))
+        .collect();
+    let width: Vec<Variable> = (0..count)
+        .map(|i| Variable::new_with_name(&format!("width{}", i)))
+        .collect();
+    let right = Variable::new_with_name("right");
+    let constraints = packing_constraints(&left, &width, &right);
+
+    // Deterministic pseudo random permutations of the removal order.
+    let mut seed: u64 = 12345;
+    for _ in 0..20 {
+        let mut order: Vec<usize> = (0..constraints.len()).collect();
+        for i in (1..order.len()).rev() {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            order.swap(i, (seed >> 33) as usize % (i + 1));
+        }
+
+        let mut solver = Solver::new();
+        for constraint in constraints.iter() {
+            solver.add_constraint(constraint.clone()).unwrap();
+        }
+        solver.update_variable();
+        assert_near(value(&right), 50.0);
+
+        for (removed, &index) in order.iter().enumerate() {
+            solver.remove_constraint(&constraints[index]).unwrap();
+            assert!(!solver.has_constraint(&constraints[index]));
+            solver.update_variable();
+
+            // The remaining required constraints must still hold.
+            for &remaining in order[removed + 1..].iter() {
+                let constraint = &constraints[remaining];
+                assert!(solver.has_constraint(constraint));
+                if constraint.strength() == REQUIRED {
+                    assert!(is_satisfied(constraint));
+                }
+            }
+        }
+
+        // Removing every constraint brings the solver back to an empty state.
+        solver.update_variable();
+        for variable in left.iter().chain(width.iter()) {
+            assert_near(value(variable), 0.0);
+        }
+        for constraint in constraints.iter() {
+            match solver.remove_constraint(constraint) {
+                Err(KiwiError::UnknownConstraint { .. }) => (),
+                _ => panic!("expected an unknown constraint error"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_removing_constraints_restores_solution() {
+    let count = 4;
+    let left: Vec<Variable> = (0..count).map(|_| Variable::new()).collect();
+    let width: Vec<Variable> = (0..count).map(|_| Variable::new()).collect();
+    let right = Variable::new();
+    let constraints = packing_constraints(&left, &width, &right);
+
+    let mut solver = Solver::new();
+    for constraint in constraints.iter() {
+        solver.add_constraint(constraint.clone()).unwrap();
+    }
+    solver.update_variable();
+    let expected: Vec<f64> = width.iter().map(value).collect();
+
+    // Adding and removing an extra constraint gives back the same solution.
+    let extra = Constraint::new(&width[0] - 30.0, RelationalOperator::Equal, STRONG);
+    solver.add_constraint(extra.clone()).unwrap();
+    solver.update_variable();
+    assert_near(value(&width[0]), 30.0);
+    solver.remove_constraint(&extra).unwrap();
+    solver.update_variable();
+    for (variable, expected) in width.iter().zip(expected) {
+        assert_near(value(variable), expected);
+    }
+}
+
+#[test]
+fn test_duplicate_errors() {
+    let x = Variable::new_with_name("x");
+    let mut solver = Solver::new();
+    let c = Constraint::new(&x - 1.0, RelationalOperator::Equal, STRONG);
+    solver.add_constraint(c.clone()).unwrap();
+
+    // A constraint with the same expression is a different constraint.
+    let same = Constraint::new(&x - 1.0, RelationalOperator::Equal, STRONG);
+    solver.add_constraint(same.clone()).unwrap();
+    match solver.add_constraint(same) {
+        Err(KiwiError::DuplicateConstraint { .. }) => (),
+        _ => panic!("expected a duplicate constraint error"),
+    }
+
+    // Changing the strength creates a new constraint.
+    let weak = Constraint::from(&c, WEAK);
+    solver.add_constraint(weak).unwrap();
+    match solver.add_constraint(c.clone()) {
+        Err(error @ KiwiError::DuplicateConstraint { .. }) => {
+            assert!(error.to_string().contains("already been added"))
+        }
+        _ => panic!("expected a duplicate constraint error"),
+    }
+
+    solver.add_edit_variable(&x, MEDIUM).unwrap();
+    match solver.add_edit_variable(&x, WEAK) {
+        Err(error @ KiwiError::DuplicateEditVariable { .. }) => {
+            assert!(error.to_string().contains("x"))
+        }
+        _ => panic!("expected a duplicate edit variable error"),
+    }
+    solver.update_variable();
+    assert_near(value(&x), 1.0);
+}
+
+#[test]
+fn test_removing_redundant_required_constraint() {
+    // The second equality is redundant and its marker becomes a basic dummy.
+    // Removing the first one must not pivot through the row of the weaker
+    // constraint, which would leave the second equality unsatisfied.
+    let x = Variable::new_with_name("x");
+    let mut solver = Solver::new();
+    let weak = Constraint::new(&x + 10.0, RelationalOperator::LessEqual, MEDIUM);
+    let first = Constraint::new(&x + 5.0, RelationalOperator::Equal, REQUIRED);
+    let second = Constraint::new(&x + 5.0, RelationalOperator::Equal, REQUIRED);
+    solver.add_constraint(weak).unwrap();
+    solver.add_constraint(first.clone()).unwrap();
+    solver.add_constraint(second.clone()).unwrap();
+    solver.update_variable();
+    assert_near(value(&x), -5.0);
+
+    solver.remove_constraint(&first).unwrap();
+    solver.update_variable();
+    assert!(is_satisfied(&second));
+    assert_near(value(&x), -5.0);
+}